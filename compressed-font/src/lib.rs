@@ -0,0 +1,246 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod style;
+
+use embedded_graphics::{
+    geometry::Size,
+    image::ImageRaw,
+    mono_font::{mapping::GlyphMapping, DecorationDimensions},
+    pixelcolor::BinaryColor,
+};
+
+pub use style::CompressedTextStyle;
+
+/// Largest width, and height of a glyph cell that can be decoded.
+pub const MAX_GLYPH_SIZE: u32 = 64;
+
+/// Size of the buffer a single glyph is decoded into.
+pub const GLYPH_BUFFER_SIZE: usize = (MAX_GLYPH_SIZE * MAX_GLYPH_SIZE / 8) as usize;
+
+/// A monospaced font, where each glyph is compressed on its own.
+///
+/// The data starts with a table of little-endian `u32` offsets, one for each
+/// glyph, followed by the offset of the end of the data. Glyph `i` is stored
+/// in `data[offsets[i]..offsets[i + 1]]`.
+///
+/// Each glyph starts with the left, top, width, and height of its bounding
+/// box within the cell, one byte each. Everything outside of the bounding
+/// box is blank. The bounding box is followed by a bit for each row: rows
+/// where the bit is set repeat the previous row, otherwise the row's pixels
+/// follow, one bit each. Bits are packed with the first one in the most
+/// significant bit of each byte.
+///
+/// Glyphs are decoded while drawing, so the cell can't be larger than
+/// [`MAX_GLYPH_SIZE`] in either direction.
+pub struct CompressedFont<'a> {
+    /// Offset table, and compressed glyphs.
+    pub data: &'a [u8],
+
+    /// Glyph mapping.
+    pub glyph_mapping: &'a dyn GlyphMapping,
+
+    /// Size of a single character in pixel.
+    pub character_size: Size,
+
+    /// Spacing between characters.
+    pub character_spacing: u32,
+
+    /// The baseline, as an offset from the top of a glyph.
+    pub baseline: u32,
+
+    /// Offset from top of a glyph to the top of the underline, and its
+    /// height.
+    pub underline: DecorationDimensions,
+
+    /// Offset from top of a glyph to the top of the strikethrough, and its
+    /// height.
+    pub strikethrough: DecorationDimensions,
+}
+
+impl CompressedFont<'_> {
+    /// Reads an entry from the offset table.
+    fn offset(&self, index: usize) -> Option<usize> {
+        let bytes = self.data.get(index * 4..index * 4 + 4)?;
+
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    /// Returns the compressed data for a glyph.
+    fn glyph_data(&self, index: usize) -> Option<&[u8]> {
+        let num_offsets = self.offset(0)? / 4;
+
+        if index + 1 >= num_offsets {
+            return None;
+        }
+
+        self.data.get(self.offset(index)?..self.offset(index + 1)?)
+    }
+
+    /// Decodes the glyph for a character into `buffer`, and returns it as an
+    /// image of the whole cell, in the same format as the image of a
+    /// `MonoFont`. Returns `None` if the cell is larger than
+    /// [`MAX_GLYPH_SIZE`], or the glyph's data is invalid.
+    pub fn decode_glyph<'b>(
+        &self,
+        chr: char,
+        buffer: &'b mut [u8; GLYPH_BUFFER_SIZE],
+    ) -> Option<ImageRaw<'b, BinaryColor>> {
+        let Size { width, height } = self.character_size;
+
+        if width > MAX_GLYPH_SIZE || height > MAX_GLYPH_SIZE {
+            return None;
+        }
+
+        let bytes_per_row = (width as usize).div_ceil(8);
+        let cell = &mut buffer[..bytes_per_row * height as usize];
+        let glyph = self.glyph_data(self.glyph_mapping.index(chr))?;
+        let (bounds, bits) = glyph.split_at_checked(4)?;
+        let [left, top, bounds_width, bounds_height] =
+            [bounds[0], bounds[1], bounds[2], bounds[3]].map(usize::from);
+
+        if left + bounds_width > width as usize || top + bounds_height > height as usize {
+            return None;
+        }
+
+        let mut reader = BitReader { data: bits, bit: 0 };
+
+        cell.fill(0);
+
+        for y in top..top + bounds_height {
+            let repeat = reader.next()?;
+
+            for x in left..left + bounds_width {
+                let set = if repeat {
+                    y > top && cell[(y - 1) * bytes_per_row + x / 8] & (0x80 >> (x % 8)) != 0
+                } else {
+                    reader.next()?
+                };
+
+                if set {
+                    cell[y * bytes_per_row + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+
+        Some(ImageRaw::new(cell, width))
+    }
+}
+
+/// Reads bits, starting with the most significant bit of each byte.
+struct BitReader<'a> {
+    data: &'a [u8],
+    bit: usize,
+}
+
+impl Iterator for BitReader<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        let byte = self.data.get(self.bit / 8)?;
+        let set = byte & (0x80 >> (self.bit % 8)) != 0;
+
+        self.bit += 1;
+
+        Some(set)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{CompressedFont, GLYPH_BUFFER_SIZE};
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        image::Image,
+        mock_display::MockDisplay,
+        mono_font::{mapping::StrGlyphMapping, DecorationDimensions},
+        Drawable,
+    };
+
+    /// A font with 4x3 cells for `a`, and `b`. `a` has a 2x3 bounding box,
+    /// where the second row repeats the first, and `b` is blank.
+    pub(crate) const FONT: CompressedFont = CompressedFont {
+        data: &[
+            12,
+            0,
+            0,
+            0,
+            17,
+            0,
+            0,
+            0,
+            21,
+            0,
+            0,
+            0, // Offsets
+            1,
+            0,
+            2,
+            3,
+            0b0111_0010, // a: `0 11`, `1`, `0 01`
+            0,
+            0,
+            0,
+            0, // b
+        ],
+        glyph_mapping: &StrGlyphMapping::new("ab", 0),
+        character_size: Size::new(4, 3),
+        character_spacing: 1,
+        baseline: 1,
+        underline: DecorationDimensions::new(2, 1),
+        strikethrough: DecorationDimensions::new(1, 1),
+    };
+
+    fn assert_glyph(chr: char, pattern: &[&str]) {
+        // Leftover data in the buffer must not show up in the glyph
+        let mut buffer = [0xFF; GLYPH_BUFFER_SIZE];
+        let image = FONT.decode_glyph(chr, &mut buffer).unwrap();
+        let mut display = MockDisplay::new();
+
+        Image::new(&image, Point::zero())
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn test_decode_glyph() {
+        assert_glyph('a', &[".##.", ".##.", "..#."]);
+        assert_glyph('b', &["....", "....", "...."]);
+    }
+
+    #[test]
+    fn test_invalid_glyph() {
+        let mut buffer = [0; GLYPH_BUFFER_SIZE];
+        let font = CompressedFont {
+            // The bounding box is wider than the cell
+            data: &[8, 0, 0, 0, 13, 0, 0, 0, 1, 0, 4, 1, 0],
+            ..FONT
+        };
+
+        assert!(font.decode_glyph('a', &mut buffer).is_none());
+
+        let font = CompressedFont {
+            character_size: Size::new(65, 3),
+            ..FONT
+        };
+
+        assert!(font.decode_glyph('a', &mut buffer).is_none());
+    }
+}
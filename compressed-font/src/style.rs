@@ -0,0 +1,368 @@
+use crate::{CompressedFont, GLYPH_BUFFER_SIZE};
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    image::ImageRaw,
+    mono_font::{
+        mapping::GlyphMapping, DecorationDimensions, MonoFont, MonoTextStyle, MonoTextStyleBuilder,
+    },
+    pixelcolor::{BinaryColor, PixelColor},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+
+/// Maps every character to the only glyph in a decoded cell.
+struct SingleGlyph;
+
+impl GlyphMapping for SingleGlyph {
+    fn index(&self, _chr: char) -> usize {
+        0
+    }
+}
+
+/// Style for text drawn with a [`CompressedFont`].
+///
+/// Each glyph is decoded into a buffer on the stack, and drawn the same way
+/// as a `MonoTextStyle` would draw it, so text looks exactly the same as it
+/// would with the uncompressed `MonoFont`.
+#[derive(Copy, Clone)]
+pub struct CompressedTextStyle<'a, C> {
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Underline color.
+    pub underline_color: DecorationColor<C>,
+
+    /// Strikethrough color.
+    pub strikethrough_color: DecorationColor<C>,
+
+    /// Font.
+    pub font: &'a CompressedFont<'a>,
+}
+
+impl<'a, C> CompressedTextStyle<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a text style with a transparent background.
+    pub const fn new(font: &'a CompressedFont<'a>, text_color: C) -> Self {
+        CompressedTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            underline_color: DecorationColor::None,
+            strikethrough_color: DecorationColor::None,
+            font,
+        }
+    }
+
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => self.font.character_size.height.saturating_sub(1) as i32,
+            Baseline::Middle => (self.font.character_size.height.saturating_sub(1) / 2) as i32,
+            Baseline::Alphabetic => self.font.baseline as i32,
+        }
+    }
+
+    /// Returns a style that draws a single decoded glyph. Decorations are
+    /// drawn separately, across the whole string.
+    fn glyph_style<'b>(&self, font: &'b MonoFont<'b>) -> MonoTextStyle<'b, C> {
+        let mut style = MonoTextStyleBuilder::new().font(font).build();
+
+        style.set_text_color(self.text_color);
+        style.set_background_color(self.background_color);
+
+        style
+    }
+
+    /// Draws the glyph for a character, with its top left corner at
+    /// `position`.
+    fn draw_glyph<D>(&self, chr: char, position: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let mut buffer = [0; GLYPH_BUFFER_SIZE];
+        let image = self
+            .font
+            .decode_glyph(chr, &mut buffer)
+            .unwrap_or(ImageRaw::<BinaryColor>::new(&[], 1));
+        let font = MonoFont {
+            image,
+            glyph_mapping: &SingleGlyph,
+            character_size: self.font.character_size,
+            character_spacing: 0,
+            baseline: self.font.baseline,
+            underline: self.font.underline,
+            strikethrough: self.font.strikethrough,
+        };
+
+        self.glyph_style(&font).draw_string(
+            chr.encode_utf8(&mut [0; 4]),
+            position,
+            Baseline::Top,
+            target,
+        )?;
+
+        Ok(())
+    }
+
+    fn draw_decoration<D>(
+        &self,
+        decoration: &DecorationDimensions,
+        color: DecorationColor<C>,
+        width: u32,
+        position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let color = match color {
+            DecorationColor::None => None,
+            DecorationColor::TextColor => self.text_color,
+            DecorationColor::Custom(color) => Some(color),
+        };
+
+        match color {
+            Some(color) => target.fill_solid(
+                &Rectangle::new(
+                    position + Point::new(0, decoration.offset as i32),
+                    Size::new(width, decoration.height),
+                ),
+                color,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn draw_decorations<D>(
+        &self,
+        width: u32,
+        position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_decoration(
+            &self.font.strikethrough,
+            self.strikethrough_color,
+            width,
+            position,
+            target,
+        )?;
+        self.draw_decoration(
+            &self.font.underline,
+            self.underline_color,
+            width,
+            position,
+            target,
+        )
+    }
+}
+
+impl<C> TextRenderer for CompressedTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let position = position - Point::new(0, self.baseline_offset(baseline));
+        let Size { width, height } = self.font.character_size;
+        let spacing = self.font.character_spacing;
+        let mut next = position;
+
+        for (i, chr) in text.chars().enumerate() {
+            if i > 0 {
+                if let (Some(background_color), true) = (self.background_color, spacing > 0) {
+                    target.fill_solid(
+                        &Rectangle::new(next, Size::new(spacing, height)),
+                        background_color,
+                    )?;
+                }
+
+                next += Point::new(spacing as i32, 0);
+            }
+
+            if self.text_color.is_some() || self.background_color.is_some() {
+                self.draw_glyph(chr, next, target)?;
+            }
+
+            next += Point::new(width as i32, 0);
+        }
+
+        if next.x > position.x {
+            self.draw_decorations((next.x - position.x) as u32, position, target)?;
+        }
+
+        Ok(next + Point::new(0, self.baseline_offset(baseline)))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let position = position - Point::new(0, self.baseline_offset(baseline));
+
+        if width != 0 {
+            if let Some(background_color) = self.background_color {
+                target.fill_solid(
+                    &Rectangle::new(position, Size::new(width, self.font.character_size.height)),
+                    background_color,
+                )?;
+            }
+
+            self.draw_decorations(width, position, target)?;
+        }
+
+        Ok(position + Point::new(width as i32, self.baseline_offset(baseline)))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let bb_position = position - Point::new(0, self.baseline_offset(baseline));
+        let bb_width = (text.chars().count() as u32
+            * (self.font.character_size.width + self.font.character_spacing))
+            .saturating_sub(self.font.character_spacing);
+        let bb_height = if self.underline_color != DecorationColor::None {
+            self.font.underline.height + self.font.underline.offset
+        } else {
+            self.font.character_size.height
+        };
+        let bb_size = Size::new(bb_width, bb_height);
+
+        TextMetrics {
+            bounding_box: Rectangle::new(bb_position, bb_size),
+            next_position: position + bb_size.x_axis(),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.character_size.height
+    }
+}
+
+impl<C> CharacterStyle for CompressedTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.underline_color = underline_color;
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.strikethrough_color = strikethrough_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompressedTextStyle;
+    use crate::tests::FONT;
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{mapping::StrGlyphMapping, MonoFont, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        text::{renderer::CharacterStyle, Baseline, DecorationColor, Text},
+        Drawable,
+    };
+    use embedded_graphics_cjk_glyph_mapping::binary_image;
+
+    /// The same font as `FONT`, without compression.
+    const MONO_FONT: MonoFont = MonoFont {
+        image: binary_image(&[0b0110_0000, 0b0110_0000, 0b0010_0000], 8),
+        glyph_mapping: &StrGlyphMapping::new("ab", 0),
+        character_size: Size::new(4, 3),
+        character_spacing: FONT.character_spacing,
+        baseline: FONT.baseline,
+        underline: FONT.underline,
+        strikethrough: FONT.strikethrough,
+    };
+
+    /// Text, background, underline, and strikethrough colors.
+    type Colors = (
+        Option<BinaryColor>,
+        Option<BinaryColor>,
+        DecorationColor<BinaryColor>,
+        DecorationColor<BinaryColor>,
+    );
+
+    fn set_colors<S>(style: &mut S, colors: Colors)
+    where
+        S: CharacterStyle<Color = BinaryColor>,
+    {
+        style.set_text_color(colors.0);
+        style.set_background_color(colors.1);
+        style.set_underline_color(colors.2);
+        style.set_strikethrough_color(colors.3);
+    }
+
+    /// Draws text with both the compressed, and uncompressed font, and
+    /// checks that the output is the same.
+    fn assert_matches_mono_font(text: &str, colors: Colors) {
+        let mut style = CompressedTextStyle::new(&FONT, BinaryColor::On);
+        let mut mono_style = MonoTextStyle::new(&MONO_FONT, BinaryColor::On);
+
+        set_colors(&mut style, colors);
+        set_colors(&mut mono_style, colors);
+
+        let mut display = MockDisplay::new();
+        let mut mono_display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        mono_display.set_allow_overdraw(true);
+
+        let next = Text::with_baseline(text, Point::new(1, 2), style, Baseline::Alphabetic)
+            .draw(&mut display)
+            .unwrap();
+        let mono_next =
+            Text::with_baseline(text, Point::new(1, 2), mono_style, Baseline::Alphabetic)
+                .draw(&mut mono_display)
+                .unwrap();
+
+        assert_eq!(next, mono_next);
+        display.assert_eq(&mono_display);
+    }
+
+    #[test]
+    fn test_matches_mono_font() {
+        use BinaryColor::{Off, On};
+        use DecorationColor::{Custom, None as NoDecoration, TextColor};
+
+        assert_matches_mono_font("abba", (Some(On), None, NoDecoration, NoDecoration));
+        assert_matches_mono_font("ab\nba", (Some(On), Some(Off), NoDecoration, NoDecoration));
+        assert_matches_mono_font("aba", (None, Some(On), NoDecoration, NoDecoration));
+        assert_matches_mono_font("a b", (Some(On), None, TextColor, Custom(Off)));
+    }
+}
@@ -0,0 +1,36 @@
+use crate::table::DoubleByteTable;
+
+/// Characters of code page 950, from `CP950.TXT`. The lead bytes before
+/// `A1`, and after `F9` are for user-defined characters, so they aren't in
+/// the table.
+const TABLE: DoubleByteTable = DoubleByteTable {
+    data: include_bytes!("data/big5.bin"),
+    lead_bytes: &[0xA1..=0xF9],
+    trail_bytes: &[0x40..=0x7E, 0xA1..=0xFE],
+};
+
+/// Decodes the Big5 character at the start of `bytes`, which can't be
+/// empty, and returns it, and the number of bytes that were read.
+pub fn decode(bytes: &[u8]) -> (Option<char>, usize) {
+    match bytes[0] {
+        byte @ 0x00..=0x7F => (Some(byte.into()), 1),
+        lead @ 0x81..=0xFE => TABLE.decode(lead, &bytes[1..]),
+        0x80 | 0xFF => (None, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(b"A"), (Some('A'), 1));
+        assert_eq!(decode(&[0xA4, 0xA4]), (Some('中'), 2));
+        assert_eq!(decode(&[0xC5, 0xE9]), (Some('體'), 2));
+        assert_eq!(decode(&[0xA3, 0xE1]), (Some('€'), 2));
+        // A user-defined character is read as a whole
+        assert_eq!(decode(&[0x81, 0xA1]), (None, 2));
+        assert_eq!(decode(&[0x80, 0xA1]), (None, 1));
+    }
+}
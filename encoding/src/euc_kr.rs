@@ -0,0 +1,35 @@
+use crate::table::DoubleByteTable;
+
+/// Characters of code page 949, from `CP949.TXT`, which has every Hangul
+/// syllable, and not just the ones in KS X 1001.
+const TABLE: DoubleByteTable = DoubleByteTable {
+    data: include_bytes!("data/euc_kr.bin"),
+    lead_bytes: &[0x81..=0xFE],
+    trail_bytes: &[0x41..=0x5A, 0x61..=0x7A, 0x81..=0xFE],
+};
+
+/// Decodes the EUC-KR character at the start of `bytes`, which can't be
+/// empty, and returns it, and the number of bytes that were read.
+pub fn decode(bytes: &[u8]) -> (Option<char>, usize) {
+    match bytes[0] {
+        byte @ 0x00..=0x7F => (Some(byte.into()), 1),
+        lead @ 0x81..=0xFE => TABLE.decode(lead, &bytes[1..]),
+        0x80 | 0xFF => (None, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(b"A"), (Some('A'), 1));
+        assert_eq!(decode(&[0xC7, 0xD1]), (Some('한'), 2));
+        assert_eq!(decode(&[0xF1, 0xE9]), (Some('中'), 2));
+        // A syllable that's only in code page 949
+        assert_eq!(decode(&[0x8C, 0x63]), (Some('똠'), 2));
+        assert_eq!(decode(&[0xC7, 0x20]), (None, 1));
+        assert_eq!(decode(&[0xFF]), (None, 1));
+    }
+}
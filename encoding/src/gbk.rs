@@ -0,0 +1,37 @@
+use crate::table::DoubleByteTable;
+
+/// Characters of code page 936, from `CP936.TXT`.
+const TABLE: DoubleByteTable = DoubleByteTable {
+    data: include_bytes!("data/gbk.bin"),
+    lead_bytes: &[0x81..=0xFE],
+    trail_bytes: &[0x40..=0x7E, 0x80..=0xFE],
+};
+
+/// Decodes the GBK character at the start of `bytes`, which can't be
+/// empty, and returns it, and the number of bytes that were read.
+pub fn decode(bytes: &[u8]) -> (Option<char>, usize) {
+    match bytes[0] {
+        byte @ 0x00..=0x7F => (Some(byte.into()), 1),
+        0x80 => (Some('€'), 1),
+        lead @ 0x81..=0xFE => TABLE.decode(lead, &bytes[1..]),
+        0xFF => (None, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(b"A"), (Some('A'), 1));
+        assert_eq!(decode(&[0x80]), (Some('€'), 1));
+        assert_eq!(decode(&[0xD6, 0xD0]), (Some('中'), 2));
+        // GB2312 punctuation, and a GBK extension
+        assert_eq!(decode(&[0xA3, 0xAC]), (Some('，'), 2));
+        assert_eq!(decode(&[0x81, 0x40]), (Some('丂'), 2));
+        assert_eq!(decode(&[0xFF, 0x40]), (None, 1));
+        assert_eq!(decode(&[0xA1, 0x41]), (None, 1));
+        assert_eq!(decode(&[0xD6]), (None, 1));
+    }
+}
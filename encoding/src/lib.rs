@@ -0,0 +1,221 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(not(any(
+    feature = "big5",
+    feature = "euc-kr",
+    feature = "gbk",
+    feature = "shift-jis"
+)))]
+compile_error!(
+    "At least one of the `big5`, `euc-kr`, `gbk`, or `shift-jis` features has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+#[cfg(feature = "big5")]
+mod big5;
+#[cfg(feature = "euc-kr")]
+mod euc_kr;
+#[cfg(feature = "gbk")]
+mod gbk;
+#[cfg(feature = "shift-jis")]
+mod shift_jis;
+mod table;
+mod text;
+
+pub use text::EncodedText;
+
+use core::iter::FusedIterator;
+
+/// A legacy CJK encoding.
+///
+/// Each encoding is decoded with a table of its double-byte characters, that
+/// is stored in flash. The tables take 22 to 48 KiB each, so every encoding
+/// is behind a cargo feature of the same name (`gbk`, `big5`, `shift-jis`,
+/// and `euc-kr`), and only the ones that are used should be enabled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// GBK, as code page 936, which is a superset of GB2312 (EUC-CN).
+    #[cfg(feature = "gbk")]
+    Gbk,
+
+    /// Big5, as code page 950.
+    #[cfg(feature = "big5")]
+    Big5,
+
+    /// Shift_JIS, as code page 932, with the NEC, and IBM extensions.
+    #[cfg(feature = "shift-jis")]
+    ShiftJis,
+
+    /// EUC-KR, as code page 949 (Unified Hangul Code), which is a superset
+    /// of EUC-KR with every Hangul syllable.
+    #[cfg(feature = "euc-kr")]
+    EucKr,
+}
+
+impl Encoding {
+    /// Returns an iterator over the characters of encoded text.
+    ///
+    /// Invalid, or unmapped byte sequences are decoded as
+    /// [`char::REPLACEMENT_CHARACTER`] (`�`).
+    ///
+    /// ```
+    /// # #[cfg(feature = "gbk")]
+    /// # fn main() {
+    /// use embedded_graphics_cjk_encoding::Encoding;
+    ///
+    /// let text = [0xC4, 0xE3, 0xBA, 0xC3, 0xA3, 0xA1];
+    ///
+    /// assert!(Encoding::Gbk.decode(&text).eq("你好！".chars()));
+    /// # }
+    /// # #[cfg(not(feature = "gbk"))]
+    /// # fn main() {}
+    /// ```
+    pub fn decode(self, bytes: &[u8]) -> Chars<'_> {
+        Chars {
+            encoding: self,
+            bytes,
+        }
+    }
+
+    /// Decodes the character at the start of `bytes`, which can't be empty,
+    /// and returns it, or `None` if it's invalid, and the number of bytes
+    /// that were read.
+    fn decode_char(self, bytes: &[u8]) -> (Option<char>, usize) {
+        match self {
+            #[cfg(feature = "gbk")]
+            Encoding::Gbk => gbk::decode(bytes),
+            #[cfg(feature = "big5")]
+            Encoding::Big5 => big5::decode(bytes),
+            #[cfg(feature = "shift-jis")]
+            Encoding::ShiftJis => shift_jis::decode(bytes),
+            #[cfg(feature = "euc-kr")]
+            Encoding::EucKr => euc_kr::decode(bytes),
+        }
+    }
+}
+
+/// Iterator over the characters of encoded text. See [`Encoding::decode`].
+#[derive(Clone, Debug)]
+pub struct Chars<'a> {
+    encoding: Encoding,
+    bytes: &'a [u8],
+}
+
+impl<'a> Chars<'a> {
+    /// Returns the bytes that haven't been decoded yet.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let (chr, len) = self.encoding.decode_char(self.bytes);
+
+        self.bytes = &self.bytes[len..];
+
+        Some(chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bytes.len();
+
+        (len.div_ceil(2), Some(len))
+    }
+}
+
+impl FusedIterator for Chars<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+
+    fn assert_decodes(encoding: Encoding, bytes: &[u8], text: &str) {
+        let mut chars = encoding.decode(bytes);
+
+        for expected in text.chars() {
+            assert_eq!(chars.next(), Some(expected), "{:X?}", chars.as_bytes());
+        }
+
+        assert_eq!(chars.next(), None);
+        assert!(chars.as_bytes().is_empty());
+    }
+
+    #[cfg(feature = "gbk")]
+    #[test]
+    fn test_gbk() {
+        assert_decodes(
+            Encoding::Gbk,
+            &[0xD6, 0xD0, 0xCE, 0xC4, 0xA3, 0xAC, b'G', b'B'],
+            "中文，GB",
+        );
+    }
+
+    #[cfg(feature = "big5")]
+    #[test]
+    fn test_big5() {
+        assert_decodes(
+            Encoding::Big5,
+            &[0xA4, 0xA4, 0xA4, 0xE5, 0xC1, 0x63, 0xC5, 0xE9],
+            "中文繁體",
+        );
+    }
+
+    #[cfg(feature = "shift-jis")]
+    #[test]
+    fn test_shift_jis() {
+        assert_decodes(
+            Encoding::ShiftJis,
+            &[0x93, 0xFA, 0x96, 0x7B, 0x8C, 0xEA, 0xB6, 0xC0, 0xB6, 0xC5],
+            "日本語ｶﾀｶﾅ",
+        );
+    }
+
+    #[cfg(feature = "euc-kr")]
+    #[test]
+    fn test_euc_kr() {
+        assert_decodes(
+            Encoding::EucKr,
+            &[0xC7, 0xD1, 0xB1, 0xB9, 0xBE, 0xEE, b' ', 0xF1, 0xE9],
+            "한국어 中",
+        );
+    }
+
+    #[cfg(feature = "gbk")]
+    #[test]
+    fn test_invalid() {
+        // A byte that can't start a character, an unmapped ASCII trail
+        // byte, and a truncated character
+        let text = [0xD6, 0xD0, 0xFF, 0xA1, b'A', 0xD6];
+        let expected = [
+            '中',
+            char::REPLACEMENT_CHARACTER,
+            char::REPLACEMENT_CHARACTER,
+            'A',
+            char::REPLACEMENT_CHARACTER,
+        ];
+
+        assert!(Encoding::Gbk.decode(&text).eq(expected));
+    }
+}
@@ -0,0 +1,39 @@
+use crate::table::DoubleByteTable;
+
+/// Characters of code page 932, from `CP932.TXT`.
+const TABLE: DoubleByteTable = DoubleByteTable {
+    data: include_bytes!("data/shift_jis.bin"),
+    lead_bytes: &[0x81..=0x9F, 0xE0..=0xFC],
+    trail_bytes: &[0x40..=0x7E, 0x80..=0xFC],
+};
+
+/// Decodes the Shift_JIS character at the start of `bytes`, which can't be
+/// empty, and returns it, and the number of bytes that were read.
+pub fn decode(bytes: &[u8]) -> (Option<char>, usize) {
+    match bytes[0] {
+        byte @ 0x00..=0x7F => (Some(byte.into()), 1),
+        // Half-width katakana
+        byte @ 0xA1..=0xDF => (char::from_u32(0xFF61 + (byte - 0xA1) as u32), 1),
+        lead @ (0x81..=0x9F | 0xE0..=0xFC) => TABLE.decode(lead, &bytes[1..]),
+        0x80 | 0xA0 | 0xFD..=0xFF => (None, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+
+    #[test]
+    fn test_decode() {
+        // Code page 932 decodes `5C` as a backslash, not a yen sign
+        assert_eq!(decode(b"\\"), (Some('\\'), 1));
+        assert_eq!(decode(&[0xB6]), (Some('ｶ'), 1));
+        assert_eq!(decode(&[0xDF]), (Some('ﾟ'), 1));
+        assert_eq!(decode(&[0x93, 0xFA]), (Some('日'), 2));
+        assert_eq!(decode(&[0x82, 0xA0]), (Some('あ'), 2));
+        assert_eq!(decode(&[0x87, 0x40]), (Some('①'), 2));
+        assert_eq!(decode(&[0xFA, 0x40]), (Some('ⅰ'), 2));
+        assert_eq!(decode(&[0xA0]), (None, 1));
+        assert_eq!(decode(&[0x93, 0x7F]), (None, 1));
+    }
+}
@@ -0,0 +1,101 @@
+use core::ops::RangeInclusive;
+
+/// Characters of a double-byte code page, generated by `ttf2bits code-page`.
+///
+/// The data has a little-endian `u16` codepoint for every combination of a
+/// lead byte, and a trail byte in their ranges, ordered by lead byte, and
+/// then by trail byte. Unmapped combinations are 0.
+pub struct DoubleByteTable {
+    pub data: &'static [u8],
+    pub lead_bytes: &'static [RangeInclusive<u8>],
+    pub trail_bytes: &'static [RangeInclusive<u8>],
+}
+
+impl DoubleByteTable {
+    /// Returns the character for a lead byte, and a trail byte, or `None` if
+    /// they aren't mapped to one.
+    pub fn get(&self, lead: u8, trail: u8) -> Option<char> {
+        let lead = byte_position(self.lead_bytes, lead)?;
+        let trail = byte_position(self.trail_bytes, trail)?;
+        let index = (lead * byte_count(self.trail_bytes) + trail) * 2;
+        let codepoint = u16::from_le_bytes([*self.data.get(index)?, *self.data.get(index + 1)?]);
+
+        match codepoint {
+            0 => None,
+            codepoint => char::from_u32(codepoint.into()),
+        }
+    }
+
+    /// Decodes the character that starts with a lead byte, and returns it,
+    /// and the number of bytes that were read.
+    ///
+    /// A lead byte that isn't followed by a character only reads the lead
+    /// byte if the next byte is ASCII, so that it is decoded on its own.
+    pub fn decode(&self, lead: u8, rest: &[u8]) -> (Option<char>, usize) {
+        match rest.first() {
+            Some(&trail) => match self.get(lead, trail) {
+                Some(chr) => (Some(chr), 2),
+                None if trail.is_ascii() => (None, 1),
+                None => (None, 2),
+            },
+            None => (None, 1),
+        }
+    }
+}
+
+/// Number of bytes in the ranges.
+fn byte_count(ranges: &[RangeInclusive<u8>]) -> usize {
+    ranges
+        .iter()
+        .map(|range| (range.end() - range.start()) as usize + 1)
+        .sum()
+}
+
+/// Position of a byte in the ranges.
+fn byte_position(ranges: &[RangeInclusive<u8>], byte: u8) -> Option<usize> {
+    let mut offset = 0;
+
+    for range in ranges {
+        if range.contains(&byte) {
+            return Some(offset + (byte - range.start()) as usize);
+        }
+
+        offset += (range.end() - range.start()) as usize + 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DoubleByteTable;
+
+    /// A table with lead bytes `81-82`, and trail bytes `40-41`, and `A1`.
+    const TABLE: DoubleByteTable = DoubleByteTable {
+        data: &[
+            0x00, 0x4E, 0x00, 0x00, 0x2D, 0x4E, 0x00, 0x00, 0x00, 0x00, 0x87, 0x65,
+        ],
+        lead_bytes: &[0x81..=0x82],
+        trail_bytes: &[0x40..=0x41, 0xA1..=0xA1],
+    };
+
+    #[test]
+    fn test_get() {
+        assert_eq!(TABLE.get(0x81, 0x40), Some('一'));
+        assert_eq!(TABLE.get(0x81, 0xA1), Some('中'));
+        assert_eq!(TABLE.get(0x82, 0xA1), Some('文'));
+        assert_eq!(TABLE.get(0x81, 0x41), None);
+        assert_eq!(TABLE.get(0x83, 0x40), None);
+        assert_eq!(TABLE.get(0x81, 0x42), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(TABLE.decode(0x81, &[0xA1, 0x41]), (Some('中'), 2));
+        assert_eq!(TABLE.decode(0x81, &[]), (None, 1));
+        // The unmapped ASCII trail byte is decoded on its own
+        assert_eq!(TABLE.decode(0x81, &[0x41]), (None, 1));
+        assert_eq!(TABLE.decode(0x82, &[0x40]), (None, 1));
+        assert_eq!(TABLE.decode(0x81, &[0xA2]), (None, 2));
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{Chars, Encoding};
+use core::{convert::Infallible, marker::PhantomData, str};
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point},
+    pixelcolor::PixelColor,
+    primitives::Rectangle,
+    text::{renderer::TextRenderer, Baseline},
+    Drawable, Pixel,
+};
+
+/// Size of the buffer that decoded text is drawn from, in bytes.
+const BUFFER_LEN: usize = 64;
+
+/// Text in a legacy encoding, like GBK, or Shift_JIS, that is drawn without
+/// converting it to a UTF-8 string first.
+///
+/// The text is decoded into a small buffer on the stack, and each run of
+/// characters is drawn with the character style, like a `MonoTextStyle` with
+/// one of the bundled fonts. Line breaks start a new line, like they do in
+/// `Text`.
+///
+/// ```
+/// # #[cfg(feature = "gbk")]
+/// # fn main() {
+/// # use embedded_graphics_cjk_glyph_mapping::embedded_graphics;
+/// use embedded_graphics::{
+///     geometry::Point, mock_display::MockDisplay, mono_font::MonoTextStyle,
+///     pixelcolor::BinaryColor, Drawable,
+/// };
+/// use embedded_graphics_cjk_encoding::{EncodedText, Encoding};
+/// use embedded_graphics_cjk_font_zpix::ZPIX_12;
+///
+/// // `中文` in GBK
+/// let text = [0xD6, 0xD0, 0xCE, 0xC4];
+/// let style = MonoTextStyle::new(&ZPIX_12, BinaryColor::On);
+/// let mut display = MockDisplay::new();
+///
+/// display.set_allow_out_of_bounds_drawing(true);
+///
+/// let next = EncodedText::new(&text, Encoding::Gbk, Point::new(0, 12), style)
+///     .draw(&mut display)
+///     .unwrap();
+///
+/// assert_eq!(next, Point::new(2 * 13, 12));
+/// # }
+/// # #[cfg(not(feature = "gbk"))]
+/// # fn main() {}
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct EncodedText<'a, S> {
+    /// The encoded text.
+    pub bytes: &'a [u8],
+
+    /// Encoding of the text.
+    pub encoding: Encoding,
+
+    /// Position of the start of the first line.
+    pub position: Point,
+
+    /// Character style.
+    pub character_style: S,
+
+    /// Vertical position of the text, relative to `position`.
+    pub baseline: Baseline,
+}
+
+impl<'a, S> EncodedText<'a, S> {
+    /// Creates text with the alphabetic baseline.
+    pub fn new(bytes: &'a [u8], encoding: Encoding, position: Point, character_style: S) -> Self {
+        EncodedText::with_baseline(
+            bytes,
+            encoding,
+            position,
+            character_style,
+            Baseline::Alphabetic,
+        )
+    }
+
+    /// Creates text with a baseline.
+    pub fn with_baseline(
+        bytes: &'a [u8],
+        encoding: Encoding,
+        position: Point,
+        character_style: S,
+        baseline: Baseline,
+    ) -> Self {
+        EncodedText {
+            bytes,
+            encoding,
+            position,
+            character_style,
+            baseline,
+        }
+    }
+
+    /// Returns an iterator over the decoded characters of the text.
+    pub fn chars(&self) -> Chars<'a> {
+        self.encoding.decode(self.bytes)
+    }
+}
+
+impl<S> EncodedText<'_, S>
+where
+    S: TextRenderer,
+{
+    /// Draws a run of decoded characters, and returns the position after
+    /// it.
+    fn draw_run<D>(&self, run: &[u8], position: Point, target: &mut D) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = S::Color>,
+    {
+        // The buffer only ever has whole characters
+        let run = str::from_utf8(run).unwrap_or_default();
+
+        if run.is_empty() {
+            return Ok(position);
+        }
+
+        self.character_style
+            .draw_string(run, position, self.baseline, target)
+    }
+}
+
+impl<S> Drawable for EncodedText<'_, S>
+where
+    S: TextRenderer,
+{
+    type Color = S::Color;
+
+    /// Position after the last character.
+    type Output = Point;
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let line_height = self.character_style.line_height() as i32;
+        let mut buffer = [0; BUFFER_LEN];
+        let mut len = 0;
+        let mut last_len = 0;
+        let mut position = self.position;
+
+        for chr in self.chars() {
+            if chr == '\n' {
+                self.draw_run(&buffer[..len], position, target)?;
+
+                len = 0;
+                position = Point::new(self.position.x, position.y + line_height);
+
+                continue;
+            }
+
+            if len + chr.len_utf8() > BUFFER_LEN {
+                // The spacing after the last character of a run isn't drawn,
+                // so the last character is drawn again at the start of the
+                // next run, to space the runs like the characters in them.
+                let last = len - last_len..len;
+                let next = self.draw_run(&buffer[..len], position, target)?;
+                let last_width = self
+                    .draw_run(&buffer[last.clone()], Point::zero(), &mut Discard::new())
+                    .unwrap_or_default()
+                    .x;
+
+                position = Point::new(next.x - last_width, position.y);
+                buffer.copy_within(last, 0);
+                len = last_len;
+            }
+
+            last_len = chr.encode_utf8(&mut buffer[len..]).len();
+            len += last_len;
+        }
+
+        self.draw_run(&buffer[..len], position, target)
+    }
+}
+
+/// A draw target that discards every pixel, to measure text by drawing it.
+///
+/// `MonoTextStyle::measure_string` in embedded-graphics 0.7 measures bytes,
+/// not characters, so it can't measure CJK characters.
+struct Discard<C>(PhantomData<C>);
+
+impl<C> Discard<C> {
+    fn new() -> Self {
+        Discard(PhantomData)
+    }
+}
+
+impl<C> Dimensions for Discard<C> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::zero()
+    }
+}
+
+impl<C> DrawTarget for Discard<C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, _pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "gbk"))]
+mod tests {
+    use super::{EncodedText, BUFFER_LEN};
+    use crate::Encoding;
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{mapping::StrGlyphMapping, DecorationDimensions, MonoFont, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        text::Baseline,
+        Drawable,
+    };
+    use embedded_graphics_cjk_glyph_mapping::binary_image;
+
+    /// A font with a 2x2 box for `中`, and a single pixel for `.`.
+    const FONT: MonoFont = MonoFont {
+        image: binary_image(&[0xC0, 0xD0], 4),
+        glyph_mapping: &StrGlyphMapping::new("中.", 0),
+        character_size: Size::new(2, 2),
+        character_spacing: 1,
+        baseline: 1,
+        underline: DecorationDimensions::new(1, 1),
+        strikethrough: DecorationDimensions::new(0, 1),
+    };
+
+    #[test]
+    fn test_draw() {
+        let style = MonoTextStyle::new(&FONT, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        let next = EncodedText::with_baseline(
+            &[0xD6, 0xD0, b'.', b'\n', 0xD6, 0xD0],
+            Encoding::Gbk,
+            Point::zero(),
+            style,
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(next, Point::new(2, 2));
+
+        display.assert_pattern(&[
+            "##    ", //
+            "##  # ", "##    ", "##    ",
+        ]);
+    }
+
+    #[test]
+    fn test_draw_long_text() {
+        let style = MonoTextStyle::new(&FONT, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        // The last character of each run is drawn twice
+        display.set_allow_out_of_bounds_drawing(true);
+        display.set_allow_overdraw(true);
+
+        // More characters than fit in the buffer, which are drawn in several
+        // runs, spaced like the characters in them
+        let text = [0xD6, 0xD0].repeat(BUFFER_LEN);
+        let next = EncodedText::new(&text, Encoding::Gbk, Point::zero(), style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(3 * BUFFER_LEN as i32 - 1, 0));
+    }
+}
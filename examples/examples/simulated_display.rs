@@ -7,7 +7,7 @@ use embedded_graphics_cjk_font_sarasa_gothic::SARASA_MONO_SC_LIGHT_36;
 use embedded_graphics_cjk_font_zpix::{ZPIX_12, ZPIX_24};
 use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay, Window};
 
-const TEXT: &'static str = "大沼澤地國家公園";
+const TEXT: &str = "大沼澤地國家公園";
 
 fn main() -> Result<(), core::convert::Infallible> {
     let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(512, 512));
@@ -0,0 +1,167 @@
+use crate::{Error, GlyphLayout, GlyphSource, GLYPH_BUFFER_SIZE};
+use embedded_graphics::geometry::Size;
+
+/// Size of a block on a [`BlockDevice`], in bytes.
+pub const BLOCK_SIZE: usize = 512;
+
+/// Storage that is read a whole block at a time, like an SD card.
+pub trait BlockDevice {
+    type Error;
+
+    /// Reads the block at `index`. The first block starts at address 0.
+    fn read_block(&mut self, index: u32, block: &mut [u8; BLOCK_SIZE]) -> Result<(), Self::Error>;
+}
+
+/// Reads glyphs from a block device. The last block that was read is kept,
+/// since rows of a glyph are often in the same block.
+pub struct BlockDeviceGlyphSource<D> {
+    device: D,
+    layout: GlyphLayout,
+    block: [u8; BLOCK_SIZE],
+    block_index: Option<u32>,
+}
+
+impl<D> BlockDeviceGlyphSource<D>
+where
+    D: BlockDevice,
+{
+    /// Reads glyphs from a bitmap written to `device` at the address in
+    /// `layout`.
+    pub fn new(device: D, layout: GlyphLayout) -> Self {
+        BlockDeviceGlyphSource {
+            device,
+            layout,
+            block: [0; BLOCK_SIZE],
+            block_index: None,
+        }
+    }
+
+    /// Returns the block device.
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Reads `bytes` from `address`, which can span more than one block.
+    fn read(&mut self, mut address: u32, mut bytes: &mut [u8]) -> Result<(), Error<D::Error>> {
+        while !bytes.is_empty() {
+            let index = address / BLOCK_SIZE as u32;
+            let start = address as usize % BLOCK_SIZE;
+            let len = bytes.len().min(BLOCK_SIZE - start);
+
+            if self.block_index != Some(index) {
+                // A failed read can leave a partial block behind.
+                self.block_index = None;
+                self.device
+                    .read_block(index, &mut self.block)
+                    .map_err(Error::Storage)?;
+                self.block_index = Some(index);
+            }
+
+            bytes[..len].copy_from_slice(&self.block[start..][..len]);
+            bytes = &mut bytes[len..];
+            address += len as u32;
+        }
+
+        Ok(())
+    }
+}
+
+impl<D> GlyphSource for BlockDeviceGlyphSource<D>
+where
+    D: BlockDevice,
+{
+    type Error = Error<D::Error>;
+
+    fn character_size(&self) -> Size {
+        self.layout.character_size
+    }
+
+    fn read_glyph(
+        &mut self,
+        index: usize,
+        glyph: &mut [u8; GLYPH_BUFFER_SIZE],
+    ) -> Result<(), Self::Error> {
+        let layout = self.layout;
+
+        layout.read_glyph(index, glyph, |address, bytes| self.read(address, bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockDevice, BlockDeviceGlyphSource, BLOCK_SIZE};
+    use crate::{tests::raw_data, Error, GlyphLayout, GlyphSource, GLYPH_BUFFER_SIZE};
+    use embedded_graphics::geometry::Size;
+
+    /// Two blocks, with the first row of the font at the end of the first
+    /// block.
+    struct MockCard {
+        blocks: [[u8; BLOCK_SIZE]; 2],
+
+        /// Index of each block that was read.
+        reads: [u32; 16],
+        num_reads: usize,
+    }
+
+    const OFFSET: u32 = BLOCK_SIZE as u32 - 20;
+
+    impl MockCard {
+        fn new() -> Self {
+            let mut blocks = [[0xFF; BLOCK_SIZE]; 2];
+            let raw = raw_data();
+
+            blocks[0][OFFSET as usize..].copy_from_slice(&raw[..20]);
+            blocks[1][..40].copy_from_slice(&raw[20..]);
+
+            MockCard {
+                blocks,
+                reads: [0; 16],
+                num_reads: 0,
+            }
+        }
+    }
+
+    impl BlockDevice for MockCard {
+        type Error = ();
+
+        fn read_block(&mut self, index: u32, block: &mut [u8; BLOCK_SIZE]) -> Result<(), ()> {
+            let data = self.blocks.get(index as usize).ok_or(())?;
+
+            self.reads[self.num_reads] = index;
+            self.num_reads += 1;
+            block.copy_from_slice(data);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_read_across_blocks() {
+        let layout = GlyphLayout::new(OFFSET, Size::new(5, 3));
+        let mut source = BlockDeviceGlyphSource::new(MockCard::new(), layout);
+        let mut glyph = [0; GLYPH_BUFFER_SIZE];
+
+        assert_eq!(source.character_size(), Size::new(5, 3));
+
+        for index in 0..5 {
+            source.read_glyph(index, &mut glyph).unwrap();
+
+            assert_eq!(glyph[..3], [0x80 >> index; 3], "{}", index);
+        }
+
+        // Each glyph's first row is in the first block, and the other rows
+        // are in the second block.
+        let card = source.into_inner();
+
+        assert_eq!(card.reads[..card.num_reads], [0, 1, 0, 1, 0, 1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_read_error() {
+        let layout = GlyphLayout::new(BLOCK_SIZE as u32 * 2, Size::new(5, 3));
+        let mut source = BlockDeviceGlyphSource::new(MockCard::new(), layout);
+        let mut glyph = [0; GLYPH_BUFFER_SIZE];
+
+        assert_eq!(source.read_glyph(0, &mut glyph), Err(Error::Storage(())));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{GlyphSource, GLYPH_BUFFER_SIZE};
+
+/// A glyph that was read from the source.
+struct CacheEntry {
+    /// Index of the glyph, or `None` if the entry is empty.
+    index: Option<usize>,
+
+    /// Value of the cache's clock when the glyph was last used.
+    last_used: u32,
+
+    glyph: [u8; GLYPH_BUFFER_SIZE],
+}
+
+/// Keeps the `N` most recently used glyphs in memory, so text that repeats
+/// characters doesn't have to read them from storage again.
+///
+/// Each entry takes [`GLYPH_BUFFER_SIZE`] bytes.
+pub struct GlyphCache<S, const N: usize> {
+    source: S,
+    entries: [CacheEntry; N],
+
+    /// Counts up every time a glyph is used.
+    clock: u32,
+}
+
+impl<S, const N: usize> GlyphCache<S, N>
+where
+    S: GlyphSource,
+{
+    /// Creates an empty cache.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    pub fn new(source: S) -> Self {
+        assert!(N > 0, "The cache needs at least one entry");
+
+        GlyphCache {
+            source,
+            entries: core::array::from_fn(|_| CacheEntry {
+                index: None,
+                last_used: 0,
+                glyph: [0; GLYPH_BUFFER_SIZE],
+            }),
+            clock: 0,
+        }
+    }
+
+    /// Returns the glyph source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Returns the glyph source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    /// Returns the glyph at `index`, reading it from the source if it isn't
+    /// cached. The least recently used glyph is replaced when the cache is
+    /// full.
+    pub fn glyph(&mut self, index: usize) -> Result<&[u8; GLYPH_BUFFER_SIZE], S::Error> {
+        self.clock = self.clock.wrapping_add(1);
+
+        let clock = self.clock;
+        let entry = match self
+            .entries
+            .iter()
+            .position(|entry| entry.index == Some(index))
+        {
+            Some(hit) => &mut self.entries[hit],
+            None => {
+                // Empty entries haven't been used since the clock started.
+                let entry = self
+                    .entries
+                    .iter_mut()
+                    .max_by_key(|entry| match entry.index {
+                        Some(_) => clock.wrapping_sub(entry.last_used),
+                        None => u32::MAX,
+                    })
+                    .unwrap();
+
+                entry.index = None;
+                self.source.read_glyph(index, &mut entry.glyph)?;
+                entry.index = Some(index);
+                entry
+            }
+        };
+
+        entry.last_used = clock;
+
+        Ok(&entry.glyph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GlyphCache;
+    use crate::{flash::tests::MockFlash, tests::OFFSET, GlyphLayout, NorFlashGlyphSource};
+    use embedded_graphics::geometry::Size;
+
+    fn cache<const N: usize>() -> GlyphCache<NorFlashGlyphSource<MockFlash<1>>, N> {
+        let layout = GlyphLayout::new(OFFSET, Size::new(5, 3));
+
+        GlyphCache::new(NorFlashGlyphSource::new(MockFlash::new(), layout))
+    }
+
+    /// Reads each glyph, and returns the number of glyphs read from flash.
+    fn glyph_reads<const N: usize>(
+        cache: &mut GlyphCache<NorFlashGlyphSource<MockFlash<1>>, N>,
+        indices: &[usize],
+    ) -> usize {
+        let before = cache.source().flash().reads;
+
+        for index in indices {
+            assert_eq!(cache.glyph(*index).unwrap()[..3], [0x80 >> index; 3]);
+        }
+
+        // Each glyph takes a read for each of its 3 rows
+        (cache.source().flash().reads - before) / 3
+    }
+
+    #[test]
+    fn test_hits() {
+        let mut cache = cache::<2>();
+
+        assert_eq!(glyph_reads(&mut cache, &[0, 1]), 2);
+        assert_eq!(glyph_reads(&mut cache, &[1, 0, 0, 1]), 0);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = cache::<2>();
+
+        assert_eq!(glyph_reads(&mut cache, &[0, 1, 0]), 2);
+
+        // 1 is replaced, since 0 was used more recently
+        assert_eq!(glyph_reads(&mut cache, &[2]), 1);
+        assert_eq!(glyph_reads(&mut cache, &[0]), 0);
+        assert_eq!(glyph_reads(&mut cache, &[1]), 1);
+        assert_eq!(glyph_reads(&mut cache, &[0, 1]), 0);
+    }
+
+    #[test]
+    fn test_failed_read_is_not_cached() {
+        let mut cache = cache::<1>();
+
+        assert!(cache.glyph(64).is_err());
+        assert_eq!(glyph_reads(&mut cache, &[0, 0]), 1);
+    }
+}
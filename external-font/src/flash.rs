@@ -0,0 +1,174 @@
+use crate::{Error, GlyphLayout, GlyphSource, GLYPH_BUFFER_SIZE};
+use embedded_graphics::geometry::Size;
+use embedded_storage::nor_flash::ReadNorFlash;
+
+/// Size of the buffer used to read from flash that can only read aligned
+/// chunks.
+const READ_BUFFER_SIZE: usize = 64;
+
+/// Reads glyphs from NOR flash, like an SPI flash chip.
+pub struct NorFlashGlyphSource<F> {
+    flash: F,
+    layout: GlyphLayout,
+}
+
+impl<F> NorFlashGlyphSource<F>
+where
+    F: ReadNorFlash,
+{
+    /// Reads glyphs from a bitmap written to `flash` at the address in
+    /// `layout`.
+    pub fn new(flash: F, layout: GlyphLayout) -> Self {
+        NorFlashGlyphSource { flash, layout }
+    }
+
+    /// Returns the flash.
+    pub fn flash(&self) -> &F {
+        &self.flash
+    }
+
+    /// Returns the flash.
+    pub fn into_inner(self) -> F {
+        self.flash
+    }
+
+    /// Reads `bytes` from `address`, which doesn't have to be aligned to
+    /// the flash's read size.
+    fn read(&mut self, address: u32, bytes: &mut [u8]) -> Result<(), Error<F::Error>> {
+        let read_size = F::READ_SIZE as u32;
+
+        if read_size <= 1 {
+            return self.flash.read(address, bytes).map_err(Error::Storage);
+        }
+
+        let start = address - address % read_size;
+        let end = (address + bytes.len() as u32).next_multiple_of(read_size);
+        let mut buffer = [0; READ_BUFFER_SIZE];
+        let buffer = buffer
+            .get_mut(..(end - start) as usize)
+            .ok_or(Error::UnsupportedReadSize)?;
+
+        self.flash.read(start, buffer).map_err(Error::Storage)?;
+        bytes.copy_from_slice(&buffer[(address - start) as usize..][..bytes.len()]);
+
+        Ok(())
+    }
+}
+
+impl<F> GlyphSource for NorFlashGlyphSource<F>
+where
+    F: ReadNorFlash,
+{
+    type Error = Error<F::Error>;
+
+    fn character_size(&self) -> Size {
+        self.layout.character_size
+    }
+
+    fn read_glyph(
+        &mut self,
+        index: usize,
+        glyph: &mut [u8; GLYPH_BUFFER_SIZE],
+    ) -> Result<(), Self::Error> {
+        let layout = self.layout;
+
+        layout.read_glyph(index, glyph, |address, bytes| self.read(address, bytes))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::NorFlashGlyphSource;
+    use crate::{
+        tests::{flash_data, OFFSET},
+        Error, GlyphLayout, GlyphSource, GLYPH_BUFFER_SIZE,
+    };
+    use embedded_graphics::geometry::Size;
+    use embedded_storage::nor_flash::{check_read, ErrorType, NorFlashErrorKind, ReadNorFlash};
+
+    /// Flash in memory, that can only read chunks of `READ_SIZE` bytes.
+    pub(crate) struct MockFlash<const READ_SIZE: usize> {
+        pub(crate) data: [u8; 80],
+
+        /// Number of times the flash was read.
+        pub(crate) reads: usize,
+    }
+
+    impl<const READ_SIZE: usize> MockFlash<READ_SIZE> {
+        pub(crate) fn new() -> Self {
+            MockFlash {
+                data: flash_data(),
+                reads: 0,
+            }
+        }
+    }
+
+    impl<const READ_SIZE: usize> ErrorType for MockFlash<READ_SIZE> {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl<const READ_SIZE: usize> ReadNorFlash for MockFlash<READ_SIZE> {
+        const READ_SIZE: usize = READ_SIZE;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            check_read(self, offset, bytes.len())?;
+
+            self.reads += 1;
+            bytes.copy_from_slice(&self.data[offset as usize..][..bytes.len()]);
+
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    fn read_glyphs<const READ_SIZE: usize>() {
+        let layout = GlyphLayout::new(OFFSET, Size::new(5, 3));
+        let mut source = NorFlashGlyphSource::new(MockFlash::<READ_SIZE>::new(), layout);
+        let mut glyph = [0; GLYPH_BUFFER_SIZE];
+
+        assert_eq!(source.character_size(), Size::new(5, 3));
+
+        for index in 0..5 {
+            source.read_glyph(index, &mut glyph).unwrap();
+
+            assert_eq!(glyph[..3], [0x80 >> index; 3], "{}", index);
+        }
+
+        // One read for each row
+        assert_eq!(source.into_inner().reads, 5 * 3);
+    }
+
+    #[test]
+    fn test_read_glyphs() {
+        read_glyphs::<1>();
+        read_glyphs::<4>();
+        read_glyphs::<16>();
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let mut glyph = [0; GLYPH_BUFFER_SIZE];
+        let layout = GlyphLayout::new(OFFSET, Size::new(5, 3));
+        let mut source = NorFlashGlyphSource::new(MockFlash::<1>::new(), layout);
+
+        assert_eq!(
+            source.read_glyph(64, &mut glyph),
+            Err(Error::Storage(NorFlashErrorKind::OutOfBounds))
+        );
+
+        let mut source = NorFlashGlyphSource::new(MockFlash::<128>::new(), layout);
+
+        assert_eq!(
+            source.read_glyph(0, &mut glyph),
+            Err(Error::UnsupportedReadSize)
+        );
+
+        let layout = GlyphLayout::new(OFFSET, Size::new(65, 3));
+        let mut source = NorFlashGlyphSource::new(MockFlash::<1>::new(), layout);
+
+        assert_eq!(source.read_glyph(0, &mut glyph), Err(Error::GlyphTooLarge));
+    }
+}
@@ -0,0 +1,198 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod block;
+mod cache;
+mod flash;
+mod style;
+
+use embedded_graphics::geometry::Size;
+
+pub use block::{BlockDevice, BlockDeviceGlyphSource, BLOCK_SIZE};
+pub use cache::GlyphCache;
+pub use flash::NorFlashGlyphSource;
+pub use style::{ExternalFont, ExternalTextStyle};
+
+/// Largest width, and height of a glyph cell that can be read.
+pub const MAX_GLYPH_SIZE: u32 = 64;
+
+/// Size of the buffer a single glyph is read into.
+pub const GLYPH_BUFFER_SIZE: usize = (MAX_GLYPH_SIZE * MAX_GLYPH_SIZE / 8) as usize;
+
+/// The number of glyphs on a single line of the bitmap written by
+/// `MonoFontBuilder`.
+const GLYPHS_PER_ROW: u64 = 32;
+
+/// Reads the bitmap of a single glyph from wherever the font is stored.
+pub trait GlyphSource {
+    type Error;
+
+    /// Size of a single character in pixels.
+    fn character_size(&self) -> Size;
+
+    /// Reads the glyph at `index` into `glyph`, in the same format as the
+    /// image of a `MonoFont` containing only that glyph. Each row starts on a
+    /// new byte, with the first pixel in the most significant bit.
+    fn read_glyph(
+        &mut self,
+        index: usize,
+        glyph: &mut [u8; GLYPH_BUFFER_SIZE],
+    ) -> Result<(), Self::Error>;
+}
+
+/// Errors reading a glyph from storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The storage couldn't be read.
+    Storage(E),
+
+    /// The glyph cell is larger than [`MAX_GLYPH_SIZE`].
+    GlyphTooLarge,
+
+    /// The glyph is past the end of the addressable storage.
+    OutOfRange,
+
+    /// The storage can't read small enough chunks to read a single row.
+    UnsupportedReadSize,
+}
+
+/// Where a font's bitmap is stored, and the size of its glyphs.
+///
+/// The bitmap is the raw data written by `MonoFontBuilder`, where glyphs are
+/// placed in rows of 32 cells, one bit per pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphLayout {
+    /// Address of the first byte of the bitmap.
+    pub offset: u32,
+
+    /// Size of a single character in pixels.
+    pub character_size: Size,
+}
+
+impl GlyphLayout {
+    /// Creates a layout for a bitmap starting at `offset`.
+    pub const fn new(offset: u32, character_size: Size) -> Self {
+        GlyphLayout {
+            offset,
+            character_size,
+        }
+    }
+
+    /// Reads the glyph at `index` one row at a time, with `read` filling a
+    /// buffer from the given address.
+    pub(crate) fn read_glyph<E, F>(
+        &self,
+        index: usize,
+        glyph: &mut [u8; GLYPH_BUFFER_SIZE],
+        mut read: F,
+    ) -> Result<(), Error<E>>
+    where
+        F: FnMut(u32, &mut [u8]) -> Result<(), Error<E>>,
+    {
+        let Size { width, height } = self.character_size;
+
+        if width > MAX_GLYPH_SIZE || height > MAX_GLYPH_SIZE {
+            return Err(Error::GlyphTooLarge);
+        }
+
+        let (width, height, index) = (u64::from(width), u64::from(height), index as u64);
+        let bytes_per_row = width.div_ceil(8) as usize;
+
+        for y in 0..height {
+            let bit = (index / GLYPHS_PER_ROW * height + y) * GLYPHS_PER_ROW * width
+                + index % GLYPHS_PER_ROW * width;
+            let address =
+                u32::try_from(u64::from(self.offset) + bit / 8).map_err(|_| Error::OutOfRange)?;
+            let shift = (bit % 8) as u32;
+
+            // The row doesn't have to start on a byte, so it can span one
+            // byte more than its width.
+            let mut bytes = [0; MAX_GLYPH_SIZE as usize / 8 + 1];
+            let len = (u64::from(shift) + width).div_ceil(8) as usize;
+
+            read(address, &mut bytes[..len])?;
+
+            let row = &mut glyph[y as usize * bytes_per_row..][..bytes_per_row];
+
+            for (i, byte) in row.iter_mut().enumerate() {
+                *byte = bytes[i].checked_shl(shift).unwrap_or(0)
+                    | bytes[i + 1].checked_shr(8 - shift).unwrap_or(0);
+            }
+
+            // Clears the pixels of the next glyph.
+            if width % 8 != 0 {
+                row[bytes_per_row - 1] &= 0xFF << (8 - width % 8);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{GlyphLayout, GLYPH_BUFFER_SIZE};
+    use embedded_graphics::geometry::Size;
+
+    /// The number of bytes before the font in [`flash_data`].
+    pub(crate) const OFFSET: u32 = 5;
+
+    /// Raw data for a font with 5x3 cells, in rows of 32 glyphs. Glyph `n`
+    /// has a single column of pixels set, `n` pixels from the left.
+    pub(crate) fn raw_data() -> [u8; 60] {
+        let mut raw = [0; 60];
+
+        for index in 0..5 {
+            for y in 0..3 {
+                let bit = y * 32 * 5 + index * 5 + index;
+
+                raw[bit / 8] |= 0x80 >> (bit % 8);
+            }
+        }
+
+        raw
+    }
+
+    /// Storage with [`raw_data`] at [`OFFSET`], and garbage everywhere else.
+    pub(crate) fn flash_data() -> [u8; 80] {
+        let mut data = [0xFF; 80];
+
+        data[OFFSET as usize..][..60].copy_from_slice(&raw_data());
+
+        data
+    }
+
+    #[test]
+    fn test_read_glyph() {
+        let layout = GlyphLayout::new(OFFSET, Size::new(5, 3));
+        let data = flash_data();
+
+        for index in 0..5 {
+            let mut glyph = [0xAA; GLYPH_BUFFER_SIZE];
+
+            layout
+                .read_glyph::<(), _>(index, &mut glyph, |address, bytes| {
+                    bytes.copy_from_slice(&data[address as usize..][..bytes.len()]);
+                    Ok(())
+                })
+                .unwrap();
+
+            assert_eq!(glyph[..3], [0x80 >> index; 3], "{}", index);
+        }
+    }
+}
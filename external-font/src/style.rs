@@ -0,0 +1,523 @@
+use crate::{GlyphCache, GlyphSource, GLYPH_BUFFER_SIZE};
+use core::cell::RefCell;
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    image::ImageRaw,
+    mono_font::{
+        mapping::GlyphMapping, DecorationDimensions, MonoFont, MonoTextStyle, MonoTextStyleBuilder,
+    },
+    pixelcolor::{BinaryColor, PixelColor},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+
+/// Maps every character to the only glyph in a decoded cell.
+struct SingleGlyph;
+
+impl GlyphMapping for SingleGlyph {
+    fn index(&self, _chr: char) -> usize {
+        0
+    }
+}
+
+/// A monospaced font, with glyphs that are read from external storage as
+/// they're drawn.
+///
+/// The `N` most recently drawn glyphs are kept in a [`GlyphCache`]. Glyphs
+/// that can't be read are drawn blank, and the error can be retrieved with
+/// [`ExternalFont::take_error`].
+pub struct ExternalFont<'a, S, const N: usize>
+where
+    S: GlyphSource,
+{
+    cache: RefCell<GlyphCache<S, N>>,
+
+    /// The last error reading a glyph.
+    error: RefCell<Option<S::Error>>,
+
+    /// Size of a single character in pixels.
+    character_size: Size,
+
+    /// Glyph mapping.
+    pub glyph_mapping: &'a dyn GlyphMapping,
+
+    /// Spacing between characters.
+    pub character_spacing: u32,
+
+    /// The baseline, as an offset from the top of a glyph.
+    pub baseline: u32,
+
+    /// Offset from top of a glyph to the top of the underline, and its
+    /// height.
+    pub underline: DecorationDimensions,
+
+    /// Offset from top of a glyph to the top of the strikethrough, and its
+    /// height.
+    pub strikethrough: DecorationDimensions,
+}
+
+impl<'a, S, const N: usize> ExternalFont<'a, S, N>
+where
+    S: GlyphSource,
+{
+    /// Creates a font that reads glyphs from `source`. The glyph mapping,
+    /// and metrics should be the same as those of the generated `MonoFont`.
+    pub fn new(source: S, glyph_mapping: &'a dyn GlyphMapping, baseline: u32) -> Self {
+        let character_size = source.character_size();
+
+        ExternalFont {
+            cache: RefCell::new(GlyphCache::new(source)),
+            error: RefCell::new(None),
+            character_size,
+            glyph_mapping,
+            character_spacing: 0,
+            baseline,
+            underline: DecorationDimensions::default_underline(character_size.height),
+            strikethrough: DecorationDimensions::default_strikethrough(character_size.height),
+        }
+    }
+
+    /// Size of a single character in pixels.
+    pub fn character_size(&self) -> Size {
+        self.character_size
+    }
+
+    /// Returns the last error reading a glyph, if there was one since the
+    /// last call.
+    pub fn take_error(&self) -> Option<S::Error> {
+        self.error.borrow_mut().take()
+    }
+
+    /// Returns the glyph cache.
+    pub fn into_cache(self) -> GlyphCache<S, N> {
+        self.cache.into_inner()
+    }
+}
+
+/// Style for text drawn with an [`ExternalFont`].
+///
+/// Each glyph is drawn the same way as a `MonoTextStyle` would draw it, so
+/// text looks exactly the same as it would with the generated `MonoFont`.
+pub struct ExternalTextStyle<'a, S, C, const N: usize>
+where
+    S: GlyphSource,
+{
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Underline color.
+    pub underline_color: DecorationColor<C>,
+
+    /// Strikethrough color.
+    pub strikethrough_color: DecorationColor<C>,
+
+    /// Font.
+    pub font: &'a ExternalFont<'a, S, N>,
+}
+
+// Deriving these would require the glyph source to implement them too.
+impl<S, C, const N: usize> Clone for ExternalTextStyle<'_, S, C, N>
+where
+    S: GlyphSource,
+    C: Copy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, C, const N: usize> Copy for ExternalTextStyle<'_, S, C, N>
+where
+    S: GlyphSource,
+    C: Copy,
+{
+}
+
+impl<'a, S, C, const N: usize> ExternalTextStyle<'a, S, C, N>
+where
+    S: GlyphSource,
+    C: PixelColor,
+{
+    /// Creates a text style with a transparent background.
+    pub const fn new(font: &'a ExternalFont<'a, S, N>, text_color: C) -> Self {
+        ExternalTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            underline_color: DecorationColor::None,
+            strikethrough_color: DecorationColor::None,
+            font,
+        }
+    }
+
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => self.font.character_size().height.saturating_sub(1) as i32,
+            Baseline::Middle => (self.font.character_size().height.saturating_sub(1) / 2) as i32,
+            Baseline::Alphabetic => self.font.baseline as i32,
+        }
+    }
+
+    /// Returns a style that draws a single decoded glyph. Decorations are
+    /// drawn separately, across the whole string.
+    fn glyph_style<'b>(&self, font: &'b MonoFont<'b>) -> MonoTextStyle<'b, C> {
+        let mut style = MonoTextStyleBuilder::new().font(font).build();
+
+        style.set_text_color(self.text_color);
+        style.set_background_color(self.background_color);
+
+        style
+    }
+
+    /// Draws the glyph for a character, with its top left corner at
+    /// `position`.
+    fn draw_glyph<D>(&self, chr: char, position: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let mut cache = self.font.cache.borrow_mut();
+        let glyph = match cache.glyph(self.font.glyph_mapping.index(chr)) {
+            Ok(glyph) => glyph,
+            Err(err) => {
+                *self.font.error.borrow_mut() = Some(err);
+                &[0; GLYPH_BUFFER_SIZE]
+            }
+        };
+        let Size { width, height } = self.font.character_size();
+        let font = MonoFont {
+            image: ImageRaw::<BinaryColor>::new(
+                &glyph[..width.div_ceil(8) as usize * height as usize],
+                width,
+            ),
+            glyph_mapping: &SingleGlyph,
+            character_size: self.font.character_size(),
+            character_spacing: 0,
+            baseline: self.font.baseline,
+            underline: self.font.underline,
+            strikethrough: self.font.strikethrough,
+        };
+
+        self.glyph_style(&font).draw_string(
+            chr.encode_utf8(&mut [0; 4]),
+            position,
+            Baseline::Top,
+            target,
+        )?;
+
+        Ok(())
+    }
+
+    fn draw_decoration<D>(
+        &self,
+        decoration: &DecorationDimensions,
+        color: DecorationColor<C>,
+        width: u32,
+        position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let color = match color {
+            DecorationColor::None => None,
+            DecorationColor::TextColor => self.text_color,
+            DecorationColor::Custom(color) => Some(color),
+        };
+
+        match color {
+            Some(color) => target.fill_solid(
+                &Rectangle::new(
+                    position + Point::new(0, decoration.offset as i32),
+                    Size::new(width, decoration.height),
+                ),
+                color,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn draw_decorations<D>(
+        &self,
+        width: u32,
+        position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_decoration(
+            &self.font.strikethrough,
+            self.strikethrough_color,
+            width,
+            position,
+            target,
+        )?;
+        self.draw_decoration(
+            &self.font.underline,
+            self.underline_color,
+            width,
+            position,
+            target,
+        )
+    }
+}
+
+impl<S, C, const N: usize> TextRenderer for ExternalTextStyle<'_, S, C, N>
+where
+    S: GlyphSource,
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let position = position - Point::new(0, self.baseline_offset(baseline));
+        let Size { width, height } = self.font.character_size();
+        let spacing = self.font.character_spacing;
+        let mut next = position;
+
+        for (i, chr) in text.chars().enumerate() {
+            if i > 0 {
+                if let (Some(background_color), true) = (self.background_color, spacing > 0) {
+                    target.fill_solid(
+                        &Rectangle::new(next, Size::new(spacing, height)),
+                        background_color,
+                    )?;
+                }
+
+                next += Point::new(spacing as i32, 0);
+            }
+
+            if self.text_color.is_some() || self.background_color.is_some() {
+                self.draw_glyph(chr, next, target)?;
+            }
+
+            next += Point::new(width as i32, 0);
+        }
+
+        if next.x > position.x {
+            self.draw_decorations((next.x - position.x) as u32, position, target)?;
+        }
+
+        Ok(next + Point::new(0, self.baseline_offset(baseline)))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let position = position - Point::new(0, self.baseline_offset(baseline));
+
+        if width != 0 {
+            if let Some(background_color) = self.background_color {
+                target.fill_solid(
+                    &Rectangle::new(
+                        position,
+                        Size::new(width, self.font.character_size().height),
+                    ),
+                    background_color,
+                )?;
+            }
+
+            self.draw_decorations(width, position, target)?;
+        }
+
+        Ok(position + Point::new(width as i32, self.baseline_offset(baseline)))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let bb_position = position - Point::new(0, self.baseline_offset(baseline));
+        let bb_width = (text.chars().count() as u32
+            * (self.font.character_size().width + self.font.character_spacing))
+            .saturating_sub(self.font.character_spacing);
+        let bb_height = if self.underline_color != DecorationColor::None {
+            self.font.underline.height + self.font.underline.offset
+        } else {
+            self.font.character_size().height
+        };
+        let bb_size = Size::new(bb_width, bb_height);
+
+        TextMetrics {
+            bounding_box: Rectangle::new(bb_position, bb_size),
+            next_position: position + bb_size.x_axis(),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.character_size().height
+    }
+}
+
+impl<S, C, const N: usize> CharacterStyle for ExternalTextStyle<'_, S, C, N>
+where
+    S: GlyphSource,
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.underline_color = underline_color;
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.strikethrough_color = strikethrough_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExternalFont, ExternalTextStyle};
+    use crate::{
+        flash::tests::MockFlash,
+        tests::{raw_data, OFFSET},
+        GlyphLayout, NorFlashGlyphSource,
+    };
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        image::ImageRaw,
+        mock_display::MockDisplay,
+        mono_font::{mapping::StrGlyphMapping, MonoFont, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        text::{Baseline, DecorationColor, Text},
+        Drawable,
+    };
+    use embedded_storage::nor_flash::NorFlashErrorKind;
+
+    const GLYPH_MAPPING: StrGlyphMapping = StrGlyphMapping::new("abcde", 0);
+
+    type Font<'a> = ExternalFont<'a, NorFlashGlyphSource<MockFlash<1>>, 2>;
+
+    fn font() -> Font<'static> {
+        let layout = GlyphLayout::new(OFFSET, Size::new(5, 3));
+
+        ExternalFont::new(
+            NorFlashGlyphSource::new(MockFlash::new(), layout),
+            &GLYPH_MAPPING,
+            1,
+        )
+    }
+
+    /// Draws text with both the external font, and a `MonoFont` with the
+    /// same data in memory, and checks that the output is the same.
+    fn assert_matches_mono_font(font: &Font, text: &str) {
+        let raw = raw_data();
+        let mono_font = MonoFont {
+            image: ImageRaw::new(&raw, 32 * 5),
+            glyph_mapping: &GLYPH_MAPPING,
+            character_size: Size::new(5, 3),
+            character_spacing: font.character_spacing,
+            baseline: font.baseline,
+            underline: font.underline,
+            strikethrough: font.strikethrough,
+        };
+        let mut style = ExternalTextStyle::new(font, BinaryColor::On);
+        let mut mono_style = MonoTextStyle::new(&mono_font, BinaryColor::On);
+
+        style.background_color = Some(BinaryColor::Off);
+        style.underline_color = DecorationColor::TextColor;
+        mono_style.background_color = Some(BinaryColor::Off);
+        mono_style.underline_color = DecorationColor::TextColor;
+
+        let mut display = MockDisplay::new();
+        let mut mono_display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        mono_display.set_allow_overdraw(true);
+
+        let next = Text::with_baseline(text, Point::new(1, 2), style, Baseline::Alphabetic)
+            .draw(&mut display)
+            .unwrap();
+        let mono_next =
+            Text::with_baseline(text, Point::new(1, 2), mono_style, Baseline::Alphabetic)
+                .draw(&mut mono_display)
+                .unwrap();
+
+        assert_eq!(next, mono_next);
+        display.assert_eq(&mono_display);
+    }
+
+    #[test]
+    fn test_matches_mono_font() {
+        let mut font = font();
+
+        assert_matches_mono_font(&font, "abcde");
+        assert_matches_mono_font(&font, "eda\nb c");
+
+        font.character_spacing = 1;
+
+        assert_matches_mono_font(&font, "aebe");
+        assert!(font.take_error().is_none());
+    }
+
+    #[test]
+    fn test_draw_string() {
+        let font = font();
+        let style = ExternalTextStyle::new(&font, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        Text::with_baseline("aca", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(&["#      #  #", "#      #  #", "#      #  #"]);
+
+        // `a`, and `c` were each read once, one row at a time.
+        assert_eq!(font.into_cache().into_inner().flash().reads, 2 * 3);
+    }
+
+    #[test]
+    fn test_read_error() {
+        let mut font = font();
+        let mut display = MockDisplay::new();
+
+        // Glyphs past the end of the flash can't be read
+        fn past_end(_: char) -> usize {
+            64
+        }
+
+        font.glyph_mapping = &past_end;
+
+        Text::new(
+            "a",
+            Point::zero(),
+            ExternalTextStyle::new(&font, BinaryColor::On),
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            font.take_error(),
+            Some(crate::Error::Storage(NorFlashErrorKind::OutOfBounds))
+        );
+        assert_eq!(font.take_error(), None);
+        display.assert_pattern(&[]);
+    }
+}
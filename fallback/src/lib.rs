@@ -0,0 +1,491 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    mono_font::{DecorationDimensions, MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::PixelColor,
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+use embedded_graphics_cjk_glyph_mapping::TryGlyphMapping;
+
+/// A font, and the glyph mapping used to check which characters it covers.
+///
+/// The font crates export a `GLYPH_MAPPING` constant for each font, for
+/// example `ZPIX_12_GLYPH_MAPPING` for `ZPIX_12`. Fonts from other crates
+/// can use any mapping that covers the same characters as their glyphs.
+#[derive(Copy, Clone)]
+pub struct FallbackFont<'a> {
+    /// Font.
+    pub font: &'a MonoFont<'a>,
+
+    /// Characters covered by the font.
+    pub glyph_mapping: &'a dyn TryGlyphMapping,
+}
+
+impl<'a> FallbackFont<'a> {
+    /// Creates a font that is used for the characters covered by the glyph
+    /// mapping.
+    pub const fn new(font: &'a MonoFont<'a>, glyph_mapping: &'a dyn TryGlyphMapping) -> Self {
+        FallbackFont {
+            font,
+            glyph_mapping,
+        }
+    }
+}
+
+/// Text style that draws each character with the first font in a list that
+/// covers it.
+///
+/// Characters that none of the fonts cover are drawn with the default glyph
+/// of the first, primary font. All fonts are drawn on a shared baseline, and
+/// underlines, and strikethroughs use the dimensions of the primary font.
+///
+/// ```
+/// # use embedded_graphics_cjk_glyph_mapping::embedded_graphics;
+/// use embedded_graphics::{mono_font::ascii::FONT_6X10, pixelcolor::BinaryColor};
+/// use embedded_graphics_cjk_fallback::{FallbackFont, FallbackTextStyle};
+/// use embedded_graphics_cjk_font_zpix::{ZPIX_12, ZPIX_12_GLYPH_MAPPING};
+/// use embedded_graphics_cjk_glyph_mapping::IndexedRangeGlyphMapping;
+///
+/// const ASCII: IndexedRangeGlyphMapping<1> = IndexedRangeGlyphMapping::new([' '..='~'], 0);
+/// const FONTS: &[FallbackFont] = &[
+///     FallbackFont::new(&ZPIX_12, &ZPIX_12_GLYPH_MAPPING),
+///     FallbackFont::new(&FONT_6X10, &ASCII),
+/// ];
+///
+/// let style = FallbackTextStyle::new(FONTS, BinaryColor::On);
+///
+/// assert!(core::ptr::eq(style.font('中'), &ZPIX_12));
+/// assert!(core::ptr::eq(style.font('A'), &FONT_6X10));
+/// ```
+#[derive(Copy, Clone)]
+pub struct FallbackTextStyle<'a, C> {
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Underline color.
+    pub underline_color: DecorationColor<C>,
+
+    /// Strikethrough color.
+    pub strikethrough_color: DecorationColor<C>,
+
+    /// Fonts, in the order they're tried. Must contain at least one font.
+    pub fonts: &'a [FallbackFont<'a>],
+}
+
+/// Vertical layout shared by every font.
+struct LineMetrics {
+    /// Distance from the top of the line to the baseline.
+    baseline: u32,
+
+    /// Height of the line, which fits every font.
+    height: u32,
+}
+
+impl<'a, C> FallbackTextStyle<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a text style with a transparent background.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fonts` is empty.
+    pub const fn new(fonts: &'a [FallbackFont<'a>], text_color: C) -> Self {
+        if fonts.is_empty() {
+            panic!("Fallback text style needs at least one font");
+        }
+
+        FallbackTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            underline_color: DecorationColor::None,
+            strikethrough_color: DecorationColor::None,
+            fonts,
+        }
+    }
+
+    fn primary_font(&self) -> &'a MonoFont<'a> {
+        self.fonts[0].font
+    }
+
+    /// Returns the index of the font that's used to draw a character.
+    fn font_index(&self, chr: char) -> usize {
+        self.fonts
+            .iter()
+            .position(|font| font.glyph_mapping.contains(chr))
+            .unwrap_or(0)
+    }
+
+    /// Returns the font that's used to draw a character.
+    pub fn font(&self, chr: char) -> &'a MonoFont<'a> {
+        self.fonts[self.font_index(chr)].font
+    }
+
+    fn line_metrics(&self) -> LineMetrics {
+        let baseline = self
+            .fonts
+            .iter()
+            .map(|font| font.font.baseline)
+            .max()
+            .unwrap_or_default();
+        let descent = self
+            .fonts
+            .iter()
+            .map(|font| font.font.character_size.height - font.font.baseline)
+            .max()
+            .unwrap_or_default();
+
+        LineMetrics {
+            baseline,
+            height: baseline + descent,
+        }
+    }
+
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        let metrics = self.line_metrics();
+
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => metrics.height.saturating_sub(1) as i32,
+            Baseline::Middle => (metrics.height.saturating_sub(1) / 2) as i32,
+            Baseline::Alphabetic => metrics.baseline as i32,
+        }
+    }
+
+    /// Horizontal distance from the start of one character to the next.
+    fn advance(&self, chr: char) -> u32 {
+        let font = self.font(chr);
+
+        font.character_size.width + font.character_spacing
+    }
+
+    /// Width of a string, excluding the spacing after the last character.
+    fn string_width(&self, text: &str) -> u32 {
+        let width: u32 = text.chars().map(|chr| self.advance(chr)).sum();
+        let trailing_spacing = text
+            .chars()
+            .last()
+            .map(|chr| self.font(chr).character_spacing)
+            .unwrap_or_default();
+
+        width - trailing_spacing
+    }
+
+    /// Returns a style that draws a run of characters with one of the fonts.
+    /// Decorations are drawn separately, across the whole string.
+    fn run_style(&self, font: &'a MonoFont<'a>) -> MonoTextStyle<'a, C> {
+        let mut style = MonoTextStyleBuilder::new().font(font).build();
+
+        style.set_text_color(self.text_color);
+        style.set_background_color(self.background_color);
+
+        style
+    }
+
+    /// Draws an underline, or strikethrough, positioned relative to the
+    /// primary font's baseline.
+    fn draw_decoration<D>(
+        &self,
+        decoration: &DecorationDimensions,
+        color: DecorationColor<C>,
+        width: u32,
+        baseline_position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let color = match color {
+            DecorationColor::None => None,
+            DecorationColor::TextColor => self.text_color,
+            DecorationColor::Custom(color) => Some(color),
+        };
+
+        match color {
+            Some(color) => {
+                let top = decoration.offset as i32 - self.primary_font().baseline as i32;
+
+                target.fill_solid(
+                    &Rectangle::new(
+                        baseline_position + Point::new(0, top),
+                        Size::new(width, decoration.height),
+                    ),
+                    color,
+                )
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn draw_decorations<D>(
+        &self,
+        width: u32,
+        baseline_position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if width == 0 {
+            return Ok(());
+        }
+
+        self.draw_decoration(
+            &self.primary_font().strikethrough,
+            self.strikethrough_color,
+            width,
+            baseline_position,
+            target,
+        )?;
+        self.draw_decoration(
+            &self.primary_font().underline,
+            self.underline_color,
+            width,
+            baseline_position,
+            target,
+        )
+    }
+}
+
+impl<C> TextRenderer for FallbackTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let metrics = self.line_metrics();
+        let line_top = position - Point::new(0, self.baseline_offset(baseline));
+        let start = line_top + Point::new(0, metrics.baseline as i32);
+        let width = self.string_width(text);
+
+        // Fonts with a shorter cell than the line leave gaps above, and below
+        // their glyphs.
+        if let Some(background_color) = self.background_color {
+            target.fill_solid(
+                &Rectangle::new(line_top, Size::new(width, metrics.height)),
+                background_color,
+            )?;
+        }
+
+        let mut next = start;
+        let mut rest = text;
+
+        // Draws runs of characters that use the same font.
+        while let Some(first) = rest.chars().next() {
+            let font_index = self.font_index(first);
+            let run_len = rest
+                .char_indices()
+                .find(|(_, chr)| self.font_index(*chr) != font_index)
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len());
+            let (run, remaining) = rest.split_at(run_len);
+            let style = self.run_style(self.fonts[font_index].font);
+
+            style.draw_string(run, next, Baseline::Alphabetic, target)?;
+
+            next += Point::new(
+                run.chars().map(|chr| self.advance(chr)).sum::<u32>() as i32,
+                0,
+            );
+            rest = remaining;
+        }
+
+        self.draw_decorations(width, start, target)?;
+
+        Ok(next + position - start)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let metrics = self.line_metrics();
+        let line_top = position - Point::new(0, self.baseline_offset(baseline));
+
+        if width != 0 {
+            if let Some(background_color) = self.background_color {
+                target.fill_solid(
+                    &Rectangle::new(line_top, Size::new(width, metrics.height)),
+                    background_color,
+                )?;
+            }
+
+            self.draw_decorations(
+                width,
+                line_top + Point::new(0, metrics.baseline as i32),
+                target,
+            )?;
+        }
+
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let bb_position = position - Point::new(0, self.baseline_offset(baseline));
+        let bb_size = Size::new(self.string_width(text), self.line_metrics().height);
+
+        TextMetrics {
+            bounding_box: Rectangle::new(bb_position, bb_size),
+            next_position: position
+                + Point::new(text.chars().map(|c| self.advance(c)).sum::<u32>() as i32, 0),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.line_metrics().height
+    }
+}
+
+impl<C> CharacterStyle for FallbackTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.underline_color = underline_color;
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.strikethrough_color = strikethrough_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FallbackFont, FallbackTextStyle};
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X10, mapping::StrGlyphMapping, DecorationDimensions, MonoFont},
+        pixelcolor::BinaryColor,
+        text::{renderer::TextRenderer, Baseline, Text},
+        Drawable,
+    };
+    use embedded_graphics_cjk_glyph_mapping::{
+        binary_image, IndexedRangeGlyphMapping, SparseGlyphMapping,
+    };
+
+    /// A primary font with a single, filled 4x6 glyph for `中`, that's drawn
+    /// for every other character too.
+    const PRIMARY: MonoFont = MonoFont {
+        image: binary_image(&[0xF0; 6], 4),
+        glyph_mapping: &StrGlyphMapping::new("中", 0),
+        character_size: Size::new(4, 6),
+        character_spacing: 0,
+        baseline: 4,
+        underline: DecorationDimensions::new(5, 1),
+        strikethrough: DecorationDimensions::new(2, 1),
+    };
+
+    const PRIMARY_MAPPING: SparseGlyphMapping = SparseGlyphMapping::new(&['中'], 0);
+
+    const ASCII: IndexedRangeGlyphMapping<1> = IndexedRangeGlyphMapping::new([' '..='~'], 0);
+
+    const FONTS: &[FallbackFont] = &[
+        FallbackFont::new(&PRIMARY, &PRIMARY_MAPPING),
+        FallbackFont::new(&FONT_6X10, &ASCII),
+    ];
+
+    #[test]
+    fn test_font_order() {
+        let style = FallbackTextStyle::new(FONTS, BinaryColor::On);
+
+        assert!(core::ptr::eq(style.font('中'), &PRIMARY));
+        assert!(core::ptr::eq(style.font('A'), &FONT_6X10));
+
+        // Characters that none of the fonts cover use the primary font
+        assert!(core::ptr::eq(style.font('国'), &PRIMARY));
+    }
+
+    #[test]
+    fn test_measure_string() {
+        let style = FallbackTextStyle::new(FONTS, BinaryColor::On);
+        let metrics = style.measure_string("中A国", Point::new(0, 7), Baseline::Alphabetic);
+
+        assert_eq!(metrics.next_position, Point::new(4 + 6 + 4, 7));
+        assert_eq!(metrics.bounding_box.top_left, Point::zero());
+        // The baseline of `FONT_6X10` is the lowest, at row 7, and it has 3
+        // rows below the baseline, where the primary font only has 2.
+        assert_eq!(metrics.bounding_box.size, Size::new(14, 10));
+    }
+
+    #[test]
+    fn test_shared_baseline() {
+        let style = FallbackTextStyle::new(FONTS, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        Text::with_baseline("中I", Point::new(0, 7), style, Baseline::Alphabetic)
+            .draw(&mut display)
+            .unwrap();
+
+        // `I` sits on row 7, which is also the primary font's baseline.
+        display.assert_pattern(&[
+            "          ",
+            "     ###  ",
+            "      #   ",
+            "####  #   ",
+            "####  #   ",
+            "####  #   ",
+            "####  #   ",
+            "#### ###  ",
+            "####      ",
+            "          ",
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one font")]
+    fn test_no_fonts() {
+        FallbackTextStyle::new(&[], BinaryColor::On);
+    }
+}
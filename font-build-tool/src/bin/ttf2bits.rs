@@ -1,11 +1,14 @@
-use clap::{ArgEnum, Parser};
+use clap::{ArgEnum, Args, Parser, Subcommand};
 use embedded_graphics_cjk_font_build_tool::{
-    BuildError, FontOutputSettings, MonoFontBuilder, UnicodeCodeBlock, CJK_RADICALS_SUPPLEMENT,
-    CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK,
+    parse_byte_range, read_charset, unicode_block_by_name, unicode_blocks_by_name, BitDepth,
+    BuildError, CodePageTable, CompressionStats, CoverageReport, FontOutputSettings,
+    GlyphMappingSource, Manifest, MissingGlyphPolicy, MonoFontBuilder, UnicodeCodeBlock,
+    CJK_RADICALS_SUPPLEMENT, CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK, DEFAULT_ALIGNMENT,
+    UNICODE_CODE_BLOCKS,
 };
+use std::{fs, ops::RangeInclusive, process};
 
-const UNICODE_CODE_BLOCKS: &[UnicodeCodeBlock] = &[
-    UnicodeCodeBlock::new('?', '?'),
+const DEFAULT_UNICODE_CODE_BLOCKS: &[UnicodeCodeBlock] = &[
     CJK_RADICALS_SUPPLEMENT,
     CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK,
 ];
@@ -13,6 +16,38 @@ const UNICODE_CODE_BLOCKS: &[UnicodeCodeBlock] = &[
 #[derive(Parser, Debug)]
 #[clap(name = "ttf2bits", author, version, about, propagate_version = true)]
 struct Ttf2Bits {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generates a single font from a TTF file.
+    Generate(Generate),
+
+    /// Generates every font listed in a manifest.
+    Build(Build),
+
+    /// Reports how many characters in each unicode code block a font has
+    /// glyphs for.
+    Coverage(Coverage),
+
+    /// Reports how much smaller the raw data of existing fonts would be if
+    /// each glyph was compressed.
+    Compression(Compression),
+
+    /// Packs every font listed in a manifest into a single image, to be
+    /// written to external flash.
+    Pack(Pack),
+
+    /// Generates the table of a double-byte code page, like GBK, from a
+    /// unicode.org mapping file, for the `embedded-graphics-cjk-encoding`
+    /// crate.
+    CodePage(CodePage),
+}
+
+#[derive(Args, Debug)]
+struct Generate {
     font_path: String,
 
     output_prefix: String,
@@ -26,40 +61,187 @@ struct Ttf2Bits {
     #[clap(long = "intensity-threshold", default_value = "128")]
     intensity_threshold: u8,
 
+    /// Bits per pixel (1, 2, 4, or 8). Anything above 1 generates an
+    /// anti-aliased `GrayFont`.
+    #[clap(long = "bit-depth", default_value = "1")]
+    bit_depth: u8,
+
     #[clap(short = 's', long = "size")]
     font_sizes: Vec<u32>,
+
+    /// Unicode code blocks to include, by name (for example `hiragana`, or
+    /// `cjk-unified-ideographs`).
+    #[clap(short = 'b', long = "block")]
+    blocks: Vec<String>,
+
+    /// Only include the characters used in these UTF-8 text files, instead
+    /// of the default unicode code blocks.
+    #[clap(long = "charset-from")]
+    charset_files: Vec<String>,
+
+    /// What to do with characters that the font has no glyph for.
+    #[clap(arg_enum, long = "missing-glyphs", default_value = "fallback")]
+    missing_glyphs: MissingGlyphs,
+
+    /// Only include half-width characters, to generate a companion for a
+    /// full-width CJK font from the same TTF file.
+    #[clap(long = "half-width")]
+    half_width: bool,
+
+    /// Only include the vertical alternates of punctuation, brackets, and the
+    /// long vowel mark, from the font's `vert`, and `vrt2` features.
+    /// Proportional fonts can't be vertical.
+    #[clap(long = "vertical")]
+    vertical: bool,
+}
+
+#[derive(Args, Debug)]
+struct Build {
+    /// Path to the TOML manifest describing the fonts to generate.
+    #[clap(short = 'm', long = "manifest", default_value = "fonts.toml")]
+    manifest_path: String,
+}
+
+#[derive(Args, Debug)]
+struct Coverage {
+    font_path: String,
+
+    /// Index of the face to check, for font files containing multiple faces.
+    #[clap(long = "face-index", default_value = "0")]
+    face_index: isize,
+
+    /// Unicode code blocks to check, by name. Every known block is checked
+    /// by default.
+    #[clap(short = 'b', long = "block")]
+    blocks: Vec<String>,
+
+    #[clap(arg_enum, long = "format", default_value = "text")]
+    format: ReportFormat,
+}
+
+#[derive(Args, Debug)]
+struct Compression {
+    /// Raw data files written by `generate`, or `build`.
+    #[clap(required = true)]
+    raw_files: Vec<String>,
+
+    #[clap(long = "glyph-width")]
+    glyph_width: usize,
+
+    #[clap(long = "glyph-height")]
+    glyph_height: usize,
+}
+
+#[derive(Args, Debug)]
+struct Pack {
+    /// Path to the TOML manifest describing the fonts to pack.
+    #[clap(short = 'm', long = "manifest", default_value = "fonts.toml")]
+    manifest_path: String,
+
+    /// Path of the image, relative to the manifest.
+    #[clap(short = 'o', long = "output", default_value = "fonts.img")]
+    image_path: String,
+
+    /// Alignment of each font's glyph data, usually the flash's erase sector
+    /// size.
+    #[clap(long = "align", default_value_t = DEFAULT_ALIGNMENT)]
+    alignment: u32,
+}
+
+#[derive(Args, Debug)]
+struct CodePage {
+    /// Mapping file, like `CP936.TXT`.
+    mapping_path: String,
+
+    /// Path of the table.
+    #[clap(short = 'o', long = "output")]
+    output_path: String,
+
+    /// Range of lead bytes in hex, like `81-FE`. Can be repeated for lead
+    /// bytes in several ranges.
+    #[clap(long = "lead", required = true, parse(try_from_str = parse_byte_range))]
+    lead_bytes: Vec<RangeInclusive<u8>>,
+
+    /// Range of trail bytes in hex, like `40-7E`. Can be repeated for trail
+    /// bytes in several ranges.
+    #[clap(long = "trail", required = true, parse(try_from_str = parse_byte_range))]
+    trail_bytes: Vec<RangeInclusive<u8>>,
 }
 
-impl Ttf2Bits {
+impl Generate {
     fn run(self) -> Result<(), BuildError> {
-        let mono_font_builder = MonoFontBuilder::new(self.font_path, UNICODE_CODE_BLOCKS)?;
+        let bit_depth = BitDepth::try_from(self.bit_depth)?;
+        let unicode_blocks = unicode_blocks_by_name(&self.blocks)?;
+        let glyph_mapping = if !self.charset_files.is_empty() {
+            let charset = read_charset(&self.charset_files)?;
+            let block_chars = unicode_blocks.iter().flat_map(|block| block.range());
+
+            GlyphMappingSource::from_chars(charset.into_iter().chain(block_chars))
+        } else if !unicode_blocks.is_empty() {
+            GlyphMappingSource::from_blocks(&unicode_blocks)
+        } else {
+            GlyphMappingSource::from_blocks(DEFAULT_UNICODE_CODE_BLOCKS)
+        };
+        let glyph_mapping = if self.half_width {
+            glyph_mapping.half_width()
+        } else {
+            glyph_mapping
+        };
+        let mono_font_builder =
+            MonoFontBuilder::with_glyph_mapping(self.font_path, 0, glyph_mapping)?;
+        let build = |settings| {
+            if self.vertical {
+                mono_font_builder.build_vertical(settings)
+            } else {
+                mono_font_builder.build(settings)
+            }
+        };
+
+        if self.vertical && matches!(self.output_format, OutputFormat::ProportionalRs) {
+            return Err(BuildError::VerticalProportionalFont);
+        }
 
         for font_size in self.font_sizes {
             let settings = FontOutputSettings {
                 font_size,
                 intensity_threshold: self.intensity_threshold,
+                bit_depth,
+                missing_glyphs: self.missing_glyphs.into(),
             };
-            let bitmap = mono_font_builder.build(settings)?;
+            let bitmap_file = format!("{}-{}.bin", self.output_prefix, font_size);
+            let rust_source_path = format!(
+                "{}/{}_{}.rs",
+                self.output_directory, self.output_prefix, font_size
+            );
 
             match self.output_format {
                 OutputFormat::Rs => {
-                    let bitmap_file = format!("{}-{}.bin", self.output_prefix, font_size);
+                    let bitmap = build(settings)?;
 
                     bitmap.save_raw(format!("{}/data/{}", self.output_directory, bitmap_file))?;
-                    bitmap.save_rust_source(
-                        format!(
-                            "{}/{}_{}.rs",
-                            self.output_directory, self.output_prefix, font_size
-                        ),
-                        format!("data/{}", bitmap_file),
-                    )?;
+                    bitmap.save_rust_source(rust_source_path, format!("data/{}", bitmap_file))?;
                 }
                 OutputFormat::Png => {
+                    let bitmap = build(settings)?;
+
                     bitmap.save_png(format!(
                         "{}/{}-{}.png",
                         self.output_directory, self.output_prefix, font_size
                     ))?;
                 }
+                OutputFormat::CompressedRs => {
+                    let font = build(settings)?.compress()?;
+
+                    println!("{}", font.stats());
+                    font.save_raw(format!("{}/data/{}", self.output_directory, bitmap_file))?;
+                    font.save_rust_source(rust_source_path, format!("data/{}", bitmap_file))?;
+                }
+                OutputFormat::ProportionalRs => {
+                    let font = mono_font_builder.build_proportional(settings)?;
+
+                    font.save_raw(format!("{}/data/{}", self.output_directory, bitmap_file))?;
+                    font.save_rust_source(rust_source_path, format!("data/{}", bitmap_file))?;
+                }
             }
         }
 
@@ -67,16 +249,119 @@ impl Ttf2Bits {
     }
 }
 
+impl Build {
+    fn run(self) -> Result<(), BuildError> {
+        Manifest::from_path(self.manifest_path)?.build()
+    }
+}
+
+impl Pack {
+    fn run(self) -> Result<(), BuildError> {
+        Manifest::from_path(self.manifest_path)?.pack(self.image_path, self.alignment)
+    }
+}
+
+impl Coverage {
+    fn run(self) -> Result<(), BuildError> {
+        let unicode_blocks = if self.blocks.is_empty() {
+            UNICODE_CODE_BLOCKS.to_vec()
+        } else {
+            self.blocks
+                .iter()
+                .map(|name| {
+                    unicode_block_by_name(name)
+                        .map(|block| (name.as_str(), block))
+                        .ok_or_else(|| BuildError::UnknownUnicodeBlock(name.clone()))
+                })
+                .collect::<Result<_, _>>()?
+        };
+        let report = CoverageReport::from_font(self.font_path, self.face_index, &unicode_blocks)?;
+
+        match self.format {
+            ReportFormat::Text => print!("{}", report),
+            ReportFormat::Json => println!("{}", report.to_json()),
+        }
+
+        Ok(())
+    }
+}
+
+impl Compression {
+    fn run(self) -> Result<(), BuildError> {
+        for raw_file in &self.raw_files {
+            let raw = fs::read(raw_file)?;
+            let stats = CompressionStats::from_raw(&raw, self.glyph_width, self.glyph_height)?;
+
+            println!("{}: {}", raw_file, stats);
+        }
+
+        Ok(())
+    }
+}
+
+impl CodePage {
+    fn run(self) -> Result<(), BuildError> {
+        let table = CodePageTable::from_mapping_file(
+            self.mapping_path,
+            &self.lead_bytes,
+            &self.trail_bytes,
+        )?;
+
+        println!("{} characters", table.len());
+        table.save_raw(self.output_path)
+    }
+}
+
 #[derive(ArgEnum, Copy, Clone, Debug)]
 enum OutputFormat {
     Rs,
     Png,
+
+    /// A `ProportionalFont`, which keeps each glyph's own advance.
+    ProportionalRs,
+
+    /// A `CompressedFont`, where each glyph is compressed on its own.
+    CompressedRs,
+}
+
+#[derive(ArgEnum, Copy, Clone, Debug)]
+enum ReportFormat {
+    Text,
+    Json,
+}
+
+#[derive(ArgEnum, Copy, Clone, Debug)]
+enum MissingGlyphs {
+    Fallback,
+    Skip,
+    Error,
+}
+
+impl From<MissingGlyphs> for MissingGlyphPolicy {
+    fn from(missing_glyphs: MissingGlyphs) -> MissingGlyphPolicy {
+        match missing_glyphs {
+            MissingGlyphs::Fallback => MissingGlyphPolicy::Fallback,
+            MissingGlyphs::Skip => MissingGlyphPolicy::Skip,
+            MissingGlyphs::Error => MissingGlyphPolicy::Error,
+        }
+    }
 }
 
 impl OutputFormat {}
 
-fn main() -> Result<(), BuildError> {
+fn main() {
     let program = Ttf2Bits::parse();
+    let result = match program.command {
+        Command::Generate(generate) => generate.run(),
+        Command::Build(build) => build.run(),
+        Command::Coverage(coverage) => coverage.run(),
+        Command::Compression(compression) => compression.run(),
+        Command::Pack(pack) => pack.run(),
+        Command::CodePage(code_page) => code_page.run(),
+    };
 
-    program.run()
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
 }
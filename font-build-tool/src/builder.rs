@@ -1,11 +1,32 @@
-use crate::{error::BuildError, unicode::UnicodeCodeBlock};
-use freetype::{face::LoadFlag, Face, Library};
-use image::{EncodableLayout, ImageBuffer, ImageResult, Luma, PixelWithColorType};
-use std::{cmp::max, ffi::OsStr, fmt::Display, fs, io, ops::Deref, path::Path};
+use crate::{
+    charset::read_charset,
+    error::BuildError,
+    gsub::{self, has_vertical_form},
+    mapping::{DedupMappingSource, GlyphMappingSource, FALLBACK_CHAR},
+    unicode::UnicodeCodeBlock,
+};
+use freetype::{face::LoadFlag, Face, GlyphSlot, Library};
+use image::{EncodableLayout, GrayImage, ImageBuffer, ImageResult, Luma, PixelWithColorType};
+use serde::Deserialize;
+use std::{
+    cmp::max,
+    collections::{hash_map::Entry, BTreeSet, HashMap},
+    ffi::OsStr,
+    fmt::Display,
+    fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
 /// The number of glyphs to include on a single line in the final bitmap.
-const ROW_SIZE: usize = 32;
+pub(crate) const ROW_SIZE: usize = 32;
 
+/// Number of rows in the final bitmap needed to fit every glyph.
+fn num_rows(num_glyphs: usize) -> usize {
+    num_glyphs.div_ceil(ROW_SIZE)
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct FontOutputSettings {
     /// The target font size.
     pub font_size: u32,
@@ -13,181 +34,649 @@ pub struct FontOutputSettings {
     /// Threshold for when to select a pixel when adding it to the bitmap.
     /// A reasonable value for this would be 128, meaning anything above 50%
     /// intensity will appear in the final bitmap (max threshold = 255).
+    ///
+    /// This is only used for 1 bpp output. Gray output stores the intensity.
     pub intensity_threshold: u8,
+
+    /// Number of bits used to store each pixel of the generated bitmap.
+    pub bit_depth: BitDepth,
+
+    /// What to do with characters that the font doesn't have a glyph for.
+    pub missing_glyphs: MissingGlyphPolicy,
 }
 
-pub struct MonoFontBuilder<'a> {
+/// Determines how characters that the font has no glyph for (glyph index 0)
+/// are handled. Without a policy, FreeType renders the font's `.notdef`
+/// glyph, which usually shows up as an empty box.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MissingGlyphPolicy {
+    /// Characters display the fallback glyph. They're removed from sparse
+    /// mappings, and rendered as a copy of the fallback glyph in ranges.
+    #[default]
+    Fallback,
+
+    /// Characters are removed from the glyph mapping. Ranges are split
+    /// around each missing character.
+    Skip,
+
+    /// The build fails with [`BuildError::MissingGlyph`].
+    Error,
+}
+
+/// Number of bits per pixel in the generated bitmap. Fonts with a bit depth
+/// above 1 are anti-aliased, and are drawn with the
+/// `embedded-graphics-cjk-gray-font` crate, rather than as a `MonoFont`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "u8")]
+pub enum BitDepth {
+    /// Each pixel is either on, or off.
+    #[default]
+    One,
+
+    /// 4 levels of gray.
+    Two,
+
+    /// 16 levels of gray.
+    Four,
+
+    /// 256 levels of gray.
+    Eight,
+}
+
+impl BitDepth {
+    /// Number of bits used for each pixel.
+    pub fn bits(self) -> u8 {
+        match self {
+            BitDepth::One => 1,
+            BitDepth::Two => 2,
+            BitDepth::Four => 4,
+            BitDepth::Eight => 8,
+        }
+    }
+
+    /// Returns `true` if the font is anti-aliased.
+    pub fn is_gray(self) -> bool {
+        self != BitDepth::One
+    }
+
+    /// Scales an 8-bit intensity down to the bit depth, rounding to the
+    /// nearest level.
+    fn quantize(self, intensity: u8) -> u8 {
+        let max = (1u16 << self.bits()) - 1;
+
+        ((u16::from(intensity) * max + 0x7F) / 0xFF) as u8
+    }
+}
+
+impl TryFrom<u8> for BitDepth {
+    type Error = BuildError;
+
+    fn try_from(bits: u8) -> Result<BitDepth, BuildError> {
+        match bits {
+            1 => Ok(BitDepth::One),
+            2 => Ok(BitDepth::Two),
+            4 => Ok(BitDepth::Four),
+            8 => Ok(BitDepth::Eight),
+            _ => Err(BuildError::InvalidBitDepth(bits)),
+        }
+    }
+}
+
+/// Opens a face in a font file.
+pub(crate) fn load_face<P>(ttf_path: P, face_index: isize) -> Result<(Library, Face), BuildError>
+where
+    P: AsRef<OsStr>,
+{
+    let lib = Library::init().map_err(BuildError::InitError)?;
+    let font = lib
+        .new_face(&ttf_path, face_index)
+        .map_err(|source| BuildError::ReadFontError {
+            path: PathBuf::from(ttf_path.as_ref()),
+            source,
+        })?;
+
+    Ok((lib, font))
+}
+
+pub struct MonoFontBuilder {
     _lib: Library,
 
     font: Face,
 
-    /// The unicode character blocks to generate bitmaps for.
-    unicode_blocks: &'a [UnicodeCodeBlock],
+    /// Font file, and index of the face. Tables that FreeType doesn't expose
+    /// are read from the file.
+    path: PathBuf,
+    face_index: isize,
+
+    /// The characters to generate bitmaps for, and how they're mapped to
+    /// glyphs.
+    glyph_mapping: GlyphMappingSource,
 }
 
-impl<'a> MonoFontBuilder<'a> {
+impl MonoFontBuilder {
+    /// Returns a builder for every character in each of the unicode code
+    /// blocks.
     pub fn new<P>(
         ttf_path: P,
-        unicode_blocks: &'a [UnicodeCodeBlock],
-    ) -> Result<MonoFontBuilder<'a>, BuildError>
+        unicode_blocks: &[UnicodeCodeBlock],
+    ) -> Result<MonoFontBuilder, BuildError>
     where
         P: AsRef<OsStr>,
     {
-        let lib = Library::init().unwrap();
-        let font = lib.new_face(ttf_path, 0).unwrap();
+        Self::with_glyph_mapping(ttf_path, 0, GlyphMappingSource::from_blocks(unicode_blocks))
+    }
+
+    /// Returns a builder for an arbitrary set of characters, rather than
+    /// whole unicode code blocks. The generated font uses a
+    /// `SparseGlyphMapping`, and always includes the fallback character.
+    pub fn with_chars<P, I>(ttf_path: P, chars: I) -> Result<MonoFontBuilder, BuildError>
+    where
+        P: AsRef<OsStr>,
+        I: IntoIterator<Item = char>,
+    {
+        Self::with_glyph_mapping(ttf_path, 0, GlyphMappingSource::from_chars(chars))
+    }
+
+    /// Returns a builder for only the characters that are used in each of
+    /// the UTF-8 text files. This is useful when the text a device displays
+    /// is known ahead of time.
+    pub fn with_charset_files<P, I, Q>(
+        ttf_path: P,
+        text_files: I,
+    ) -> Result<MonoFontBuilder, BuildError>
+    where
+        P: AsRef<OsStr>,
+        I: IntoIterator<Item = Q>,
+        Q: AsRef<Path>,
+    {
+        Self::with_chars(ttf_path, read_charset(text_files)?)
+    }
+
+    /// Returns a builder for the characters covered by the glyph mapping,
+    /// using a specific face in the font file. Most font files only contain
+    /// a single face, with an index of 0.
+    pub fn with_glyph_mapping<P>(
+        ttf_path: P,
+        face_index: isize,
+        glyph_mapping: GlyphMappingSource,
+    ) -> Result<MonoFontBuilder, BuildError>
+    where
+        P: AsRef<OsStr>,
+    {
+        let (lib, font) = load_face(&ttf_path, face_index)?;
 
         Ok(MonoFontBuilder {
             _lib: lib,
             font,
-            unicode_blocks,
+            path: PathBuf::from(ttf_path.as_ref()),
+            face_index,
+            glyph_mapping,
         })
     }
 
-    /// Returns an iterator over characters in each unicode code block.
-    fn chars_iter(&self) -> impl Iterator<Item = char> + 'a {
-        self.unicode_blocks.iter().flat_map(|block| block.range())
+    /// Returns the characters covered by the glyph mapping that the font
+    /// doesn't have a glyph for.
+    pub fn missing_chars(&self) -> BTreeSet<char> {
+        self.glyph_mapping
+            .chars()
+            .filter(|chr| self.font.get_char_index(*chr as usize) == 0)
+            .collect()
     }
 
-    /// Number of characters covered by all code blocks.
-    fn num_chars(&self) -> usize {
-        self.unicode_blocks
-            .iter()
-            .map(UnicodeCodeBlock::block_size)
-            .sum()
+    /// Loads, and renders the glyph for a character into the face's glyph
+    /// slot.
+    pub(crate) fn load_char(&self, chr: char) -> Result<&GlyphSlot, BuildError> {
+        self.font
+            .load_char(chr as usize, LoadFlag::RENDER)
+            .map_err(|source| BuildError::RenderGlyphError {
+                codepoint: chr,
+                source,
+            })?;
+
+        Ok(self.font.glyph())
     }
 
-    /// Renders glyphs for each of the selected fonts, then stores it in a
-    /// bitmap that can be exported as PNG, BBP, or source code compatible with
-    /// the embedded-graphics library.
-    pub fn build(
+    /// Loads, and renders a glyph by its index in the font, rather than by
+    /// character. `chr` is the character the glyph is rendered for.
+    fn load_glyph(&self, chr: char, glyph_index: u32) -> Result<&GlyphSlot, BuildError> {
+        self.font
+            .load_glyph(glyph_index, LoadFlag::RENDER)
+            .map_err(|source| BuildError::RenderGlyphError {
+                codepoint: chr,
+                source,
+            })?;
+
+        Ok(self.font.glyph())
+    }
+
+    /// Returns the face's ascender, and descender at the current size, in
+    /// pixels, rounded up.
+    pub(crate) fn size_metrics(&self) -> (usize, usize) {
+        match self.font.size_metrics() {
+            Some(metrics) => (
+                (metrics.ascender.max(0) as usize).div_ceil(64),
+                ((-metrics.descender).max(0) as usize).div_ceil(64),
+            ),
+            None => (0, 0),
+        }
+    }
+
+    /// Scales a distance in font units to pixels at the current size.
+    fn font_units_to_pixels(&self, units: i16) -> f64 {
+        match (self.font.size_metrics(), self.font.em_size()) {
+            (Some(metrics), em_size) if em_size > 0 => {
+                f64::from(units) * f64::from(metrics.y_ppem) / f64::from(em_size)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Computes the baseline, and decoration positions for a cell with
+    /// `ascent` rows above the baseline, and `height` rows in total.
+    /// Decorations that would extend past the bottom of the cell are moved
+    /// up into it.
+    pub(crate) fn font_metrics(&self, ascent: usize, height: usize) -> FontMetrics {
+        // The last row above the baseline, which is where embedded-graphics
+        // expects the bottom of a capital letter.
+        let baseline = ascent.saturating_sub(1);
+
+        // FreeType reports the position of the center of the underline, with
+        // negative values below the baseline.
+        let thickness = self
+            .font_units_to_pixels(self.font.underline_thickness())
+            .round()
+            .max(1.0);
+        let underline_top =
+            -self.font_units_to_pixels(self.font.underline_position()) - thickness / 2.0;
+        let underline = Decoration::clamped(
+            ascent + underline_top.round().max(0.0) as usize,
+            thickness as usize,
+            height,
+        );
+
+        // The strikethrough is centered on half of the x-height, falling back
+        // to a third of the ascent for fonts without a lowercase x.
+        let x_height = match self.font.get_char_index('x' as usize) {
+            0 => ascent * 2 / 3,
+            _ => match self.font.load_char('x' as usize, LoadFlag::DEFAULT) {
+                Ok(()) => (self.font.glyph().metrics().horiBearingY.max(0) as usize + 32) / 64,
+                Err(_) => ascent * 2 / 3,
+            },
+        };
+        let strikethrough = Decoration::clamped(
+            ascent.saturating_sub(x_height / 2 + underline.height.div_ceil(2)),
+            underline.height,
+            height,
+        );
+
+        FontMetrics {
+            baseline,
+            underline,
+            strikethrough,
+        }
+    }
+
+    /// Sets the font size, and applies the missing glyph policy. Returns the
+    /// glyph mapping of the generated font, and a function that returns the
+    /// character whose glyph is rendered for each character in the mapping.
+    /// Characters that are still in the mapping, but missing from the font
+    /// are rendered with the fallback glyph.
+    pub(crate) fn prepare(
         &self,
-        settings: FontOutputSettings,
-    ) -> Result<MonoFontData<ImageBuffer<Luma<u8>, Vec<u8>>>, BuildError> {
+        settings: &FontOutputSettings,
+    ) -> Result<(GlyphMappingSource, impl Fn(char) -> char), BuildError> {
         println!(
             "font.family_name={:?} font.style_name={:?}",
             self.font.family_name(),
             self.font.style_name()
         );
 
+        // FreeType treats a size of 0 as 1, rather than reporting an error.
+        if settings.font_size == 0 {
+            return Err(BuildError::InvalidFontSize(settings.font_size));
+        }
+
         self.font
-            .set_pixel_sizes(0, settings.font_size as u32)
-            .unwrap();
+            .set_pixel_sizes(0, settings.font_size)
+            .map_err(|source| BuildError::FontSizeError {
+                size: settings.font_size,
+                source,
+            })?;
+
+        let missing_chars = self.missing_chars();
+
+        if !missing_chars.is_empty() {
+            println!("missing_glyphs={}", missing_chars.len());
+        }
+
+        let glyph_mapping = match settings.missing_glyphs {
+            MissingGlyphPolicy::Error => match missing_chars.iter().next() {
+                Some(&codepoint) => return Err(BuildError::MissingGlyph { codepoint }),
+                None => self.glyph_mapping.clone(),
+            },
+            MissingGlyphPolicy::Fallback => match self.glyph_mapping {
+                GlyphMappingSource::Ranges(_) => self.glyph_mapping.clone(),
+                GlyphMappingSource::Sparse(_) => self.glyph_mapping.without(&missing_chars),
+            },
+            MissingGlyphPolicy::Skip => self.glyph_mapping.without(&missing_chars),
+        };
+        let glyph_char = move |chr| {
+            if missing_chars.contains(&chr) {
+                FALLBACK_CHAR
+            } else {
+                chr
+            }
+        };
+
+        Ok((glyph_mapping, glyph_char))
+    }
+
+    /// Renders glyphs for each of the selected fonts, then stores it in a
+    /// bitmap that can be exported as PNG, BBP, or source code compatible with
+    /// the embedded-graphics library.
+    pub fn build(
+        &self,
+        settings: FontOutputSettings,
+    ) -> Result<MonoFontData<GrayImage>, BuildError> {
+        let (glyph_mapping, glyph_char) = self.prepare(&settings)?;
+        let cell = self.cell_metrics(&glyph_mapping, &glyph_char)?;
+
+        self.rasterize(settings, cell, glyph_mapping, |chr| {
+            self.load_char(glyph_char(chr))
+        })
+    }
+
+    /// Renders the vertical alternates of punctuation, brackets, and the
+    /// long vowel mark, from the font's OpenType `vert`, and `vrt2`
+    /// substitutions. The glyphs use the same cell as the font generated by
+    /// [`MonoFontBuilder::build`], so they can be drawn in its place in
+    /// vertical text. The glyph mapping only covers characters that have an
+    /// alternate, and the fallback character.
+    pub fn build_vertical(
+        &self,
+        settings: FontOutputSettings,
+    ) -> Result<MonoFontData<GrayImage>, BuildError> {
+        let (glyph_mapping, glyph_char) = self.prepare(&settings)?;
+        let cell = self.cell_metrics(&glyph_mapping, &glyph_char)?;
+        let substitutions = self.vertical_substitutions()?;
+        let alternates: HashMap<char, u32> = glyph_mapping
+            .chars()
+            .filter(|chr| has_vertical_form(*chr))
+            .filter_map(|chr| {
+                let glyph_index = u16::try_from(self.font.get_char_index(chr as usize)).ok()?;
 
-        // Determines the maximum glyph height and glyph width based on the
-        // glyph metrics for each chosen character.
-        let (max_glyph_height, max_glyph_width) = self
-            .chars_iter()
-            .map(|chr| {
-                self.font.load_char(chr as usize, LoadFlag::RENDER).unwrap();
-
-                let glyph = self.font.glyph();
-                let metrics = glyph.metrics();
-                let bitmap = glyph.bitmap();
-
-                (
-                    std::cmp::max(bitmap.rows() as usize, metrics.vertAdvance as usize / 64)
-                        .checked_add_signed(bitmap.rows() as isize - glyph.bitmap_top() as isize)
-                        .unwrap(),
-                    metrics.horiAdvance as usize / 64,
-                )
+                substitutions
+                    .get(&glyph_index)
+                    .map(|alternate| (chr, u32::from(*alternate)))
             })
-            .reduce(
-                |(max_glyph_height, max_glyph_width), (glyph_height, glyph_width)| {
-                    (
-                        max(max_glyph_height, glyph_height),
-                        max(max_glyph_width, glyph_width),
-                    )
-                },
-            )
-            .expect("expected at least one character");
+            .collect();
+
+        if alternates.is_empty() {
+            return Err(BuildError::NoVerticalAlternates);
+        }
+
+        println!("vertical_alternates={}", alternates.len());
+
+        let vertical_mapping = GlyphMappingSource::from_chars(alternates.keys().copied());
+
+        self.rasterize(settings, cell, vertical_mapping, |chr| {
+            match alternates.get(&chr) {
+                Some(glyph_index) => self.load_glyph(chr, *glyph_index),
+                None => self.load_char(glyph_char(chr)),
+            }
+        })
+    }
+
+    /// Reads the `vert`, and `vrt2` substitutions from the font file. Fonts
+    /// without a `GSUB` table don't have any.
+    fn vertical_substitutions(&self) -> Result<HashMap<u16, u16>, BuildError> {
+        let font_file = fs::read(&self.path)?;
+
+        Ok(
+            gsub::sfnt_table(&font_file, self.face_index as usize, b"GSUB")
+                .map(gsub::vertical_substitutions)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Determines the cell size from the glyph metrics for each chosen
+    /// character. The face's ascender and descender are the minimum, so the
+    /// baseline is consistent across sizes, and fonts. Glyphs that extend
+    /// past them (for example accented capitals) grow the cell. The glyph
+    /// mapping always contains at least the fallback character.
+    fn cell_metrics(
+        &self,
+        glyph_mapping: &GlyphMappingSource,
+        glyph_char: impl Fn(char) -> char,
+    ) -> Result<CellMetrics, BuildError> {
+        let (mut ascent, mut descent) = self.size_metrics();
+        let mut width = 0;
+
+        for chr in glyph_mapping.chars().map(glyph_char) {
+            let glyph = self.load_char(chr)?;
+            let bitmap = glyph.bitmap();
+
+            ascent = max(ascent, glyph.bitmap_top().max(0) as usize);
+            descent = max(
+                descent,
+                (bitmap.rows() - glyph.bitmap_top()).max(0) as usize,
+            );
+            width = max(width, glyph.metrics().horiAdvance as usize / 64);
+        }
+
+        let metrics = self.font_metrics(ascent, ascent + descent);
 
         println!(
-            "max_glyph_height={} max_glyph_width={}",
-            max_glyph_height, max_glyph_width
+            "max_glyph_height={} max_glyph_width={} baseline={}",
+            ascent + descent,
+            width,
+            metrics.baseline
         );
 
-        // Image buffer that contains every glyph specified in rows of ROW_SIZE.
-        let mut imgbuf = image::GrayImage::new(
-            (max_glyph_width * ROW_SIZE) as u32,
-            (max_glyph_height * ((self.num_chars() - 1) / ROW_SIZE + 1)) as u32,
-        );
+        Ok(CellMetrics {
+            ascent,
+            width,
+            height: ascent + descent,
+            metrics,
+        })
+    }
 
-        // Rasterizes the font, and copies the bitmap onto the image buffer.
-        for (index, chr) in self.chars_iter().enumerate() {
-            self.font.load_char(chr as usize, LoadFlag::RENDER).unwrap();
+    /// Rasterizes the glyph loaded by `load` for each character in the glyph
+    /// mapping into a cell, and stores the cells in a bitmap.
+    fn rasterize<'a, F>(
+        &'a self,
+        settings: FontOutputSettings,
+        cell: CellMetrics,
+        glyph_mapping: GlyphMappingSource,
+        load: F,
+    ) -> Result<MonoFontData<GrayImage>, BuildError>
+    where
+        F: Fn(char) -> Result<&'a GlyphSlot, BuildError>,
+    {
+        let CellMetrics {
+            ascent,
+            width: max_glyph_width,
+            height: max_glyph_height,
+            metrics,
+        } = cell;
 
-            let glyph = self.font.glyph();
-            let metrics = glyph.metrics();
+        // Rasterizes the font into a cell for each glyph. Glyphs that look
+        // the same as an earlier glyph, like characters missing from the font,
+        // are only stored once.
+        let cell_size = max_glyph_width * max_glyph_height;
+        let mut cells: Vec<Vec<u8>> = Vec::new();
+        let mut unique_cells = HashMap::new();
+        let mut duplicates = Vec::new();
+
+        for (slot, chr) in glyph_mapping.chars().enumerate() {
+            let glyph = load(chr)?;
             let bitmap = glyph.bitmap();
+            let mut cell = vec![0; cell_size];
 
-            /*
-            println!(
-                "[{: >8}: {}] bitmap.width = {: >3} \
-                bitmap.rows = {: >3} \
-                glyph.left = {: >3} \
-                glyph.top = {: >3} \
-                glyph.v_advance = {: >3} \
-                glyph.h_advance = {: >3} \
-                bitmap.len = {: >6} \
-                bitmap.pixel_mode = {:?}",
-                index,
-                chr,
-                bitmap.width(),
-                bitmap.rows(),
-                glyph.bitmap_left(),
-                glyph.bitmap_top(),
-                metrics.vertAdvance as usize / 64,
-                metrics.horiAdvance as usize / 64,
-                bitmap.buffer().len(),
-                bitmap.pixel_mode().unwrap()
-            );
-            */
-
-            let col = index % ROW_SIZE;
-            let row = index / ROW_SIZE;
-            let img_x = col as isize * max_glyph_width as isize;
-            let img_y = row as isize * max_glyph_height as isize;
-            let img_x_offset = glyph.bitmap_left() as usize;
-            let img_y_offset = (max_glyph_height - glyph.bitmap_top() as usize) / 2;
+            // Glyphs are positioned relative to the pen position on the
+            // baseline. `bitmap_top` is the number of rows above the baseline.
+            let glyph_x = glyph.bitmap_left() as isize;
+            let glyph_y = ascent as isize - glyph.bitmap_top() as isize;
             let cols = bitmap.width() as usize;
 
-            // Copy onto image
+            // Copy onto cell
             for y in 0..bitmap.rows() as usize {
-                for x in 0..bitmap.width() as usize {
+                for x in 0..cols {
                     let val = bitmap.buffer()[y * cols + x];
+                    let intensity = match settings.bit_depth {
+                        BitDepth::One if val > settings.intensity_threshold => 0xFF,
+                        BitDepth::One => continue,
+                        _ => val,
+                    };
+                    let cell_x = glyph_x + x as isize;
+                    let cell_y = glyph_y + y as isize;
 
-                    if val > settings.intensity_threshold {
-                        let pixel_x = img_x + x as isize + img_x_offset as isize;
-                        let pixel_y = img_y + y as isize + img_y_offset as isize;
-                        if pixel_x > 0 && pixel_y > 0 {
-                            imgbuf.put_pixel(pixel_x as u32, pixel_y as u32, Luma([0xFF]));
-                        }
+                    // Pixels outside of the cell would bleed into the
+                    // neighbouring glyphs.
+                    if intensity > 0
+                        && (0..max_glyph_width as isize).contains(&cell_x)
+                        && (0..max_glyph_height as isize).contains(&cell_y)
+                    {
+                        cell[cell_y as usize * max_glyph_width + cell_x as usize] = intensity;
                     }
                 }
             }
+
+            // Cells are compared after quantizing, since intensities that
+            // round to the same level are stored the same way.
+            let key: Vec<u8> = cell
+                .iter()
+                .map(|intensity| settings.bit_depth.quantize(*intensity))
+                .collect();
+
+            match unique_cells.entry(key) {
+                Entry::Occupied(entry) => duplicates.push((slot, *entry.get())),
+                Entry::Vacant(entry) => {
+                    entry.insert(cells.len());
+                    cells.push(cell);
+                }
+            }
+        }
+
+        let num_glyphs = cells.len();
+        let row_bytes =
+            ROW_SIZE * max_glyph_width * max_glyph_height * usize::from(settings.bit_depth.bits())
+                / 8;
+
+        println!(
+            "num_glyphs={} duplicates={} saved_bytes={}",
+            num_glyphs,
+            duplicates.len(),
+            (num_rows(glyph_mapping.len()) - num_rows(num_glyphs)) * row_bytes
+        );
+
+        // Image buffer that contains every glyph specified in rows of ROW_SIZE.
+        let mut imgbuf = image::GrayImage::new(
+            (max_glyph_width * ROW_SIZE) as u32,
+            (max_glyph_height * num_rows(num_glyphs)) as u32,
+        );
+
+        // Copies each cell onto the image buffer.
+        for (index, cell) in cells.iter().enumerate() {
+            let img_x = index % ROW_SIZE * max_glyph_width;
+            let img_y = index / ROW_SIZE * max_glyph_height;
+
+            for (i, intensity) in cell.iter().enumerate() {
+                imgbuf.put_pixel(
+                    (img_x + i % max_glyph_width) as u32,
+                    (img_y + i / max_glyph_width) as u32,
+                    Luma([*intensity]),
+                );
+            }
         }
 
         Ok(MonoFontData {
             data: imgbuf,
             glyph_width: max_glyph_width,
             glyph_height: max_glyph_height,
+            metrics,
+            num_glyphs,
+            bit_depth: settings.bit_depth,
+            glyph_mapping: DedupMappingSource {
+                mapping: glyph_mapping,
+                duplicates,
+            },
         })
     }
 }
 
+/// Size of the glyph cells of a font, and the positions within them.
+#[derive(Clone, Copy, Debug)]
+struct CellMetrics {
+    /// Rows above the baseline.
+    ascent: usize,
+    width: usize,
+    height: usize,
+    metrics: FontMetrics,
+}
+
+/// Vertical positions within a glyph cell, measured in rows from the top of
+/// the cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct FontMetrics {
+    /// Row that the bottom of a capital letter sits on.
+    pub(crate) baseline: usize,
+
+    pub(crate) underline: Decoration,
+
+    pub(crate) strikethrough: Decoration,
+}
+
+/// Position, and thickness of an underline, or strikethrough.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Decoration {
+    pub(crate) offset: usize,
+    pub(crate) height: usize,
+}
+
+impl Decoration {
+    /// Creates a decoration that fits in a cell with `cell_height` rows.
+    fn clamped(offset: usize, height: usize, cell_height: usize) -> Decoration {
+        let height = height.min(cell_height);
+
+        Decoration {
+            offset: offset.min(cell_height - height),
+            height,
+        }
+    }
+}
+
 pub struct MonoFontData<C> {
     data: C,
-    glyph_width: usize,
-    glyph_height: usize,
+    pub(crate) glyph_width: usize,
+    pub(crate) glyph_height: usize,
+    pub(crate) metrics: FontMetrics,
+    num_glyphs: usize,
+    pub(crate) bit_depth: BitDepth,
+    pub(crate) glyph_mapping: DedupMappingSource,
 }
 
 impl<C> MonoFontData<C> {
+    /// Number of glyphs that were rendered into the bitmap. Glyphs that look
+    /// the same as an earlier glyph aren't counted.
+    pub fn num_glyphs(&self) -> usize {
+        self.num_glyphs
+    }
+
+    /// Number of characters that reuse the bitmap of an earlier glyph,
+    /// rather than having their own.
+    pub fn num_duplicates(&self) -> usize {
+        self.glyph_mapping.duplicates.len()
+    }
+
     /// Writes the Rust source code that enables the generated font data to be
     /// used with embedded-graphics.
     ///
     /// This generates a single Rust source code file, with a single constant
     /// named `FONT`. `FONT` can then be imported in `lib.rs` and re-exported
-    /// with the desired name.
+    /// with the desired name. Gray fonts generate a `GrayFont` instead of a
+    /// `MonoFont`.
     pub fn save_rust_source<P0, P1>(
         &self,
         rust_source_path: P0,
@@ -197,45 +686,91 @@ impl<C> MonoFontData<C> {
         P0: AsRef<Path>,
         P1: AsRef<Path> + Display,
     {
+        if self.bit_depth.is_gray() {
+            return self.save_gray_rust_source(rust_source_path, bin_data_path);
+        }
+
         // TODO: Make this better
         #[rustfmt::skip]
         let source = format!(
 r#"// This is generated code. Any modifications to this file will
 // be overwritten.
-use embedded_graphics::{{
-    geometry::Size,
-    image::ImageRaw,
-    mono_font::{{DecorationDimensions, MonoFont}},
+use embedded_graphics_cjk_glyph_mapping::{{
+    binary_image,
+    embedded_graphics::{{
+        geometry::Size,
+        mono_font::{{DecorationDimensions, MonoFont}},
+    }},
+    {glyph_mapping_type},
 }};
-use embedded_graphics_cjk_glyph_mapping::RangeGlyphMapping;
 
+{glyph_mapping}
 #[rustfmt::skip]
 pub const FONT: MonoFont = MonoFont {{
-    image: ImageRaw::new_binary(
+    image: binary_image(
         include_bytes!("{bin_data_path}"),
         {chars_per_row} * {glyph_width},
     ),
-    glyph_mapping: &RangeGlyphMapping::new_unchecked(
-        [
-            '?'..='?',                      // ?
-            '\u{{2E80}}'..='\u{{2EF3}}',    // CJK Radicals Supplement
-            '\u{{4E00}}'..='\u{{9FFF}}',    // CJK Unified Ideographs
-        ],
-        0
-    ),
+    glyph_mapping: &GLYPH_MAPPING,
     character_size: Size::new({glyph_width}, {glyph_height}),
     character_spacing: 0,
-    baseline: 0,
-    underline: DecorationDimensions::new({underline}, 1),
-    strikethrough: DecorationDimensions::new({strikethrough}, 1),
+    baseline: {baseline},
+    underline: DecorationDimensions::new({underline_offset}, {underline_height}),
+    strikethrough: DecorationDimensions::new({strikethrough_offset}, {strikethrough_height}),
 }};
 "#,
             bin_data_path = bin_data_path,
             chars_per_row = ROW_SIZE,
+            glyph_mapping_type = self.glyph_mapping.rust_type(),
+            glyph_mapping = self.glyph_mapping.rust_source(),
             glyph_width = self.glyph_width,
             glyph_height = self.glyph_height,
-            underline = self.glyph_height + 1,
-            strikethrough = self.glyph_height / 2
+            baseline = self.metrics.baseline,
+            underline_offset = self.metrics.underline.offset,
+            underline_height = self.metrics.underline.height,
+            strikethrough_offset = self.metrics.strikethrough.offset,
+            strikethrough_height = self.metrics.strikethrough.height,
+        );
+
+        fs::write(rust_source_path, &source)
+    }
+
+    fn save_gray_rust_source<P0, P1>(
+        &self,
+        rust_source_path: P0,
+        bin_data_path: P1,
+    ) -> io::Result<()>
+    where
+        P0: AsRef<Path>,
+        P1: AsRef<Path> + Display,
+    {
+        #[rustfmt::skip]
+        let source = format!(
+r#"// This is generated code. Any modifications to this file will
+// be overwritten.
+use embedded_graphics_cjk_glyph_mapping::{{embedded_graphics::geometry::Size, {glyph_mapping_type}}};
+use embedded_graphics_cjk_gray_font::GrayFont;
+
+{glyph_mapping}
+#[rustfmt::skip]
+pub const FONT: GrayFont = GrayFont {{
+    data: include_bytes!("{bin_data_path}"),
+    image_width: {chars_per_row} * {glyph_width},
+    bits_per_pixel: {bits_per_pixel},
+    glyph_mapping: &GLYPH_MAPPING,
+    character_size: Size::new({glyph_width}, {glyph_height}),
+    character_spacing: 0,
+    baseline: {baseline},
+}};
+"#,
+            bin_data_path = bin_data_path,
+            chars_per_row = ROW_SIZE,
+            bits_per_pixel = self.bit_depth.bits(),
+            glyph_mapping_type = self.glyph_mapping.rust_type(),
+            glyph_mapping = self.glyph_mapping.rust_source(),
+            glyph_width = self.glyph_width,
+            glyph_height = self.glyph_height,
+            baseline = self.metrics.baseline,
         );
 
         fs::write(rust_source_path, &source)
@@ -266,23 +801,400 @@ where
     /// This function will iterate collapse every 8 bytes (0 or 255) into a
     /// single byte, where each bit corresponds to a pixel's on/off state.
     ///
+    /// Gray fonts are packed the same way, but with 2, 4, or 8 bits for each
+    /// pixel's intensity.
+    ///
     /// See: https://docs.rs/embedded-graphics/0.7.1/embedded_graphics/image/struct.ImageRaw.html#draw-a-1bpp-image
     pub fn save_raw<P>(&self, raw_file: P) -> io::Result<()>
     where
         P: AsRef<Path>,
     {
-        let bpp_vec: Vec<u8> = self
+        fs::write(raw_file, self.to_raw())
+    }
+
+    /// Packs the image data into the format written by
+    /// [`MonoFontData::save_raw`].
+    pub(crate) fn to_raw(&self) -> Vec<u8> {
+        let bits = self.bit_depth.bits();
+
+        self.data
+            .chunks_exact(usize::from(8 / bits))
+            .map(|pixels| {
+                pixels.iter().enumerate().fold(0, |byte, (i, intensity)| {
+                    byte | self.bit_depth.quantize(*intensity) << (8 - bits * (i as u8 + 1))
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{
+        num_rows, BitDepth, Decoration, FontOutputSettings, MissingGlyphPolicy, MonoFontBuilder,
+        ROW_SIZE,
+    };
+    use crate::{
+        error::BuildError,
+        mapping::FALLBACK_CHAR,
+        unicode::{CJK_RADICALS_SUPPLEMENT, UNICODE_CODE_BLOCKS},
+    };
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::mapping::StrGlyphMapping,
+        pixelcolor::{Gray8, GrayColor},
+        primitives::PointsIter,
+        text::{Baseline, Text},
+        Drawable,
+    };
+    use embedded_graphics_cjk_gray_font::{GrayFont, GrayTextStyle};
+    use std::path::{Path, PathBuf};
+
+    /// Font used to render glyphs in tests, Fira Sans, which is licensed under
+    /// the SIL Open Font License (see `tests/fonts/OFL.txt`).
+    pub(crate) fn test_font() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts/FiraSans-Regular.ttf")
+    }
+
+    #[test]
+    fn test_nonexistent_font() {
+        let err = MonoFontBuilder::new("does-not-exist.ttf", &[CJK_RADICALS_SUPPLEMENT])
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            &err,
+            BuildError::ReadFontError {
+                source: freetype::Error::CannotOpenResource,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "reading font `does-not-exist.ttf`: Cannot open resource"
+        );
+    }
+
+    #[test]
+    fn test_non_font_file() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let err = MonoFontBuilder::new(path, &[CJK_RADICALS_SUPPLEMENT])
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            &err,
+            BuildError::ReadFontError {
+                source: freetype::Error::UnknownFileFormat,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("Cargo.toml"));
+    }
+
+    #[test]
+    fn test_zero_font_size() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::new(font_path, &[CJK_RADICALS_SUPPLEMENT]).unwrap();
+        let err = builder
+            .build(FontOutputSettings {
+                font_size: 0,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::One,
+                missing_glyphs: MissingGlyphPolicy::Fallback,
+            })
+            .err()
+            .unwrap();
+
+        assert!(matches!(err, BuildError::InvalidFontSize(0)));
+    }
+
+    #[test]
+    fn test_build_vertical_without_alternates() {
+        let font_path = test_font();
+        let chars = ['、', '。', '「', '」', 'ー', '…', 'A'];
+        let builder = MonoFontBuilder::with_chars(font_path, chars).unwrap();
+        let err = builder
+            .build_vertical(FontOutputSettings {
+                font_size: 12,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::One,
+                missing_glyphs: MissingGlyphPolicy::Fallback,
+            })
+            .err()
+            .unwrap();
+
+        // Fira Sans doesn't have a `vert` feature
+        assert!(matches!(err, BuildError::NoVerticalAlternates));
+    }
+
+    #[test]
+    fn test_render_glyph_error_message() {
+        let err = BuildError::RenderGlyphError {
+            codepoint: '\u{2E80}',
+            source: freetype::Error::InvalidGlyphIndex,
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "rendering glyph for U+2E80 `\u{2e80}`: Invalid glyph index"
+        );
+    }
+
+    #[test]
+    fn test_missing_glyph_policy() {
+        let font_path = test_font();
+        let build = |builder: &MonoFontBuilder, missing_glyphs| {
+            builder.build(FontOutputSettings {
+                font_size: 8,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::One,
+                missing_glyphs,
+            })
+        };
+
+        // Fira Sans doesn't include any CJK ideographs
+        let sparse = MonoFontBuilder::with_chars(&font_path, "a一".chars()).unwrap();
+        let ranges = MonoFontBuilder::new(&font_path, &[CJK_RADICALS_SUPPLEMENT]).unwrap();
+
+        assert_eq!(sparse.missing_chars().into_iter().collect::<String>(), "一");
+        assert_eq!(
+            ranges.missing_chars().len(),
+            CJK_RADICALS_SUPPLEMENT.block_size()
+        );
+
+        let bitmap = build(&sparse, MissingGlyphPolicy::Fallback).unwrap();
+
+        assert_eq!(
+            bitmap.glyph_mapping.mapping.chars().collect::<String>(),
+            "?a"
+        );
+
+        // Every missing character is drawn with the fallback glyph, which is
+        // only stored once.
+        let bitmap = build(&ranges, MissingGlyphPolicy::Fallback).unwrap();
+
+        assert_eq!(bitmap.num_glyphs(), 1);
+        assert_eq!(
+            bitmap.num_duplicates(),
+            CJK_RADICALS_SUPPLEMENT.block_size()
+        );
+        assert!(bitmap
+            .glyph_mapping
+            .duplicates
+            .iter()
+            .all(|(_, glyph)| *glyph == 0));
+
+        let bitmap = build(&ranges, MissingGlyphPolicy::Skip).unwrap();
+
+        assert_eq!(
+            bitmap.glyph_mapping.mapping.chars().collect::<String>(),
+            "?"
+        );
+
+        match build(&sparse, MissingGlyphPolicy::Error) {
+            Err(BuildError::MissingGlyph { codepoint }) => assert_eq!(codepoint, '一'),
+            _ => panic!("expected a missing glyph error"),
+        }
+    }
+
+    #[test]
+    fn test_bit_depth() {
+        assert_eq!(BitDepth::try_from(4).unwrap(), BitDepth::Four);
+        assert!(matches!(
+            BitDepth::try_from(3),
+            Err(BuildError::InvalidBitDepth(3))
+        ));
+
+        assert_eq!(BitDepth::One.quantize(0xFF), 1);
+        assert_eq!(BitDepth::Two.quantize(0x55), 1);
+        assert_eq!(BitDepth::Four.quantize(0x80), 8);
+        assert_eq!(BitDepth::Eight.quantize(0x12), 0x12);
+    }
+
+    #[test]
+    fn test_gray_output() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::with_chars(&font_path, "A".chars()).unwrap();
+        let build = |bit_depth| {
+            builder
+                .build(FontOutputSettings {
+                    font_size: 16,
+                    intensity_threshold: 128,
+                    bit_depth,
+                    missing_glyphs: MissingGlyphPolicy::Fallback,
+                })
+                .unwrap()
+        };
+
+        let mono = build(BitDepth::One);
+        let gray = build(BitDepth::Four);
+
+        // Anti-aliased edges keep their partial intensity
+        assert!(mono
+            .data
+            .pixels()
+            .all(|pixel| matches!(pixel.0[0], 0 | 0xFF)));
+        assert!(gray
             .data
-            .chunks_exact(8)
-            .map(|byte| {
-                byte.iter()
-                    .enumerate()
-                    .filter(|(_, bit)| **bit > 0)
-                    .map(|(i, _)| 0x80 >> i)
-                    .sum()
+            .pixels()
+            .any(|pixel| !matches!(pixel.0[0], 0 | 0xFF)));
+
+        assert_eq!(gray.to_raw().len(), mono.to_raw().len() * 4);
+        assert_eq!(build(BitDepth::Eight).to_raw(), gray.data.as_raw()[..]);
+    }
+
+    #[test]
+    fn test_draw_gray_font() {
+        let font_path = test_font();
+        let gray = MonoFontBuilder::with_chars(&font_path, "A".chars())
+            .unwrap()
+            .build(FontOutputSettings {
+                font_size: 16,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::Four,
+                missing_glyphs: MissingGlyphPolicy::Fallback,
             })
+            .unwrap();
+        let data = gray.to_raw();
+        let font = GrayFont {
+            data: &data,
+            image_width: (ROW_SIZE * gray.glyph_width) as u32,
+            bits_per_pixel: 4,
+            glyph_mapping: &StrGlyphMapping::new("?A", 0),
+            character_size: Size::new(gray.glyph_width as u32, gray.glyph_height as u32),
+            character_spacing: 0,
+            baseline: gray.metrics.baseline as u32,
+        };
+        let style = GrayTextStyle::with_background(&font, Gray8::WHITE, Gray8::BLACK);
+        let mut display = MockDisplay::new();
+
+        Text::with_baseline("A", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        let lumas: Vec<u8> = display
+            .affected_area()
+            .points()
+            .filter_map(|point| display.get_pixel(point))
+            .map(|color| color.luma())
             .collect();
 
-        fs::write(raw_file, &bpp_vec[..])
+        // The edges of the glyph are blended with the background
+        assert!(lumas.contains(&0xFF));
+        assert!(lumas.iter().any(|luma| !matches!(luma, 0 | 0xFF)));
+    }
+
+    #[test]
+    fn test_glyphs_sit_on_baseline() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::with_chars(&font_path, "Hgx".chars()).unwrap();
+        let bitmap = builder
+            .build(FontOutputSettings {
+                font_size: 16,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::One,
+                missing_glyphs: MissingGlyphPolicy::Fallback,
+            })
+            .unwrap();
+        let metrics = bitmap.metrics;
+
+        // Rows of the cell containing part of the glyph at `index`
+        let ink_rows = |index: usize| -> Vec<usize> {
+            (0..bitmap.glyph_height)
+                .filter(|y| {
+                    (0..bitmap.glyph_width).any(|x| {
+                        let x = (index * bitmap.glyph_width + x) as u32;
+
+                        bitmap.data.get_pixel(x, *y as u32).0[0] > 0
+                    })
+                })
+                .collect()
+        };
+
+        // Glyphs are stored in the order `?`, `H`, `g`, `x`
+        assert_eq!(ink_rows(1).last(), Some(&metrics.baseline));
+        assert!(ink_rows(2).last().unwrap() > &metrics.baseline);
+        assert_eq!(ink_rows(3).last(), Some(&metrics.baseline));
+
+        assert!(metrics.underline.offset > metrics.baseline);
+        assert!(metrics.underline.offset + metrics.underline.height <= bitmap.glyph_height);
+        assert!(metrics.strikethrough.offset < metrics.baseline);
+        assert!(metrics.strikethrough.offset > ink_rows(3)[0]);
+    }
+
+    #[test]
+    fn test_decorations_fit_in_cell() {
+        // An underline below the last row of a 12 row cell
+        assert_eq!(
+            Decoration::clamped(13, 2, 12),
+            Decoration {
+                offset: 10,
+                height: 2
+            }
+        );
+        assert_eq!(
+            Decoration::clamped(4, 1, 12),
+            Decoration {
+                offset: 4,
+                height: 1
+            }
+        );
+        assert_eq!(
+            Decoration::clamped(0, 3, 2),
+            Decoration {
+                offset: 0,
+                height: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_num_rows() {
+        assert_eq!(num_rows(1), 1);
+        assert_eq!(num_rows(ROW_SIZE), 1);
+        assert_eq!(num_rows(ROW_SIZE + 1), 2);
+        assert_eq!(num_rows(1 + 20992), 657);
+    }
+
+    #[test]
+    fn test_bitmap_rows_match_rendered_glyphs() {
+        let font_path = test_font();
+
+        for (name, block) in UNICODE_CODE_BLOCKS {
+            let builder = MonoFontBuilder::new(&font_path, &[*block]).unwrap();
+            let bitmap = builder
+                .build(FontOutputSettings {
+                    font_size: 8,
+                    intensity_threshold: 128,
+                    bit_depth: BitDepth::One,
+                    missing_glyphs: MissingGlyphPolicy::Fallback,
+                })
+                .unwrap();
+
+            // The fallback character is added when the block doesn't contain it
+            let fallback = !block.range().contains(&FALLBACK_CHAR) as usize;
+            let rows = num_rows(bitmap.num_glyphs());
+
+            assert_eq!(
+                bitmap.num_glyphs() + bitmap.num_duplicates(),
+                block.block_size() + fallback,
+                "{}",
+                name
+            );
+            assert_eq!(
+                bitmap.data.height() as usize,
+                rows * bitmap.glyph_height,
+                "{}",
+                name
+            );
+            assert_eq!(
+                bitmap.to_raw().len(),
+                rows * bitmap.glyph_height * ROW_SIZE * bitmap.glyph_width / 8,
+                "{}",
+                name
+            );
+        }
     }
 }
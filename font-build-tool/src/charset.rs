@@ -0,0 +1,86 @@
+use crate::error::BuildError;
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// Collects the distinct characters used in each of the UTF-8 text files.
+///
+/// Control characters (like line breaks) are never displayed, so they're
+/// excluded from the set.
+pub fn read_charset<I, P>(text_files: I) -> Result<BTreeSet<char>, BuildError>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut charset = BTreeSet::new();
+
+    for text_file in text_files {
+        let text = fs::read_to_string(text_file)?;
+
+        charset.extend(text.chars().filter(|chr| !chr.is_control()));
+    }
+
+    Ok(charset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_charset;
+    use crate::{
+        builder::MonoFontBuilder,
+        error::BuildError,
+        mapping::{GlyphMappingSource, FALLBACK_CHAR},
+    };
+    use std::io::{self, Write};
+    use tempfile::NamedTempFile;
+
+    /// Writes a temporary text file, which is removed when it's dropped.
+    fn text_file(contents: &[u8]) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+
+        file.write_all(contents).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_dedup_across_files() {
+        let first = text_file("你好，世界".as_bytes());
+        let second = text_file("世界你好".as_bytes());
+        let charset = read_charset([&first, &second]).unwrap();
+
+        assert_eq!(charset.into_iter().collect::<String>(), "世你好界，");
+    }
+
+    #[test]
+    fn test_control_chars_excluded() {
+        let path = text_file("开\r\n关\t\u{7}".as_bytes());
+        let charset = read_charset([&path]).unwrap();
+
+        assert_eq!(charset.into_iter().collect::<String>(), "关开");
+    }
+
+    #[test]
+    fn test_non_utf8_file() {
+        // `中文` in GBK
+        let path = text_file(&[0xD6, 0xD0, 0xCE, 0xC4]);
+
+        assert!(matches!(
+            read_charset([&path]),
+            Err(BuildError::IoError(err)) if err.kind() == io::ErrorKind::InvalidData
+        ));
+        assert!(matches!(
+            MonoFontBuilder::with_charset_files("does-not-exist.ttf", [&path]),
+            Err(BuildError::IoError(err)) if err.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+
+    #[test]
+    fn test_sorted_mapping_with_fallback() {
+        let path = text_file("温度：25℃\n湿度：60%\n".as_bytes());
+        let glyph_mapping = GlyphMappingSource::from_chars(read_charset([&path]).unwrap());
+        let chars: Vec<char> = glyph_mapping.chars().collect();
+
+        assert!(matches!(glyph_mapping, GlyphMappingSource::Sparse(_)));
+        assert!(chars.contains(&FALLBACK_CHAR));
+        assert!(chars.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(chars.iter().collect::<String>(), "%0256?℃度温湿：");
+    }
+}
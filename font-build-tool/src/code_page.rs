@@ -0,0 +1,224 @@
+use crate::error::BuildError;
+use std::{fs, ops::RangeInclusive, path::Path};
+
+/// Table of the characters of a double-byte code page, like GBK, or
+/// Shift_JIS, that is read by the `embedded-graphics-cjk-encoding` crate.
+///
+/// The table has a little-endian `u16` for every combination of a lead
+/// byte, and a trail byte, ordered by lead byte, and then by trail byte. The
+/// lead, and trail bytes are the bytes in their ranges, and each byte is
+/// numbered by its position in the ranges. Unmapped combinations are 0.
+pub struct CodePageTable {
+    chars: Vec<u16>,
+}
+
+impl CodePageTable {
+    /// Reads a mapping file in the format of the code page mappings published
+    /// by unicode.org, like `CP936.TXT`.
+    ///
+    /// Each line has the bytes of a character, and its codepoint, in hex,
+    /// followed by an optional comment. Single byte characters, and lines
+    /// without a codepoint (like `#DBCS LEAD BYTE`) are skipped.
+    pub fn from_mapping_file<P>(
+        path: P,
+        lead_bytes: &[RangeInclusive<u8>],
+        trail_bytes: &[RangeInclusive<u8>],
+    ) -> Result<CodePageTable, BuildError>
+    where
+        P: AsRef<Path>,
+    {
+        let mapping = fs::read_to_string(path)?;
+
+        CodePageTable::from_mapping(&mapping, lead_bytes, trail_bytes)
+    }
+
+    fn from_mapping(
+        mapping: &str,
+        lead_bytes: &[RangeInclusive<u8>],
+        trail_bytes: &[RangeInclusive<u8>],
+    ) -> Result<CodePageTable, BuildError> {
+        let lead_count = byte_count(lead_bytes);
+        let trail_count = byte_count(trail_bytes);
+        let mut chars = vec![0; lead_count * trail_count];
+
+        for (index, line) in mapping.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let (bytes, codepoint) = match (fields.next(), fields.next()) {
+                (Some(bytes), Some(codepoint)) => (bytes, codepoint),
+                _ => continue,
+            };
+            let invalid_line = || BuildError::InvalidCodePageMapping(index + 1);
+            let bytes = parse_hex(bytes).ok_or_else(invalid_line)?;
+            let codepoint = parse_hex(codepoint).ok_or_else(invalid_line)?;
+
+            if bytes <= 0xFF {
+                continue;
+            }
+
+            let [_, _, lead, trail] = bytes.to_be_bytes();
+            let position = byte_position(lead_bytes, lead)
+                .zip(byte_position(trail_bytes, trail))
+                .filter(|_| bytes <= 0xFFFF)
+                .ok_or(BuildError::CodePageBytesOutOfRange(bytes))?;
+            let codepoint = u16::try_from(codepoint)
+                .map_err(|_| BuildError::CodePageCodepointOutOfRange(codepoint))?;
+
+            chars[position.0 * trail_count + position.1] = codepoint;
+        }
+
+        Ok(CodePageTable { chars })
+    }
+
+    /// Number of characters in the table.
+    pub fn len(&self) -> usize {
+        self.chars.iter().filter(|chr| **chr != 0).count()
+    }
+
+    /// Returns `true` if the mapping had no double-byte characters.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the table, as it is read by the decoders.
+    pub fn to_raw(&self) -> Vec<u8> {
+        self.chars
+            .iter()
+            .flat_map(|chr| chr.to_le_bytes())
+            .collect()
+    }
+
+    /// Saves the table to a file.
+    pub fn save_raw<P>(&self, path: P) -> Result<(), BuildError>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_raw())?;
+
+        Ok(())
+    }
+}
+
+/// Parses a range of bytes, written in hex, like `81-FE`. A single byte,
+/// like `80`, is a range of one byte.
+pub fn parse_byte_range(value: &str) -> Result<RangeInclusive<u8>, BuildError> {
+    let invalid_range = || BuildError::InvalidByteRange(value.to_string());
+    let (start, end) = value.split_once('-').unwrap_or((value, value));
+    let start = u8::from_str_radix(start, 16).map_err(|_| invalid_range())?;
+    let end = u8::from_str_radix(end, 16).map_err(|_| invalid_range())?;
+
+    if start > end {
+        return Err(invalid_range());
+    }
+
+    Ok(start..=end)
+}
+
+/// Parses a hex number, like `0x8140`.
+fn parse_hex(value: &str) -> Option<u32> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))?;
+
+    u32::from_str_radix(digits, 16).ok()
+}
+
+/// Number of bytes in the ranges.
+fn byte_count(ranges: &[RangeInclusive<u8>]) -> usize {
+    ranges.iter().map(|range| range.clone().count()).sum()
+}
+
+/// Position of a byte in the ranges.
+fn byte_position(ranges: &[RangeInclusive<u8>], byte: u8) -> Option<usize> {
+    let mut offset = 0;
+
+    for range in ranges {
+        if range.contains(&byte) {
+            return Some(offset + (byte - range.start()) as usize);
+        }
+
+        offset += range.clone().count();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{byte_position, parse_byte_range, CodePageTable};
+    use crate::error::BuildError;
+
+    const MAPPING: &str = "\
+#    Name:     cp936 to Unicode table
+0x41\t0x0041\t#LATIN CAPITAL LETTER A
+0x80\t0x20AC\t#EURO SIGN
+0x81\t      \t#DBCS LEAD BYTE
+0x8140\t0x4E02\t#CJK UNIFIED IDEOGRAPH
+0x8180\t0x4E8A\t#CJK UNIFIED IDEOGRAPH
+0xD6D0\t0x4E2D\t#CJK UNIFIED IDEOGRAPH
+";
+
+    /// Returns the codepoint for a lead byte, and a trail byte, by their
+    /// positions in the ranges, of a table with 190 trail bytes.
+    fn get(table: &CodePageTable, lead: usize, trail: usize) -> u16 {
+        table.chars[lead * 190 + trail]
+    }
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range("81-FE").unwrap(), 0x81..=0xFE);
+        assert_eq!(parse_byte_range("80").unwrap(), 0x80..=0x80);
+        assert!(parse_byte_range("FE-81").is_err());
+        assert!(parse_byte_range("81-1FE").is_err());
+    }
+
+    #[test]
+    fn test_byte_position() {
+        let ranges = [0x40..=0x7E, 0x80..=0xFE];
+
+        assert_eq!(byte_position(&ranges, 0x40), Some(0));
+        assert_eq!(byte_position(&ranges, 0x7E), Some(0x3E));
+        assert_eq!(byte_position(&ranges, 0x80), Some(0x3F));
+        assert_eq!(byte_position(&ranges, 0x7F), None);
+        assert_eq!(byte_position(&ranges, 0xFF), None);
+    }
+
+    #[test]
+    fn test_from_mapping() {
+        let table =
+            CodePageTable::from_mapping(MAPPING, &[0x81..=0xFE], &[0x40..=0x7E, 0x80..=0xFE])
+                .unwrap();
+
+        assert_eq!(table.len(), 3);
+        assert_eq!(get(&table, 0, 0), 0x4E02);
+        assert_eq!(get(&table, 0, 0x3F), 0x4E8A);
+        assert_eq!(get(&table, 0xD6 - 0x81, 0xD0 - 0x41), 0x4E2D);
+        assert_eq!(get(&table, 0, 1), 0);
+
+        let raw = table.to_raw();
+
+        assert_eq!(raw.len(), 126 * 190 * 2);
+        assert_eq!(&raw[..2], &[0x02, 0x4E]);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let result = CodePageTable::from_mapping(MAPPING, &[0xA1..=0xFE], &[0x40..=0xFE]);
+
+        assert!(matches!(
+            result,
+            Err(BuildError::CodePageBytesOutOfRange(0x8140))
+        ));
+
+        let result = CodePageTable::from_mapping("0x8140\t0x20000", &[0x81..=0xFE], &[0x40..=0xFE]);
+
+        assert!(matches!(
+            result,
+            Err(BuildError::CodePageCodepointOutOfRange(0x20000))
+        ));
+
+        let result = CodePageTable::from_mapping("0x8140\tabc", &[0x81..=0xFE], &[0x40..=0xFE]);
+
+        assert!(matches!(result, Err(BuildError::InvalidCodePageMapping(1))));
+    }
+}
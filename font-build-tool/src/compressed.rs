@@ -0,0 +1,470 @@
+use crate::{
+    builder::{FontMetrics, MonoFontData, ROW_SIZE},
+    error::BuildError,
+    mapping::DedupMappingSource,
+};
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    ops::Deref,
+    path::Path,
+};
+
+/// Largest width, and height of a glyph cell that a `CompressedFont` can
+/// decode.
+const MAX_GLYPH_SIZE: usize = 64;
+
+/// Glyph bitmaps where each glyph is compressed on its own, so it can be
+/// decoded while drawing. See `CompressedFont` for the format.
+pub struct CompressedFontData {
+    pub(crate) data: Vec<u8>,
+    pub(crate) glyph_width: usize,
+    pub(crate) glyph_height: usize,
+    pub(crate) metrics: FontMetrics,
+    pub(crate) glyph_mapping: DedupMappingSource,
+    stats: CompressionStats,
+}
+
+/// Size of a font before, and after compression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompressionStats {
+    /// Number of glyphs that were compressed.
+    pub num_glyphs: usize,
+
+    /// Size of the uncompressed bitmap, in bytes.
+    pub raw_size: usize,
+
+    /// Size of the compressed glyphs, including the offset table, in bytes.
+    pub compressed_size: usize,
+}
+
+impl<C> MonoFontData<C>
+where
+    C: Deref<Target = [u8]>,
+{
+    /// Compresses each glyph on its own, for drawing with a
+    /// `CompressedFont`.
+    ///
+    /// Only fonts with a bit depth of 1, and cells up to 64x64 pixels can be
+    /// compressed.
+    pub fn compress(&self) -> Result<CompressedFontData, BuildError> {
+        if self.bit_depth.is_gray() {
+            return Err(BuildError::UnsupportedBitDepth(self.bit_depth.bits()));
+        }
+
+        let raw = self.to_raw();
+        let data = compress_raw(&raw, self.glyph_width, self.glyph_height, self.num_glyphs())?;
+
+        Ok(CompressedFontData {
+            stats: CompressionStats {
+                num_glyphs: self.num_glyphs(),
+                raw_size: raw.len(),
+                compressed_size: data.len(),
+            },
+            data,
+            glyph_width: self.glyph_width,
+            glyph_height: self.glyph_height,
+            metrics: self.metrics,
+            glyph_mapping: self.glyph_mapping.clone(),
+        })
+    }
+}
+
+impl CompressedFontData {
+    /// Number of glyphs that were compressed.
+    pub fn num_glyphs(&self) -> usize {
+        self.stats.num_glyphs
+    }
+
+    /// Size of the font before, and after compression.
+    pub fn stats(&self) -> CompressionStats {
+        self.stats
+    }
+
+    /// Writes the offset table, and the compressed glyphs.
+    pub fn save_raw<P>(&self, raw_file: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(raw_file, &self.data)
+    }
+
+    /// Writes the Rust source code for a `CompressedFont` constant named
+    /// `FONT`, with the compressed glyphs included from the raw data file.
+    pub fn save_rust_source<P0, P1>(
+        &self,
+        rust_source_path: P0,
+        bin_data_path: P1,
+    ) -> io::Result<()>
+    where
+        P0: AsRef<Path>,
+        P1: AsRef<Path> + Display,
+    {
+        fs::write(rust_source_path, self.rust_source(bin_data_path))
+    }
+
+    fn rust_source<P>(&self, bin_data_path: P) -> String
+    where
+        P: Display,
+    {
+        #[rustfmt::skip]
+        let source = format!(
+r#"// This is generated code. Any modifications to this file will
+// be overwritten.
+use embedded_graphics_cjk_compressed_font::CompressedFont;
+use embedded_graphics_cjk_glyph_mapping::{{
+    embedded_graphics::{{geometry::Size, mono_font::DecorationDimensions}},
+    {glyph_mapping_type},
+}};
+
+{glyph_mapping}
+#[rustfmt::skip]
+pub const FONT: CompressedFont = CompressedFont {{
+    data: include_bytes!("{bin_data_path}"),
+    glyph_mapping: &GLYPH_MAPPING,
+    character_size: Size::new({glyph_width}, {glyph_height}),
+    character_spacing: 0,
+    baseline: {baseline},
+    underline: DecorationDimensions::new({underline_offset}, {underline_height}),
+    strikethrough: DecorationDimensions::new({strikethrough_offset}, {strikethrough_height}),
+}};
+"#,
+            bin_data_path = bin_data_path,
+            glyph_mapping_type = self.glyph_mapping.rust_type(),
+            glyph_mapping = self.glyph_mapping.rust_source(),
+            glyph_width = self.glyph_width,
+            glyph_height = self.glyph_height,
+            baseline = self.metrics.baseline,
+            underline_offset = self.metrics.underline.offset,
+            underline_height = self.metrics.underline.height,
+            strikethrough_offset = self.metrics.strikethrough.offset,
+            strikethrough_height = self.metrics.strikethrough.height,
+        );
+
+        source
+    }
+}
+
+impl CompressionStats {
+    /// Compresses raw data written by [`MonoFontData::save_raw`], to find
+    /// out how much smaller an existing font would be.
+    ///
+    /// The raw data doesn't store the number of glyphs, so every cell in the
+    /// bitmap is counted, including the blank cells after the last glyph.
+    pub fn from_raw(
+        raw: &[u8],
+        glyph_width: usize,
+        glyph_height: usize,
+    ) -> Result<CompressionStats, BuildError> {
+        let num_glyphs = num_cells(raw, glyph_width, glyph_height)?;
+        let data = compress_raw(raw, glyph_width, glyph_height, num_glyphs)?;
+
+        Ok(CompressionStats {
+            num_glyphs,
+            raw_size: raw.len(),
+            compressed_size: data.len(),
+        })
+    }
+
+    /// Size of the compressed font, as a fraction of the raw size.
+    pub fn ratio(&self) -> f64 {
+        self.compressed_size as f64 / self.raw_size as f64
+    }
+}
+
+impl Display for CompressionStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} glyphs, {} bytes raw, {} bytes compressed ({:.1}%)",
+            self.num_glyphs,
+            self.raw_size,
+            self.compressed_size,
+            self.ratio() * 100.0
+        )
+    }
+}
+
+/// Number of glyph cells in raw data, which is a bitmap with [`ROW_SIZE`]
+/// cells on each line.
+fn num_cells(raw: &[u8], glyph_width: usize, glyph_height: usize) -> Result<usize, BuildError> {
+    let line_bits = ROW_SIZE * glyph_width * glyph_height;
+
+    if line_bits == 0 || !(raw.len() * 8).is_multiple_of(line_bits) {
+        return Err(BuildError::InvalidRawData {
+            len: raw.len(),
+            width: glyph_width,
+            height: glyph_height,
+        });
+    }
+
+    Ok(raw.len() * 8 / line_bits * ROW_SIZE)
+}
+
+/// Compresses the first `num_glyphs` cells of raw data, and prepends the
+/// offset table.
+fn compress_raw(
+    raw: &[u8],
+    glyph_width: usize,
+    glyph_height: usize,
+    num_glyphs: usize,
+) -> Result<Vec<u8>, BuildError> {
+    if glyph_width > MAX_GLYPH_SIZE || glyph_height > MAX_GLYPH_SIZE {
+        return Err(BuildError::GlyphCellTooLarge {
+            width: glyph_width,
+            height: glyph_height,
+            max: MAX_GLYPH_SIZE,
+        });
+    }
+
+    if num_glyphs > num_cells(raw, glyph_width, glyph_height)? {
+        return Err(BuildError::InvalidRawData {
+            len: raw.len(),
+            width: glyph_width,
+            height: glyph_height,
+        });
+    }
+
+    let table_size = (num_glyphs + 1) * 4;
+    let mut glyphs = Vec::new();
+    let mut offsets = Vec::with_capacity(table_size);
+
+    for index in 0..num_glyphs {
+        let cell_x = index % ROW_SIZE * glyph_width;
+        let cell_y = index / ROW_SIZE * glyph_height;
+        let pixel = |x: usize, y: usize| {
+            let bit = (cell_y + y) * ROW_SIZE * glyph_width + cell_x + x;
+
+            raw[bit / 8] & (0x80 >> (bit % 8)) != 0
+        };
+
+        offsets.extend_from_slice(&offset(table_size + glyphs.len())?);
+        compress_glyph(pixel, glyph_width, glyph_height, &mut glyphs);
+    }
+
+    offsets.extend_from_slice(&offset(table_size + glyphs.len())?);
+    offsets.extend_from_slice(&glyphs);
+
+    Ok(offsets)
+}
+
+/// Entry in the offset table.
+fn offset(offset: usize) -> Result<[u8; 4], BuildError> {
+    u32::try_from(offset)
+        .map(u32::to_le_bytes)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "font is larger than 4 GiB").into())
+}
+
+/// Writes the bounding box of the glyph's pixels, followed by a bit for each
+/// row in the bounding box, that is set if the row repeats the previous
+/// one. Other rows are followed by their pixels.
+fn compress_glyph<F>(pixel: F, glyph_width: usize, glyph_height: usize, out: &mut Vec<u8>)
+where
+    F: Fn(usize, usize) -> bool,
+{
+    let set_pixels = (0..glyph_height)
+        .flat_map(|y| (0..glyph_width).map(move |x| (x, y)))
+        .filter(|&(x, y)| pixel(x, y));
+    let bounds = set_pixels.fold(None, |bounds, (x, y)| match bounds {
+        None => Some((x, y, x, y)),
+        Some((left, top, right, bottom)) => {
+            Some((left.min(x), top.min(y), right.max(x), bottom.max(y)))
+        }
+    });
+    let (left, top, right, bottom) = match bounds {
+        Some(bounds) => bounds,
+        None => {
+            out.extend_from_slice(&[0; 4]);
+            return;
+        }
+    };
+    let pixel = &pixel;
+    let row = |y: usize| (left..=right).map(move |x| pixel(x, y));
+    let mut bits = Vec::new();
+
+    for y in top..=bottom {
+        if y > top && row(y).eq(row(y - 1)) {
+            bits.push(true);
+        } else {
+            bits.push(false);
+            bits.extend(row(y));
+        }
+    }
+
+    // Cells are at most 64 pixels, so the bounding box fits in a byte.
+    out.extend_from_slice(&[
+        left as u8,
+        top as u8,
+        (right - left + 1) as u8,
+        (bottom - top + 1) as u8,
+    ]);
+    out.extend(bits.chunks(8).map(|bits| {
+        bits.iter()
+            .enumerate()
+            .fold(0, |byte, (i, bit)| byte | u8::from(*bit) << (7 - i))
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compress_raw, CompressionStats};
+    use crate::{
+        builder::{tests::test_font, BitDepth, FontOutputSettings, MissingGlyphPolicy, ROW_SIZE},
+        error::BuildError,
+        unicode::BASIC_LATIN,
+        MonoFontBuilder,
+    };
+    use embedded_graphics::{geometry::Size, mono_font::DecorationDimensions};
+    use embedded_graphics_cjk_compressed_font::{CompressedFont, GLYPH_BUFFER_SIZE};
+
+    /// Raw data with 4x3 cells, where the first glyph is:
+    ///
+    /// ```text
+    /// .##.
+    /// .##.
+    /// ..#.
+    /// ```
+    fn raw_data() -> Vec<u8> {
+        let mut raw = vec![0; ROW_SIZE * 4 * 3 / 8];
+
+        for (x, y) in [(1, 0), (2, 0), (1, 1), (2, 1), (2, 2)] {
+            let bit = y * ROW_SIZE * 4 + x;
+
+            raw[bit / 8] |= 0x80 >> (bit % 8);
+        }
+
+        raw
+    }
+
+    #[test]
+    fn test_compress_raw() {
+        assert_eq!(
+            compress_raw(&raw_data(), 4, 3, 2).unwrap(),
+            [
+                12,
+                0,
+                0,
+                0,
+                17,
+                0,
+                0,
+                0,
+                21,
+                0,
+                0,
+                0, // Offsets
+                1,
+                0,
+                2,
+                3,
+                0b0111_0010, // `0 11`, `1`, `0 01`
+                0,
+                0,
+                0,
+                0, // Blank glyph
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_from_raw() {
+        let stats = CompressionStats::from_raw(&raw_data(), 4, 3).unwrap();
+
+        assert_eq!(stats.num_glyphs, ROW_SIZE);
+        assert_eq!(stats.raw_size, 48);
+        assert_eq!(
+            stats.compressed_size,
+            (ROW_SIZE + 1) * 4 + 5 + (ROW_SIZE - 1) * 4
+        );
+        assert!(matches!(
+            CompressionStats::from_raw(&raw_data(), 4, 4),
+            Err(BuildError::InvalidRawData { len: 48, .. })
+        ));
+        assert!(matches!(
+            CompressionStats::from_raw(&[0; 65 * 4], 65, 1),
+            Err(BuildError::GlyphCellTooLarge {
+                width: 65,
+                height: 1,
+                max: 64
+            })
+        ));
+    }
+
+    #[test]
+    fn test_decodes_to_same_pixels() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::new(font_path, &[BASIC_LATIN]).unwrap();
+        let bitmap = builder
+            .build(FontOutputSettings {
+                font_size: 24,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::One,
+                missing_glyphs: MissingGlyphPolicy::Fallback,
+            })
+            .unwrap();
+        let raw = bitmap.to_raw();
+        let compressed = bitmap.compress().unwrap();
+        let (width, height) = (bitmap.glyph_width, bitmap.glyph_height);
+        let font = CompressedFont {
+            data: &compressed.data,
+            glyph_mapping: &|chr| chr as usize,
+            character_size: Size::new(width as u32, height as u32),
+            character_spacing: 0,
+            baseline: 0,
+            underline: DecorationDimensions::new(0, 1),
+            strikethrough: DecorationDimensions::new(0, 1),
+        };
+        let bytes_per_row = width.div_ceil(8);
+
+        assert_eq!(compressed.num_glyphs(), bitmap.num_glyphs());
+        assert!(compressed.stats().compressed_size < compressed.stats().raw_size);
+
+        for index in 0..compressed.num_glyphs() {
+            let mut buffer = [0; GLYPH_BUFFER_SIZE];
+
+            font.decode_glyph(char::from_u32(index as u32).unwrap(), &mut buffer)
+                .unwrap();
+
+            for y in 0..height {
+                for x in 0..width {
+                    let bit = (index / ROW_SIZE * height + y) * ROW_SIZE * width
+                        + index % ROW_SIZE * width
+                        + x;
+
+                    assert_eq!(
+                        buffer[y * bytes_per_row + x / 8] & (0x80 >> (x % 8)) != 0,
+                        raw[bit / 8] & (0x80 >> (bit % 8)) != 0,
+                        "glyph {} at ({}, {})",
+                        index,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+
+        let source = compressed.rust_source("data/test-24.bin");
+
+        assert!(source.contains("pub const FONT: CompressedFont = CompressedFont {"));
+        assert!(source.contains(&format!("Size::new({}, {})", width, height)));
+    }
+
+    #[test]
+    fn test_gray_font() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::new(font_path, &[BASIC_LATIN]).unwrap();
+        let bitmap = builder
+            .build(FontOutputSettings {
+                font_size: 12,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::Two,
+                missing_glyphs: MissingGlyphPolicy::Fallback,
+            })
+            .unwrap();
+
+        assert!(matches!(
+            bitmap.compress(),
+            Err(BuildError::UnsupportedBitDepth(2))
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{builder::load_face, error::BuildError, unicode::UnicodeCodeBlock};
+use serde::Serialize;
+use std::{ffi::OsStr, fmt};
+
+/// Describes how many characters in each unicode code block a font has
+/// glyphs for.
+#[derive(Debug, Serialize)]
+pub struct CoverageReport {
+    /// Family, and style name of the font.
+    pub font: String,
+
+    pub blocks: Vec<BlockCoverage>,
+}
+
+/// Glyph coverage for a single unicode code block.
+#[derive(Debug, Serialize)]
+pub struct BlockCoverage {
+    pub name: String,
+
+    /// First code point in the block, formatted as `U+XXXX`.
+    pub start: String,
+
+    /// Last code point in the block (inclusive), formatted as `U+XXXX`.
+    pub end: String,
+
+    /// Number of characters in the block.
+    pub total: usize,
+
+    /// Number of characters in the block that the font has a glyph for.
+    pub covered: usize,
+
+    /// Characters in the block that the font doesn't have a glyph for.
+    pub missing: Vec<char>,
+}
+
+impl CoverageReport {
+    /// Checks which characters in each of the unicode code blocks have a
+    /// glyph in the font.
+    pub fn from_font<P>(
+        ttf_path: P,
+        face_index: isize,
+        unicode_blocks: &[(&str, UnicodeCodeBlock)],
+    ) -> Result<CoverageReport, BuildError>
+    where
+        P: AsRef<OsStr>,
+    {
+        let (_lib, font) = load_face(ttf_path, face_index)?;
+        let blocks = unicode_blocks
+            .iter()
+            .map(|(name, block)| {
+                let missing: Vec<char> = block
+                    .range()
+                    .filter(|chr| font.get_char_index(*chr as usize) == 0)
+                    .collect();
+
+                BlockCoverage {
+                    name: name.to_string(),
+                    start: format!("U+{:04X}", block.start() as u32),
+                    end: format!("U+{:04X}", block.end() as u32),
+                    total: block.block_size(),
+                    covered: block.block_size() - missing.len(),
+                    missing,
+                }
+            })
+            .collect();
+
+        Ok(CoverageReport {
+            font: format!(
+                "{} {}",
+                font.family_name().unwrap_or_default(),
+                font.style_name().unwrap_or_default()
+            )
+            .trim()
+            .to_string(),
+            blocks,
+        })
+    }
+
+    /// Serializes the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("coverage report is always serializable")
+    }
+}
+
+impl BlockCoverage {
+    /// Percentage of characters in the block that the font has a glyph for.
+    pub fn percent(&self) -> f64 {
+        self.covered as f64 * 100.0 / self.total as f64
+    }
+}
+
+/// Formats the report as a table, with a row for each block.
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name_width = self
+            .blocks
+            .iter()
+            .map(|block| block.name.len())
+            .max()
+            .unwrap_or_default()
+            .max("block".len());
+
+        writeln!(f, "{}", self.font)?;
+        writeln!(
+            f,
+            "{:<name_width$}  {:<15}  {:>7}  {:>7}  {:>7}",
+            "block", "range", "covered", "total", "percent"
+        )?;
+
+        for block in &self.blocks {
+            writeln!(
+                f,
+                "{:<name_width$}  {:<15}  {:>7}  {:>7}  {:>6.1}%",
+                block.name,
+                format!("{}..{}", block.start, block.end),
+                block.covered,
+                block.total,
+                block.percent()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoverageReport;
+    use crate::{
+        builder::tests::test_font,
+        unicode::{BASIC_LATIN, HIRAGANA},
+    };
+
+    #[test]
+    fn test_coverage_report() {
+        let font_path = test_font();
+        let report = CoverageReport::from_font(
+            font_path,
+            0,
+            &[("basic-latin", BASIC_LATIN), ("hiragana", HIRAGANA)],
+        )
+        .unwrap();
+        let latin = &report.blocks[0];
+        let hiragana = &report.blocks[1];
+
+        assert_eq!(latin.covered + latin.missing.len(), 128);
+        assert_eq!(
+            hiragana.covered + hiragana.missing.len(),
+            HIRAGANA.block_size()
+        );
+        assert!(!latin.missing.contains(&'A'));
+
+        let text = report.to_string();
+        let hiragana_row = text
+            .lines()
+            .find(|line| line.starts_with("hiragana "))
+            .unwrap();
+
+        assert!(hiragana_row.contains("U+3040..U+309F"));
+        assert!(hiragana_row.ends_with(&format!("{:.1}%", hiragana.percent())));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+        assert_eq!(json["blocks"][1]["name"], "hiragana");
+        assert_eq!(json["blocks"][1]["start"], "U+3040");
+        assert_eq!(json["blocks"][1]["covered"], hiragana.covered);
+        assert_eq!(json["blocks"][1]["missing"][0], "\u{3040}");
+    }
+}
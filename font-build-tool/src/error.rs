@@ -1,14 +1,119 @@
-use std::io;
+use std::{io, path::PathBuf};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum BuildError {
-    #[error("io error")]
+    #[error("io error: {0}")]
     IoError(#[from] io::Error),
 
     #[error("error writing image")]
     ImageError(#[from] image::ImageError),
 
-    #[error("reading font")]
-    ReadFontError { message: &'static str },
+    #[error("initializing FreeType: {0}")]
+    InitError(#[source] freetype::Error),
+
+    #[error("reading font `{}`: {source}", path.display())]
+    ReadFontError {
+        path: PathBuf,
+        #[source]
+        source: freetype::Error,
+    },
+
+    #[error("invalid font size {0}, the size must be at least 1 pixel")]
+    InvalidFontSize(u32),
+
+    #[error("invalid bit depth {0}, the bit depth must be 1, 2, 4, or 8")]
+    InvalidBitDepth(u8),
+
+    #[error("setting font size to {size} pixels: {source}")]
+    FontSizeError {
+        size: u32,
+        #[source]
+        source: freetype::Error,
+    },
+
+    #[error("rendering glyph for U+{:04X} `{}`: {source}", *codepoint as u32, codepoint.escape_debug())]
+    RenderGlyphError {
+        codepoint: char,
+        #[source]
+        source: freetype::Error,
+    },
+
+    #[error("font has no glyph for U+{:04X} `{}`", *codepoint as u32, codepoint.escape_debug())]
+    MissingGlyph { codepoint: char },
+
+    #[error("glyph for U+{:04X} `{}` is too large for a proportional font", *codepoint as u32, codepoint.escape_debug())]
+    GlyphTooLarge { codepoint: char },
+
+    #[error("proportional, and compressed fonts only support a bit depth of 1, not {0}")]
+    UnsupportedBitDepth(u8),
+
+    #[error(
+        "glyph cell of {width}x{height} pixels is too large to compress, the limit is {max}x{max}"
+    )]
+    GlyphCellTooLarge {
+        width: usize,
+        height: usize,
+        max: usize,
+    },
+
+    #[error("raw font data of {len} bytes doesn't fit {width}x{height} glyph cells")]
+    InvalidRawData {
+        len: usize,
+        width: usize,
+        height: usize,
+    },
+
+    #[error("invalid font name `{0}`, names must be 1 to 24 bytes long, and can't contain NUL")]
+    InvalidFontName(String),
+
+    #[error("font image already contains a font named `{0}`")]
+    DuplicateFontName(String),
+
+    #[error("font `{0}` can't be packed, its cell size, and metrics must be under 256 pixels")]
+    FontTooLargeToPack(String),
+
+    #[error("invalid alignment {0}, fonts must be aligned to at least 1 byte")]
+    InvalidAlignment(u32),
+
+    #[error("reading manifest `{}`: {source}", path.display())]
+    ReadManifestError {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("parsing manifest `{}`: {source}", path.display())]
+    ManifestError {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("unknown unicode block `{0}`")]
+    UnknownUnicodeBlock(String),
+
+    #[error("font `{0}` doesn't list any unicode blocks or charset files")]
+    EmptyCharacterSet(String),
+
+    #[error("font has no vertical alternates for any of the characters in its glyph mapping")]
+    NoVerticalAlternates,
+
+    #[error("proportional fonts can't have vertical alternates")]
+    VerticalProportionalFont,
+
+    #[error("invalid code page mapping on line {0}")]
+    InvalidCodePageMapping(usize),
+
+    #[error("code page bytes 0x{0:X} are outside of the lead, and trail byte ranges")]
+    CodePageBytesOutOfRange(u32),
+
+    #[error("codepoint U+{0:04X} is outside of the basic multilingual plane")]
+    CodePageCodepointOutOfRange(u32),
+
+    #[error("invalid byte range `{0}`, ranges are written as two hex bytes, like `81-FE`")]
+    InvalidByteRange(String),
+
+    #[error("default size {0} isn't generated for any font")]
+    UnknownDefaultSize(u32),
 }
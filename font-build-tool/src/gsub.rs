@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+/// Features that substitute the glyphs used in vertical text.
+const VERTICAL_FEATURES: [&[u8; 4]; 2] = [b"vert", b"vrt2"];
+
+/// Lookup type of a single substitution.
+const SINGLE_SUBSTITUTION: u16 = 1;
+
+/// Lookup type of an extension, which points to a subtable of another type
+/// with a 32-bit offset.
+const EXTENSION_SUBSTITUTION: u16 = 7;
+
+/// Returns `true` for the punctuation, brackets, and the long vowel mark that
+/// have a different form in vertical text.
+pub(crate) fn has_vertical_form(chr: char) -> bool {
+    match chr {
+        // General Punctuation, like `…`, and `‥`
+        '\u{2010}'..='\u{206F}' => true,
+
+        // CJK Symbols and Punctuation, like `、`, `。`, and `「`
+        '\u{3000}'..='\u{303F}' => true,
+
+        // Katakana-Hiragana Prolonged Sound Mark, and its half-width form
+        '\u{30FC}' | '\u{FF70}' => true,
+
+        // Vertical forms of the small, and fullwidth variants of ASCII
+        // punctuation, like `（`, and `～`
+        '\u{FE30}'..='\u{FE4F}' | '\u{FF5F}'..='\u{FF65}' => true,
+        '\u{FF01}'..='\u{FF5E}' => {
+            char::from_u32(chr as u32 - 0xFEE0).is_some_and(|ascii| ascii.is_ascii_punctuation())
+        }
+
+        _ => false,
+    }
+}
+
+/// Returns a table of an OpenType font, or of one of the faces in a font
+/// collection.
+pub(crate) fn sfnt_table<'a>(font: &'a [u8], face_index: usize, tag: &[u8; 4]) -> Option<&'a [u8]> {
+    let offset = if font.get(..4)? == b"ttcf" {
+        if face_index >= read_u32(font, 8)? as usize {
+            return None;
+        }
+
+        read_u32(font, 12 + face_index * 4)? as usize
+    } else {
+        0
+    };
+    let num_tables = usize::from(read_u16(font, offset + 4)?);
+
+    (0..num_tables).find_map(|index| {
+        let record = offset + 12 + index * 16;
+
+        if font.get(record..record + 4)? != tag {
+            return None;
+        }
+
+        let table_offset = read_u32(font, record + 8)? as usize;
+        let len = read_u32(font, record + 12)? as usize;
+
+        font.get(table_offset..table_offset.checked_add(len)?)
+    })
+}
+
+/// Returns the glyph substitutions of the `vert`, and `vrt2` features of a
+/// `GSUB` table, by glyph index. Only single substitutions are read, since
+/// they're the only kind used for vertical alternates. Parts of the table
+/// that are out of bounds are skipped.
+pub(crate) fn vertical_substitutions(gsub: &[u8]) -> HashMap<u16, u16> {
+    let mut substitutions = HashMap::new();
+    let mut lookup_indices = Vec::new();
+
+    let feature_list = read_u16(gsub, 6).map(usize::from).unwrap_or(gsub.len());
+    let lookup_list = read_u16(gsub, 8).map(usize::from).unwrap_or(gsub.len());
+    let num_features = read_u16(gsub, feature_list).unwrap_or(0);
+
+    for index in 0..usize::from(num_features) {
+        let record = feature_list + 2 + index * 6;
+
+        if !VERTICAL_FEATURES
+            .iter()
+            .any(|tag| gsub.get(record..record + 4) == Some(&tag[..]))
+        {
+            continue;
+        }
+
+        let feature = feature_list + usize::from(read_u16(gsub, record + 4).unwrap_or(0));
+        let num_lookups = read_u16(gsub, feature + 2).unwrap_or(0);
+
+        lookup_indices.extend(
+            (0..usize::from(num_lookups)).filter_map(|i| read_u16(gsub, feature + 4 + i * 2)),
+        );
+    }
+
+    // Both features usually share the same lookups.
+    lookup_indices.sort_unstable();
+    lookup_indices.dedup();
+
+    for lookup_index in lookup_indices {
+        let Some(lookup_offset) = read_u16(gsub, lookup_list + 2 + usize::from(lookup_index) * 2)
+        else {
+            continue;
+        };
+        let lookup = lookup_list + usize::from(lookup_offset);
+        let lookup_type = read_u16(gsub, lookup).unwrap_or(0);
+        let num_subtables = read_u16(gsub, lookup + 4).unwrap_or(0);
+
+        for index in 0..usize::from(num_subtables) {
+            let Some(subtable_offset) = read_u16(gsub, lookup + 6 + index * 2) else {
+                continue;
+            };
+            let mut subtable = lookup + usize::from(subtable_offset);
+
+            if lookup_type == EXTENSION_SUBSTITUTION {
+                match (read_u16(gsub, subtable + 2), read_u32(gsub, subtable + 4)) {
+                    (Some(SINGLE_SUBSTITUTION), Some(offset)) => subtable += offset as usize,
+                    _ => continue,
+                }
+            } else if lookup_type != SINGLE_SUBSTITUTION {
+                continue;
+            }
+
+            read_single_substitution(gsub, subtable, &mut substitutions);
+        }
+    }
+
+    substitutions
+}
+
+/// Adds the substitutions of a single substitution subtable. Glyphs that
+/// already have a substitution from an earlier lookup keep it.
+fn read_single_substitution(gsub: &[u8], subtable: usize, substitutions: &mut HashMap<u16, u16>) {
+    let format = read_u16(gsub, subtable);
+    let coverage = subtable + usize::from(read_u16(gsub, subtable + 2).unwrap_or(0));
+
+    for (coverage_index, glyph) in coverage_glyphs(gsub, coverage) {
+        let substitute = match format {
+            Some(1) => read_u16(gsub, subtable + 4).map(|delta| glyph.wrapping_add(delta)),
+            Some(2) => read_u16(gsub, subtable + 6 + coverage_index * 2),
+            _ => None,
+        };
+
+        if let Some(substitute) = substitute {
+            substitutions.entry(glyph).or_insert(substitute);
+        }
+    }
+}
+
+/// Returns the glyphs in a coverage table, along with their coverage index.
+fn coverage_glyphs(gsub: &[u8], coverage: usize) -> Vec<(usize, u16)> {
+    let count = usize::from(read_u16(gsub, coverage + 2).unwrap_or(0));
+
+    match read_u16(gsub, coverage) {
+        Some(1) => (0..count)
+            .map_while(|index| read_u16(gsub, coverage + 4 + index * 2))
+            .enumerate()
+            .collect(),
+        Some(2) => (0..count)
+            .map_while(|index| {
+                let record = coverage + 4 + index * 6;
+
+                Some((
+                    read_u16(gsub, record)?,
+                    read_u16(gsub, record + 2)?,
+                    usize::from(read_u16(gsub, record + 4)?),
+                ))
+            })
+            .flat_map(|(start, end, start_index)| {
+                (start..=end)
+                    .enumerate()
+                    .map(move |(offset, glyph)| (start_index + offset, glyph))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_vertical_form, sfnt_table, vertical_substitutions};
+
+    /// Builds a `GSUB` table with a `liga` feature, and a `vert` feature.
+    /// `vert` uses a single substitution with a delta for glyphs 10, and 11,
+    /// and an extension that substitutes glyphs 20 to 22 with a list. `liga`
+    /// substitutes glyph 30.
+    fn gsub() -> Vec<u8> {
+        #[rustfmt::skip]
+        let words: &[u16] = &[
+            // 0: Header: version, script list, feature list, lookup list
+            1, 0, 0, 10, 38,
+            // 10: Feature list with `liga` at 24, and `vert` at 30
+            2,
+            0x6C69, 0x6761, 14,
+            0x7665, 0x7274, 20,
+            // 24: `liga` uses lookup 0
+            0, 1, 0,
+            // 30: `vert` uses lookups 1, and 2
+            0, 2, 1, 2,
+            // 38: Lookup list, with lookups at 106, 46, and 68
+            3, 68, 8, 30,
+            // 46: Single substitution with a subtable at 54
+            1, 0, 1, 8,
+            // 54: Format 1, coverage at 60, and a delta
+            1, 6, 100,
+            // 60: Coverage format 1 with glyphs 10, and 11
+            1, 2, 10, 11,
+            // 68: Extension with a subtable at 76
+            7, 0, 1, 8,
+            // 76: Extension of a single substitution at 84
+            1, 1, 0, 8,
+            // 84: Format 2, coverage at 96, and the substitutes of 20 to 22
+            2, 12, 3, 200, 201, 202,
+            // 96: Coverage format 2 with a range of glyphs 20 to 22
+            2, 1, 20, 22, 0,
+            // 106: Single substitution with a subtable at 114
+            1, 0, 1, 8,
+            // 114: Format 1, coverage at 120, and a delta
+            1, 6, 1,
+            // 120: Coverage format 1 with glyph 30
+            1, 1, 30,
+        ];
+
+        words.iter().flat_map(|word| word.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_vertical_substitutions() {
+        let substitutions = vertical_substitutions(&gsub());
+
+        assert_eq!(substitutions.len(), 5);
+        assert_eq!(substitutions[&10], 110);
+        assert_eq!(substitutions[&11], 111);
+        assert_eq!(substitutions[&20], 200);
+        assert_eq!(substitutions[&22], 202);
+    }
+
+    #[test]
+    fn test_truncated_gsub() {
+        let gsub = gsub();
+
+        for len in 0..gsub.len() {
+            assert!(vertical_substitutions(&gsub[..len]).len() <= 5);
+        }
+    }
+
+    #[test]
+    fn test_sfnt_table() {
+        let mut font = vec![0, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0];
+
+        for (tag, offset) in [(b"GDEF", 44u32), (b"GSUB", 48)] {
+            font.extend(tag);
+            font.extend([0; 4]);
+            font.extend(offset.to_be_bytes());
+            font.extend(4u32.to_be_bytes());
+        }
+
+        font.extend(b"gdefgsub");
+
+        assert_eq!(sfnt_table(&font, 0, b"GSUB"), Some(&b"gsub"[..]));
+        assert_eq!(sfnt_table(&font, 0, b"GPOS"), None);
+
+        // The same face in a collection
+        let mut collection = b"ttcf".to_vec();
+
+        collection.extend([0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 16]);
+        collection.extend(&font[..44]);
+        collection.extend(b"gdefgsub");
+
+        for record in [16 + 12 + 8, 16 + 28 + 8] {
+            let offset = u32::from_be_bytes(collection[record..record + 4].try_into().unwrap());
+
+            collection[record..record + 4].copy_from_slice(&(offset + 16).to_be_bytes());
+        }
+
+        assert_eq!(sfnt_table(&collection, 0, b"GSUB"), Some(&b"gsub"[..]));
+        assert_eq!(sfnt_table(&collection, 1, b"GSUB"), None);
+    }
+
+    #[test]
+    fn test_has_vertical_form() {
+        for chr in ['、', '。', '「', '』', 'ー', '…', '（', '～', '！'] {
+            assert!(has_vertical_form(chr), "{}", chr);
+        }
+
+        for chr in ['a', '(', '中', 'あ', 'ア', 'Ａ', '０'] {
+            assert!(!has_vertical_form(chr), "{}", chr);
+        }
+    }
+}
@@ -1,9 +1,26 @@
-#![feature(mixed_integer_ops)]
-
 mod builder;
+mod charset;
+mod code_page;
+mod compressed;
+mod coverage;
 mod error;
+mod gsub;
+mod manifest;
+mod mapping;
+mod pack;
+mod proportional;
 mod unicode;
 
-pub use builder::{FontOutputSettings, MonoFontBuilder, MonoFontData};
+pub use builder::{
+    BitDepth, FontOutputSettings, MissingGlyphPolicy, MonoFontBuilder, MonoFontData,
+};
+pub use charset::read_charset;
+pub use code_page::{parse_byte_range, CodePageTable};
+pub use compressed::{CompressedFontData, CompressionStats};
+pub use coverage::{BlockCoverage, CoverageReport};
 pub use error::BuildError;
+pub use manifest::{FontManifest, Manifest};
+pub use mapping::{GlyphMappingSource, FALLBACK_CHAR};
+pub use pack::{FontImageBuilder, DEFAULT_ALIGNMENT};
+pub use proportional::ProportionalFontData;
 pub use unicode::*;
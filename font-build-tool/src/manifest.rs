@@ -0,0 +1,991 @@
+use crate::{
+    builder::{BitDepth, FontOutputSettings, MissingGlyphPolicy, MonoFontBuilder, MonoFontData},
+    charset::read_charset,
+    error::BuildError,
+    mapping::GlyphMappingSource,
+    pack::FontImageBuilder,
+    unicode::unicode_blocks_by_name,
+};
+use image::GrayImage;
+use serde::Deserialize;
+use std::{
+    collections::BTreeSet,
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Placeholder in a font's source path that is replaced with the contents of
+/// the manifest's version file.
+const VERSION_PLACEHOLDER: &str = "{version}";
+
+/// Every version of embedded-graphics that generated fonts can be built with.
+const EMBEDDED_GRAPHICS_FEATURES: [&str; 2] = ["embedded-graphics-07", "embedded-graphics-08"];
+
+/// Version of embedded-graphics that is used by default.
+const DEFAULT_EMBEDDED_GRAPHICS_FEATURE: &str = "embedded-graphics-08";
+
+const GLYPH_MAPPING_CRATE: &str = "embedded-graphics-cjk-glyph-mapping";
+const GRAY_FONT_CRATE: &str = "embedded-graphics-cjk-gray-font";
+const COMPRESSED_FONT_CRATE: &str = "embedded-graphics-cjk-compressed-font";
+
+/// Describes every font that is generated for a font crate.
+///
+/// Paths are relative to the directory containing the manifest.
+///
+/// ```toml
+/// version-file = "ZPIX_VERSION"
+/// source-dir = "src"
+/// png-dir = "png"
+/// default-sizes = [12]
+///
+/// [[font]]
+/// source = "target/font/zpix-{version}/zpix.ttf"
+/// output-name = "zpix"
+/// const-name = "ZPIX"
+/// sizes = [12, 24]
+/// blocks = ["cjk-radicals-supplement", "cjk-unified-ideographs"]
+/// vertical = true
+///
+/// [font.half-width]
+/// output-name = "zpix_half_width"
+/// const-name = "ZPIX_HALF_WIDTH"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Manifest {
+    /// File containing the version of the source fonts, which is substituted
+    /// for `{version}` in each font's source path.
+    pub version_file: Option<PathBuf>,
+
+    /// Directory where the Rust source code, and bitmap data is written.
+    #[serde(default = "default_source_dir")]
+    pub source_dir: PathBuf,
+
+    /// Directory where PNGs are written for debugging. PNGs aren't written if
+    /// this isn't set.
+    pub png_dir: Option<PathBuf>,
+
+    /// Sizes that are enabled by default. Each size is only included if its
+    /// `size-{size}` feature is enabled, and every size is enabled by default
+    /// if this isn't set.
+    pub default_sizes: Option<Vec<u32>>,
+
+    #[serde(rename = "font")]
+    pub fonts: Vec<FontManifest>,
+
+    /// Directory containing the manifest.
+    #[serde(skip)]
+    root: PathBuf,
+}
+
+/// A source font, and the sizes to generate from it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FontManifest {
+    /// Path to the TTF or OTF file.
+    pub source: String,
+
+    /// Index of the face to use, for font files containing multiple faces.
+    #[serde(default)]
+    pub face_index: isize,
+
+    /// Prefix used for the generated file names (`{output-name}_{size}.rs`,
+    /// and `data/{output-name}-{size}.bin`).
+    pub output_name: String,
+
+    /// Prefix used for the exported constants (`{const-name}_{size}`).
+    pub const_name: String,
+
+    /// Font sizes to generate.
+    pub sizes: Vec<u32>,
+
+    /// Names of the unicode code blocks to include.
+    #[serde(default)]
+    pub blocks: Vec<String>,
+
+    /// UTF-8 text files. Only the characters used in these files are
+    /// included.
+    #[serde(default)]
+    pub charset_files: Vec<PathBuf>,
+
+    /// See [`FontOutputSettings::intensity_threshold`].
+    #[serde(default = "default_intensity_threshold")]
+    pub intensity_threshold: u8,
+
+    /// See [`FontOutputSettings::bit_depth`]. Fonts with a bit depth above 1
+    /// are generated as a `GrayFont`.
+    #[serde(default)]
+    pub bit_depth: BitDepth,
+
+    /// See [`FontOutputSettings::missing_glyphs`].
+    #[serde(default)]
+    pub missing_glyphs: MissingGlyphPolicy,
+
+    /// Compresses each glyph, and generates a `CompressedFont` instead of a
+    /// `MonoFont`. Only fonts with a bit depth of 1 can be compressed.
+    #[serde(default)]
+    pub compress: bool,
+
+    /// Cargo feature that has to be enabled to include the font.
+    pub feature: Option<String>,
+
+    /// Also generates the vertical alternates of punctuation, brackets, and
+    /// the long vowel mark, from the font's `vert`, and `vrt2` features, as
+    /// `{output-name}_vertical_{size}`, and `{const-name}_VERTICAL_{size}`.
+    #[serde(default)]
+    pub vertical: bool,
+
+    /// Half-width companion font, generated from the same source at each of
+    /// the sizes, for drawing mixed text with a `MixedWidthTextStyle`.
+    pub half_width: Option<HalfWidthManifest>,
+}
+
+/// A font with the half-width glyphs of its parent font. Wide, and
+/// fullwidth characters are left out, so the glyph width is that of the
+/// widest half-width glyph.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct HalfWidthManifest {
+    /// Prefix used for the generated file names.
+    pub output_name: String,
+
+    /// Prefix used for the exported constants.
+    pub const_name: String,
+
+    /// Names of the unicode code blocks to include.
+    #[serde(default = "default_half_width_blocks")]
+    pub blocks: Vec<String>,
+}
+
+fn default_source_dir() -> PathBuf {
+    PathBuf::from("src")
+}
+
+fn default_intensity_threshold() -> u8 {
+    128
+}
+
+fn default_half_width_blocks() -> Vec<String> {
+    vec!["basic-latin".to_string()]
+}
+
+impl Manifest {
+    /// Reads a manifest from a TOML file.
+    pub fn from_path<P>(manifest_path: P) -> Result<Manifest, BuildError>
+    where
+        P: AsRef<Path>,
+    {
+        let manifest_path = manifest_path.as_ref();
+        let manifest =
+            fs::read_to_string(manifest_path).map_err(|source| BuildError::ReadManifestError {
+                path: manifest_path.to_path_buf(),
+                source,
+            })?;
+        let mut manifest: Manifest =
+            toml::from_str(&manifest).map_err(|source| BuildError::ManifestError {
+                path: manifest_path.to_path_buf(),
+                source,
+            })?;
+
+        manifest.root = manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        Ok(manifest)
+    }
+
+    /// Generates the bitmap data, and Rust source code for every font size,
+    /// along with a `lib.rs` that re-exports each of them. If there's a
+    /// `Cargo.toml` next to the manifest, its `[features]` table is replaced
+    /// with one that has a feature for each size.
+    pub fn build(&self) -> Result<(), BuildError> {
+        let source_dir = self.root.join(&self.source_dir);
+        let features = self.features_table()?;
+
+        fs::create_dir_all(source_dir.join("data"))?;
+
+        if let Some(png_dir) = &self.png_dir {
+            fs::create_dir_all(self.root.join(png_dir))?;
+        }
+
+        self.for_each_font(|font, render, settings, output_name, _| {
+            self.build_font(render(settings)?, settings, output_name, font.compress)
+        })?;
+
+        save_lib_source(source_dir.join("lib.rs"), &self.modules())?;
+
+        let cargo_toml_path = self.root.join("Cargo.toml");
+
+        if cargo_toml_path.exists() {
+            save_features_table(cargo_toml_path, &features)?;
+        }
+
+        Ok(())
+    }
+
+    /// Packs every font size into a single image for external flash. Fonts
+    /// are named like their generated modules (`{output-name}_{size}`).
+    pub fn pack<P>(&self, image_path: P, alignment: u32) -> Result<(), BuildError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut image = FontImageBuilder::new(alignment)?;
+
+        self.for_each_font(|font, render, settings, output_name, _| {
+            let name = format!("{}_{}", output_name, settings.font_size);
+            let bitmap = render(settings)?;
+
+            if font.compress {
+                let compressed = bitmap.compress()?;
+
+                println!("{}", compressed.stats());
+                image.add_compressed_font(&name, &compressed)
+            } else {
+                image.add_font(&name, &bitmap)
+            }
+        })?;
+
+        image.save(self.root.join(image_path))
+    }
+
+    /// Calls `build` with a function that renders the font, and the settings
+    /// for every font size, each size of the half-width companion fonts, and
+    /// of the vertical alternates, along with their output, and const names.
+    fn for_each_font<F>(&self, mut build: F) -> Result<(), BuildError>
+    where
+        F: FnMut(&FontManifest, &Render, FontOutputSettings, &str, &str) -> Result<(), BuildError>,
+    {
+        let version = match &self.version_file {
+            Some(version_file) => fs::read_to_string(self.root.join(version_file))?,
+            None => String::new(),
+        };
+
+        for font in &self.fonts {
+            let source = self
+                .root
+                .join(font.source.replace(VERSION_PLACEHOLDER, version.trim()));
+            let mono_font_builder = MonoFontBuilder::with_glyph_mapping(
+                &source,
+                font.face_index,
+                font.glyph_mapping(&self.root)?,
+            )?;
+            let half_width_builder = match &font.half_width {
+                Some(half_width) => Some(MonoFontBuilder::with_glyph_mapping(
+                    &source,
+                    font.face_index,
+                    half_width.glyph_mapping()?,
+                )?),
+                None => None,
+            };
+
+            for &font_size in &font.sizes {
+                let settings = FontOutputSettings {
+                    font_size,
+                    intensity_threshold: font.intensity_threshold,
+                    bit_depth: font.bit_depth,
+                    missing_glyphs: font.missing_glyphs,
+                };
+
+                build(
+                    font,
+                    &|settings| mono_font_builder.build(settings),
+                    settings,
+                    &font.output_name,
+                    &font.const_name,
+                )?;
+
+                if let (Some(half_width), Some(half_width_builder)) =
+                    (&font.half_width, &half_width_builder)
+                {
+                    build(
+                        font,
+                        &|settings| half_width_builder.build(settings),
+                        settings,
+                        &half_width.output_name,
+                        &half_width.const_name,
+                    )?;
+                }
+
+                if font.vertical {
+                    build(
+                        font,
+                        &|settings| mono_font_builder.build_vertical(settings),
+                        settings,
+                        &font.vertical_output_name(),
+                        &font.vertical_const_name(),
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generates the bitmap data, Rust source code, and optionally the PNG
+    /// for a single font size.
+    fn build_font(
+        &self,
+        bitmap: MonoFontData<GrayImage>,
+        settings: FontOutputSettings,
+        output_name: &str,
+        compress: bool,
+    ) -> Result<(), BuildError> {
+        let source_dir = self.root.join(&self.source_dir);
+        let module = format!("{}_{}", output_name, settings.font_size);
+        let bitmap_file = format!("{}-{}.bin", output_name, settings.font_size);
+
+        let rust_source_path = source_dir.join(format!("{}.rs", module));
+
+        if compress {
+            let font = bitmap.compress()?;
+
+            println!("{}", font.stats());
+            font.save_raw(source_dir.join("data").join(&bitmap_file))?;
+            font.save_rust_source(rust_source_path, format!("data/{}", bitmap_file))?;
+        } else {
+            bitmap.save_raw(source_dir.join("data").join(&bitmap_file))?;
+            bitmap.save_rust_source(rust_source_path, format!("data/{}", bitmap_file))?;
+        }
+
+        if let Some(png_dir) = &self.png_dir {
+            bitmap.save_png(
+                self.root
+                    .join(png_dir)
+                    .join(format!("{}-{}.png", output_name, settings.font_size)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// The modules generated for every font size, in the same order as
+    /// `for_each_font`.
+    fn modules(&self) -> Vec<ModuleSource> {
+        let mut modules = Vec::new();
+
+        for font in &self.fonts {
+            let mut names = vec![(font.output_name.clone(), font.const_name.clone())];
+
+            if let Some(half_width) = &font.half_width {
+                names.push((
+                    half_width.output_name.clone(),
+                    half_width.const_name.clone(),
+                ));
+            }
+
+            if font.vertical {
+                names.push((font.vertical_output_name(), font.vertical_const_name()));
+            }
+
+            for &font_size in &font.sizes {
+                for (output_name, const_name) in &names {
+                    modules.push(ModuleSource {
+                        module: format!("{}_{}", output_name, font_size),
+                        const_name: format!("{}_{}", const_name, font_size),
+                        features: font
+                            .feature
+                            .iter()
+                            .cloned()
+                            .chain([size_feature(font_size)])
+                            .collect(),
+                    });
+                }
+            }
+        }
+
+        modules
+    }
+
+    /// Crates that the generated fonts use, and whether each one is
+    /// optional. Crates that are only used by fonts behind a feature are
+    /// optional.
+    fn dependencies(&self) -> Vec<(&'static str, bool)> {
+        [GLYPH_MAPPING_CRATE, GRAY_FONT_CRATE, COMPRESSED_FONT_CRATE]
+            .into_iter()
+            .filter_map(|name| {
+                let mut fonts = self
+                    .fonts
+                    .iter()
+                    .filter(|font| font.dependencies().contains(&name))
+                    .peekable();
+
+                fonts.peek()?;
+
+                Some((name, fonts.all(|font| font.feature.is_some())))
+            })
+            .collect()
+    }
+
+    /// Returns the `[features]` table of the font crate. There's a feature
+    /// for each size, and one for each version of embedded-graphics, which
+    /// is forwarded to the crates the generated fonts use. Features named in
+    /// the manifest enable the optional crates their fonts need.
+    fn features_table(&self) -> Result<String, BuildError> {
+        let sizes: BTreeSet<u32> = self
+            .fonts
+            .iter()
+            .flat_map(|font| font.sizes.iter().copied())
+            .collect();
+        let default_sizes = match &self.default_sizes {
+            Some(default_sizes) => default_sizes.clone(),
+            None => sizes.iter().copied().collect(),
+        };
+
+        if let Some(size) = default_sizes.iter().find(|size| !sizes.contains(size)) {
+            return Err(BuildError::UnknownDefaultSize(*size));
+        }
+
+        let dependencies = self.dependencies();
+        let mut features = vec![(
+            "default".to_string(),
+            [DEFAULT_EMBEDDED_GRAPHICS_FEATURE.to_string()]
+                .into_iter()
+                .chain(default_sizes.into_iter().map(size_feature))
+                .collect(),
+        )];
+
+        for version in EMBEDDED_GRAPHICS_FEATURES {
+            let mut forwarded: Vec<String> = dependencies
+                .iter()
+                .map(|(name, optional)| {
+                    format!("{}{}/{}", name, if *optional { "?" } else { "" }, version)
+                })
+                .collect();
+
+            forwarded.sort();
+            features.push((version.to_string(), forwarded));
+        }
+
+        let font_features: BTreeSet<&String> = self
+            .fonts
+            .iter()
+            .filter_map(|font| font.feature.as_ref())
+            .collect();
+
+        for feature in font_features {
+            let enabled: BTreeSet<String> = self
+                .fonts
+                .iter()
+                .filter(|font| font.feature.as_ref() == Some(feature))
+                .flat_map(|font| font.dependencies())
+                .filter(|name| dependencies.contains(&(*name, true)))
+                .map(str::to_string)
+                .collect();
+
+            features.push((feature.clone(), enabled.into_iter().collect()));
+        }
+
+        for size in sizes {
+            features.push((size_feature(size), Vec::new()));
+        }
+
+        let mut table = String::from("[features]\n");
+
+        for (name, values) in features {
+            let _ = write!(table, "{:<24}= ", name);
+
+            if values.len() < 2 {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| format!("\"{}\"", value))
+                    .collect();
+                let _ = writeln!(table, "[{}]", values.join(", "));
+            } else {
+                table.push_str("[\n");
+
+                for value in values {
+                    let _ = writeln!(table, "    \"{}\",", value);
+                }
+
+                table.push_str("]\n");
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+impl FontManifest {
+    fn vertical_output_name(&self) -> String {
+        format!("{}_vertical", self.output_name)
+    }
+
+    fn vertical_const_name(&self) -> String {
+        format!("{}_VERTICAL", self.const_name)
+    }
+
+    /// Crates that the generated source code of the font uses.
+    fn dependencies(&self) -> Vec<&'static str> {
+        let mut dependencies = vec![GLYPH_MAPPING_CRATE];
+
+        if self.bit_depth != BitDepth::One {
+            dependencies.push(GRAY_FONT_CRATE);
+        }
+
+        if self.compress {
+            dependencies.push(COMPRESSED_FONT_CRATE);
+        }
+
+        dependencies
+    }
+
+    /// Determines which characters are included in the font. Unicode blocks
+    /// are mapped with ranges, but as soon as charset files are used, the
+    /// font only contains the exact set of characters.
+    fn glyph_mapping(&self, root: &Path) -> Result<GlyphMappingSource, BuildError> {
+        let unicode_blocks = unicode_blocks_by_name(&self.blocks)?;
+
+        if self.charset_files.is_empty() {
+            if unicode_blocks.is_empty() {
+                return Err(BuildError::EmptyCharacterSet(self.output_name.clone()));
+            }
+
+            Ok(GlyphMappingSource::from_blocks(&unicode_blocks))
+        } else {
+            let charset = read_charset(self.charset_files.iter().map(|path| root.join(path)))?;
+            let block_chars = unicode_blocks.iter().flat_map(|block| block.range());
+
+            Ok(GlyphMappingSource::from_chars(
+                charset.into_iter().chain(block_chars),
+            ))
+        }
+    }
+}
+
+impl HalfWidthManifest {
+    /// Covers the half-width characters in each of the unicode code blocks.
+    fn glyph_mapping(&self) -> Result<GlyphMappingSource, BuildError> {
+        let unicode_blocks = unicode_blocks_by_name(&self.blocks)?;
+
+        if unicode_blocks.is_empty() {
+            return Err(BuildError::EmptyCharacterSet(self.output_name.clone()));
+        }
+
+        Ok(GlyphMappingSource::from_blocks(&unicode_blocks).half_width())
+    }
+}
+
+/// Renders a font at the given settings.
+type Render<'a> = dyn Fn(FontOutputSettings) -> Result<MonoFontData<GrayImage>, BuildError> + 'a;
+
+/// A generated module, the name its `FONT` constant is exported as, and the
+/// features that have to be enabled to include it.
+struct ModuleSource {
+    module: String,
+    const_name: String,
+    features: Vec<String>,
+}
+
+impl ModuleSource {
+    /// Attribute that only includes the module if its features are enabled.
+    fn cfg_attribute(&self) -> String {
+        let features: Vec<String> = self
+            .features
+            .iter()
+            .map(|feature| format!("feature = \"{}\"", feature))
+            .collect();
+
+        match features.as_slice() {
+            [] => String::new(),
+            [feature] => format!("#[cfg({})]\n", feature),
+            _ => format!("#[cfg(all({}))]\n", features.join(", ")),
+        }
+    }
+}
+
+/// Name of the feature that includes every font of a size.
+fn size_feature(font_size: u32) -> String {
+    format!("size-{}", font_size)
+}
+
+/// Writes a `lib.rs` that declares each generated module, and re-exports its
+/// `FONT`, and `GLYPH_MAPPING` constants with the given name.
+fn save_lib_source<P>(lib_path: P, modules: &[ModuleSource]) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    fs::write(lib_path, lib_source(modules))
+}
+
+/// Replaces the `[features]` table of a `Cargo.toml`, keeping everything else
+/// as it is.
+fn save_features_table<P>(cargo_toml_path: P, table: &str) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let cargo_toml = fs::read_to_string(&cargo_toml_path)?;
+
+    fs::write(cargo_toml_path, replace_features_table(&cargo_toml, table))
+}
+
+/// Replaces the `[features]` table, or adds it after the `[package]` table if
+/// there isn't one.
+fn replace_features_table(cargo_toml: &str, table: &str) -> String {
+    let lines: Vec<&str> = cargo_toml.lines().collect();
+    let is_table = |line: &&str| line.starts_with('[');
+    let (start, end) = match lines.iter().position(|line| *line == "[features]") {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(is_table)
+                .map_or(lines.len(), |end| start + 1 + end);
+
+            (start, end)
+        }
+        None => {
+            let start = lines
+                .iter()
+                .skip(1)
+                .position(is_table)
+                .map_or(lines.len(), |start| start + 1);
+
+            (start, start)
+        }
+    };
+    let mut source = String::new();
+
+    for line in &lines[..start] {
+        source.push_str(line);
+        source.push('\n');
+    }
+
+    if !source.is_empty() && !source.ends_with("\n\n") {
+        source.push('\n');
+    }
+
+    source.push_str(table);
+
+    if end < lines.len() {
+        source.push('\n');
+
+        for line in &lines[end..] {
+            source.push_str(line);
+            source.push('\n');
+        }
+    }
+
+    source
+}
+
+fn lib_source(modules: &[ModuleSource]) -> String {
+    let mut source = String::from(
+        "// This is generated code. Any modifications to this file will\n\
+         // be overwritten.\n",
+    );
+
+    for module in modules {
+        let _ = writeln!(source, "{}mod {};", module.cfg_attribute(), module.module);
+    }
+
+    source.push('\n');
+
+    for module in modules {
+        let _ = writeln!(
+            source,
+            "{0}pub use {1}::FONT as {2};\n{0}pub use {1}::GLYPH_MAPPING as {2}_GLYPH_MAPPING;",
+            module.cfg_attribute(),
+            module.module,
+            module.const_name
+        );
+    }
+
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lib_source, replace_features_table, Manifest, ModuleSource};
+    use crate::builder::{BitDepth, MissingGlyphPolicy};
+    use crate::GlyphMappingSource;
+    use std::{collections::BTreeMap, fs, io::Write, path::Path};
+    use tempfile::NamedTempFile;
+
+    const MANIFEST: &str = r#"
+version-file = "ZPIX_VERSION"
+png-dir = "png"
+
+[[font]]
+source = "target/font/zpix-{version}/zpix.ttf"
+output-name = "zpix"
+const-name = "ZPIX"
+sizes = [12, 24]
+blocks = ["cjk-radicals-supplement", "CJK Unified Ideographs"]
+"#;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        let font = &manifest.fonts[0];
+
+        assert_eq!(manifest.source_dir, Path::new("src"));
+        assert_eq!(font.sizes, [12, 24]);
+        assert_eq!(font.face_index, 0);
+        assert_eq!(font.intensity_threshold, 128);
+        assert_eq!(font.missing_glyphs, MissingGlyphPolicy::Fallback);
+        assert_eq!(font.bit_depth, BitDepth::One);
+        assert!(!font.compress);
+        assert!(!font.vertical);
+
+        match font.glyph_mapping(Path::new(".")).unwrap() {
+            GlyphMappingSource::Ranges(ranges) => assert_eq!(
+                ranges,
+                ['?'..='?', '\u{2E80}'..='\u{2EF3}', '\u{4E00}'..='\u{9FFF}']
+            ),
+            GlyphMappingSource::Sparse(_) => panic!("expected ranges"),
+        }
+    }
+
+    #[test]
+    fn test_missing_glyphs() {
+        let manifest: Manifest =
+            toml::from_str(&format!("{}missing-glyphs = \"skip\"\n", MANIFEST)).unwrap();
+
+        assert_eq!(manifest.fonts[0].missing_glyphs, MissingGlyphPolicy::Skip);
+    }
+
+    #[test]
+    fn test_bit_depth() {
+        let manifest: Manifest = toml::from_str(&format!("{}bit-depth = 4\n", MANIFEST)).unwrap();
+
+        assert_eq!(manifest.fonts[0].bit_depth, BitDepth::Four);
+        assert!(toml::from_str::<Manifest>(&format!("{}bit-depth = 3\n", MANIFEST)).is_err());
+    }
+
+    #[test]
+    fn test_half_width() {
+        let manifest: Manifest = toml::from_str(&format!(
+            "{}\n[font.half-width]\noutput-name = \"zpix_half_width\"\nconst-name = \"ZPIX_HALF_WIDTH\"\n",
+            MANIFEST
+        ))
+        .unwrap();
+        let half_width = manifest.fonts[0].half_width.as_ref().unwrap();
+
+        assert_eq!(half_width.blocks, ["basic-latin"]);
+
+        match half_width.glyph_mapping().unwrap() {
+            GlyphMappingSource::Ranges(ranges) => assert_eq!(ranges, ['\u{0}'..='\u{7F}']),
+            GlyphMappingSource::Sparse(_) => panic!("expected ranges"),
+        }
+    }
+
+    #[test]
+    fn test_vertical_modules() {
+        let manifest: Manifest = toml::from_str(&format!("{}vertical = true\n", MANIFEST)).unwrap();
+        let modules: Vec<(String, String)> = manifest
+            .modules()
+            .into_iter()
+            .map(|module| (module.module, module.const_name))
+            .collect();
+
+        assert_eq!(
+            modules,
+            [
+                ("zpix_12", "ZPIX_12"),
+                ("zpix_vertical_12", "ZPIX_VERTICAL_12"),
+                ("zpix_24", "ZPIX_24"),
+                ("zpix_vertical_24", "ZPIX_VERTICAL_24"),
+            ]
+            .map(|(module, const_name)| (module.to_string(), const_name.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_lib_source() {
+        let modules = [
+            ModuleSource {
+                module: "zpix_12".to_string(),
+                const_name: "ZPIX_12".to_string(),
+                features: vec!["size-12".to_string()],
+            },
+            ModuleSource {
+                module: "zpix_gray4_12".to_string(),
+                const_name: "ZPIX_GRAY4_12".to_string(),
+                features: vec!["gray".to_string(), "size-12".to_string()],
+            },
+        ];
+
+        assert_eq!(
+            lib_source(&modules),
+            "// This is generated code. Any modifications to this file will
+// be overwritten.
+#[cfg(feature = \"size-12\")]
+mod zpix_12;
+#[cfg(all(feature = \"gray\", feature = \"size-12\"))]
+mod zpix_gray4_12;
+
+#[cfg(feature = \"size-12\")]
+pub use zpix_12::FONT as ZPIX_12;
+#[cfg(feature = \"size-12\")]
+pub use zpix_12::GLYPH_MAPPING as ZPIX_12_GLYPH_MAPPING;
+#[cfg(all(feature = \"gray\", feature = \"size-12\"))]
+pub use zpix_gray4_12::FONT as ZPIX_GRAY4_12;
+#[cfg(all(feature = \"gray\", feature = \"size-12\"))]
+pub use zpix_gray4_12::GLYPH_MAPPING as ZPIX_GRAY4_12_GLYPH_MAPPING;
+"
+        );
+    }
+
+    #[test]
+    fn test_features_table() {
+        let manifest: Manifest = toml::from_str(&format!(
+            "default-sizes = [12]\n{}\n[[font]]\nsource = \"zpix.ttf\"\noutput-name = \"zpix_gray4\"\nconst-name = \"ZPIX_GRAY4\"\nsizes = [24]\nblocks = [\"hiragana\"]\nbit-depth = 4\nfeature = \"gray\"\n",
+            MANIFEST
+        ))
+        .unwrap();
+
+        assert_eq!(
+            manifest.features_table().unwrap(),
+            r#"[features]
+default                 = [
+    "embedded-graphics-08",
+    "size-12",
+]
+embedded-graphics-07    = [
+    "embedded-graphics-cjk-glyph-mapping/embedded-graphics-07",
+    "embedded-graphics-cjk-gray-font?/embedded-graphics-07",
+]
+embedded-graphics-08    = [
+    "embedded-graphics-cjk-glyph-mapping/embedded-graphics-08",
+    "embedded-graphics-cjk-gray-font?/embedded-graphics-08",
+]
+gray                    = ["embedded-graphics-cjk-gray-font"]
+size-12                 = []
+size-24                 = []
+"#
+        );
+    }
+
+    #[test]
+    fn test_unknown_default_size() {
+        let manifest: Manifest =
+            toml::from_str(&format!("default-sizes = [16]\n{}", MANIFEST)).unwrap();
+
+        assert!(manifest.features_table().is_err());
+    }
+
+    #[test]
+    fn test_replace_features_table() {
+        let package = "[package]\nname = \"zpix\"\n";
+        let dependencies = "[dependencies]\nzpix = \"0.1\"\n";
+        let table = "[features]\nsize-12 = []\n";
+
+        assert_eq!(
+            replace_features_table(&format!("{}\n{}", package, dependencies), table),
+            format!("{}\n{}\n{}", package, table, dependencies)
+        );
+        assert_eq!(
+            replace_features_table(
+                &format!("{}\n[features]\nsize-24 = []\n\n{}", package, dependencies),
+                table
+            ),
+            format!("{}\n{}\n{}", package, table, dependencies)
+        );
+        assert_eq!(
+            replace_features_table(package, table),
+            format!("{}\n{}", package, table)
+        );
+    }
+
+    #[test]
+    fn test_bundled_manifests() {
+        for font_crate in [
+            "font-fusion-pixel",
+            "font-noto",
+            "font-sarasa-gothic",
+            "font-zpix",
+        ] {
+            let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join(font_crate)
+                .join("fonts.toml");
+            let manifest = Manifest::from_path(&manifest_path).unwrap();
+
+            for font in &manifest.fonts {
+                font.glyph_mapping(&manifest.root).unwrap();
+            }
+        }
+    }
+
+    /// The checked in `lib.rs`, and `[features]` table of each font crate
+    /// have to match what `build` generates, so every font in a manifest has
+    /// to be generated. The crate has to depend on exactly the crates that
+    /// its fonts use, which are optional if the features use them.
+    #[test]
+    fn test_bundled_crates_in_sync() {
+        for font_crate in [
+            "font-fusion-pixel",
+            "font-noto",
+            "font-sarasa-gothic",
+            "font-zpix",
+        ] {
+            let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join(font_crate);
+            let manifest = Manifest::from_path(root.join("fonts.toml")).unwrap();
+            let cargo_toml = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+
+            assert_eq!(
+                fs::read_to_string(root.join("src").join("lib.rs")).unwrap(),
+                lib_source(&manifest.modules()),
+                "{}",
+                font_crate
+            );
+            assert_eq!(
+                replace_features_table(&cargo_toml, &manifest.features_table().unwrap()),
+                cargo_toml,
+                "{}",
+                font_crate
+            );
+
+            let cargo_toml: toml::Value = toml::from_str(&cargo_toml).unwrap();
+            let dependencies: BTreeMap<&str, bool> = cargo_toml["dependencies"]
+                .as_table()
+                .unwrap()
+                .iter()
+                .filter(|(name, _)| name.starts_with("embedded-graphics-cjk-"))
+                .map(|(name, dependency)| {
+                    let optional = dependency.get("optional").and_then(toml::Value::as_bool);
+
+                    (name.as_str(), optional.unwrap_or(false))
+                })
+                .collect();
+
+            assert_eq!(
+                dependencies,
+                manifest.dependencies().into_iter().collect(),
+                "{}",
+                font_crate
+            );
+        }
+    }
+
+    #[test]
+    fn test_manifest_errors() {
+        let mut file = NamedTempFile::new().unwrap();
+
+        file.write_all(b"[[font]]\nsizes = 12\n").unwrap();
+
+        let path = file.path();
+        let err = Manifest::from_path(path).unwrap_err().to_string();
+
+        assert!(err.starts_with(&format!("parsing manifest `{}`: ", path.display())));
+        assert!(err.contains("line 2"), "{}", err);
+
+        let err = Manifest::from_path("does-not-exist.toml")
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.starts_with("reading manifest `does-not-exist.toml`: "));
+        assert!(err.contains("No such file"), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_block() {
+        let manifest: Manifest =
+            toml::from_str(&MANIFEST.replace("cjk-radicals-supplement", "klingon")).unwrap();
+
+        assert!(manifest.fonts[0].glyph_mapping(Path::new(".")).is_err());
+    }
+}
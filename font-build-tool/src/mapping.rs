@@ -0,0 +1,492 @@
+use crate::unicode::UnicodeCodeBlock;
+use std::{collections::BTreeSet, fmt::Write, ops::RangeInclusive};
+use unicode_width::UnicodeWidthChar;
+
+/// The glyph that is displayed for characters that aren't in the font.
+pub const FALLBACK_CHAR: char = '?';
+
+/// The number of characters to include on a single line in generated
+/// `SparseGlyphMapping` source code.
+const SPARSE_CHARS_PER_LINE: usize = 8;
+
+/// The number of duplicates to include on a single line in generated
+/// `DedupGlyphMapping` source code.
+const DUPLICATES_PER_LINE: usize = 8;
+
+/// Describes which characters are included in a font, in the order their
+/// glyphs are stored, and the glyph mapping used to look them up.
+#[derive(Clone, Debug)]
+pub enum GlyphMappingSource {
+    /// Contiguous ranges of characters, looked up with an
+    /// `IndexedRangeGlyphMapping`.
+    Ranges(Vec<RangeInclusive<char>>),
+
+    /// An arbitrary, sorted set of characters, looked up with a
+    /// `SparseGlyphMapping`.
+    Sparse(Vec<char>),
+}
+
+impl GlyphMappingSource {
+    /// Covers every character in each of the unicode code blocks, and the
+    /// fallback character. The ranges are sorted, and overlapping, or
+    /// adjacent ranges are merged, since `IndexedRangeGlyphMapping` searches
+    /// them in order, and each character should only have a single glyph.
+    pub fn from_blocks(unicode_blocks: &[UnicodeCodeBlock]) -> GlyphMappingSource {
+        let mut blocks: Vec<_> = unicode_blocks.iter().map(UnicodeCodeBlock::range).collect();
+
+        blocks.push(FALLBACK_CHAR..=FALLBACK_CHAR);
+        blocks.sort_unstable_by_key(|range| *range.start());
+
+        let mut ranges: Vec<RangeInclusive<char>> = Vec::with_capacity(blocks.len());
+
+        for block in blocks {
+            match ranges.last_mut() {
+                Some(last) if *block.start() as u32 <= *last.end() as u32 + 1 => {
+                    if block.end() > last.end() {
+                        *last = *last.start()..=*block.end();
+                    }
+                }
+                _ => ranges.push(block),
+            }
+        }
+
+        GlyphMappingSource::Ranges(ranges)
+    }
+
+    /// Covers an arbitrary set of characters. The fallback character is
+    /// always included.
+    pub fn from_chars<I>(chars: I) -> GlyphMappingSource
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let mut chars: Vec<char> = chars.into_iter().collect();
+
+        chars.push(FALLBACK_CHAR);
+        chars.sort_unstable();
+        chars.dedup();
+
+        GlyphMappingSource::Sparse(chars)
+    }
+
+    /// Returns a mapping that doesn't cover any of the excluded characters.
+    /// Ranges are split around excluded characters. The fallback character is
+    /// never excluded, since it's used for every character that isn't
+    /// covered.
+    pub fn without(&self, excluded: &BTreeSet<char>) -> GlyphMappingSource {
+        let is_excluded = |chr: &char| *chr != FALLBACK_CHAR && excluded.contains(chr);
+
+        match self {
+            GlyphMappingSource::Ranges(ranges) => {
+                let mut split = Vec::new();
+
+                for range in ranges {
+                    let mut start = None;
+
+                    for chr in range.clone() {
+                        match (start, is_excluded(&chr)) {
+                            (None, false) => start = Some(chr),
+                            (Some(first), true) => {
+                                // `first` comes before `chr`, so `chr` always
+                                // has a previous character
+                                if let Some(last) = previous_char(chr) {
+                                    split.push(first..=last);
+                                }
+
+                                start = None;
+                            }
+                            _ => (),
+                        }
+                    }
+
+                    if let Some(first) = start {
+                        split.push(first..=*range.end());
+                    }
+                }
+
+                GlyphMappingSource::Ranges(split)
+            }
+            GlyphMappingSource::Sparse(chars) => GlyphMappingSource::Sparse(
+                chars
+                    .iter()
+                    .copied()
+                    .filter(|chr| !is_excluded(chr))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns a mapping that only covers half-width characters, for use as
+    /// the companion of a full-width font. Characters with an East Asian
+    /// Width of wide or fullwidth are excluded. Ambiguous characters are
+    /// kept, since they're narrow outside of CJK contexts.
+    pub fn half_width(&self) -> GlyphMappingSource {
+        let excluded = self.chars().filter(|chr| chr.width() == Some(2)).collect();
+
+        self.without(&excluded)
+    }
+
+    /// Returns an iterator over the characters, in glyph index order.
+    pub fn chars(&self) -> Box<dyn Iterator<Item = char> + '_> {
+        match self {
+            GlyphMappingSource::Ranges(ranges) => Box::new(ranges.iter().cloned().flatten()),
+            GlyphMappingSource::Sparse(chars) => Box::new(chars.iter().copied()),
+        }
+    }
+
+    /// Number of glyphs covered by the mapping.
+    pub fn len(&self) -> usize {
+        match self {
+            // Add 1, since the range is inclusive
+            GlyphMappingSource::Ranges(ranges) => ranges
+                .iter()
+                .map(|range| *range.end() as usize - *range.start() as usize + 1)
+                .sum(),
+            GlyphMappingSource::Sparse(chars) => chars.len(),
+        }
+    }
+
+    /// Returns `true` if the mapping doesn't cover any characters.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Glyph index that is used for characters that aren't in the font. This
+    /// is the index of the fallback character, or the first glyph if the
+    /// fallback character isn't covered.
+    pub fn default_index(&self) -> usize {
+        self.chars()
+            .position(|chr| chr == FALLBACK_CHAR)
+            .unwrap_or(0)
+    }
+
+    /// Name of the `embedded-graphics-cjk-glyph-mapping` type used in the
+    /// generated source code.
+    pub fn rust_type(&self) -> &'static str {
+        match self {
+            GlyphMappingSource::Ranges(_) => "IndexedRangeGlyphMapping",
+            GlyphMappingSource::Sparse(_) => "SparseGlyphMapping",
+        }
+    }
+
+    /// Generates a `GLYPH_MAPPING` constant. Fonts refer to it, and it's
+    /// exported so text styles can check which characters a font covers.
+    pub fn rust_source(&self) -> String {
+        format!(
+            "#[rustfmt::skip]\npub const GLYPH_MAPPING: {} = {};\n",
+            self.rust_type_name(),
+            self.rust_constructor()
+        )
+    }
+
+    /// Type of the generated `GLYPH_MAPPING` constant.
+    fn rust_type_name(&self) -> String {
+        match self {
+            GlyphMappingSource::Ranges(ranges) => {
+                format!("IndexedRangeGlyphMapping<{}>", ranges.len())
+            }
+            GlyphMappingSource::Sparse(_) => "SparseGlyphMapping".to_string(),
+        }
+    }
+
+    /// Expression that creates the glyph mapping.
+    fn rust_constructor(&self) -> String {
+        let mut source = String::new();
+
+        match self {
+            GlyphMappingSource::Ranges(ranges) => {
+                source.push_str("IndexedRangeGlyphMapping::new(\n    [\n");
+
+                for range in ranges {
+                    let _ = writeln!(
+                        source,
+                        "        '{}'..='{}',",
+                        escape_char(*range.start()),
+                        escape_char(*range.end())
+                    );
+                }
+            }
+            GlyphMappingSource::Sparse(chars) => {
+                source.push_str("SparseGlyphMapping::new(\n    &[\n");
+
+                for line in chars.chunks(SPARSE_CHARS_PER_LINE) {
+                    source.push_str("       ");
+
+                    for chr in line {
+                        let _ = write!(source, " '{}',", escape_char(*chr));
+                    }
+
+                    source.push('\n');
+                }
+            }
+        }
+
+        let _ = write!(source, "    ],\n    {}\n)", self.default_index());
+
+        source
+    }
+}
+
+/// A glyph mapping for a font where glyphs with the same bitmap are only
+/// stored once. Each duplicate is a slot in the glyph mapping, and the index
+/// of the earlier glyph it reuses.
+#[derive(Clone, Debug)]
+pub(crate) struct DedupMappingSource {
+    pub(crate) mapping: GlyphMappingSource,
+    pub(crate) duplicates: Vec<(usize, usize)>,
+}
+
+impl DedupMappingSource {
+    /// Names of the `embedded-graphics-cjk-glyph-mapping` types used in the
+    /// generated source code. The glyph mapping is only wrapped in a
+    /// `DedupGlyphMapping` if there are any duplicates.
+    pub(crate) fn rust_type(&self) -> String {
+        if self.duplicates.is_empty() {
+            self.mapping.rust_type().to_string()
+        } else {
+            format!("{{DedupGlyphMapping, {}}}", self.mapping.rust_type())
+        }
+    }
+
+    /// Generates the `GLYPH_MAPPING` constant.
+    pub(crate) fn rust_source(&self) -> String {
+        if self.duplicates.is_empty() {
+            return self.mapping.rust_source();
+        }
+
+        let mut source = format!(
+            "#[rustfmt::skip]\npub const GLYPH_MAPPING: DedupGlyphMapping<{}> = \
+             DedupGlyphMapping::new(\n    {},\n    &[\n",
+            self.mapping.rust_type_name(),
+            self.mapping.rust_constructor().replace('\n', "\n    ")
+        );
+
+        for line in self.duplicates.chunks(DUPLICATES_PER_LINE) {
+            source.push_str("       ");
+
+            for (slot, glyph) in line {
+                let _ = write!(source, " ({}, {}),", slot, glyph);
+            }
+
+            source.push('\n');
+        }
+
+        source.push_str("    ],\n);\n");
+
+        source
+    }
+}
+
+/// Returns the character before `chr`, skipping the surrogates, which
+/// aren't characters, or `None` if `chr` is the first character.
+fn previous_char(chr: char) -> Option<char> {
+    let mut codepoint = chr as u32;
+
+    loop {
+        codepoint = codepoint.checked_sub(1)?;
+
+        if let Some(chr) = char::from_u32(codepoint) {
+            return Some(chr);
+        }
+    }
+}
+
+/// Escapes a character so it can be used in a character literal. Printable
+/// ASCII characters are written as-is, everything else as a unicode escape.
+fn escape_char(chr: char) -> String {
+    match chr {
+        '\'' | '\\' => format!("\\{}", chr),
+        ' '..='~' => chr.to_string(),
+        _ => format!("\\u{{{:04X}}}", chr as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{previous_char, DedupMappingSource, GlyphMappingSource};
+    use crate::unicode::{
+        UnicodeCodeBlock, BASIC_LATIN, CJK_RADICALS_SUPPLEMENT,
+        CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK, HIRAGANA, KATAKANA,
+    };
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_from_chars_adds_fallback() {
+        let mapping = GlyphMappingSource::from_chars("国大一一".chars());
+
+        assert_eq!(mapping.len(), 4);
+        assert_eq!(mapping.default_index(), 0);
+        assert_eq!(mapping.chars().collect::<String>(), "?一国大");
+    }
+
+    #[test]
+    fn test_from_blocks_adds_fallback() {
+        let mapping = GlyphMappingSource::from_blocks(&[CJK_RADICALS_SUPPLEMENT]);
+
+        assert_eq!(mapping.chars().next(), Some('?'));
+        assert_eq!(mapping.default_index(), 0);
+
+        let mapping = GlyphMappingSource::from_blocks(&[BASIC_LATIN]);
+
+        assert_eq!(mapping.chars().next(), Some('\u{0}'));
+        assert_eq!(mapping.default_index(), '?' as usize);
+    }
+
+    #[test]
+    fn test_from_blocks_sorts_and_merges() {
+        let overlapping = UnicodeCodeBlock::new('\u{3090}', '\u{30A5}');
+        let mapping = GlyphMappingSource::from_blocks(&[
+            CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK,
+            KATAKANA,
+            HIRAGANA,
+            overlapping,
+            KATAKANA,
+        ]);
+
+        match &mapping {
+            GlyphMappingSource::Ranges(ranges) => assert_eq!(
+                ranges,
+                &[
+                    '?'..='?',
+                    '\u{3040}'..='\u{30FF}',
+                    CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK.range(),
+                ]
+            ),
+            GlyphMappingSource::Sparse(_) => panic!("expected ranges"),
+        }
+
+        let chars: Vec<char> = mapping.chars().collect();
+
+        assert!(chars.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            mapping.len(),
+            1 + 0xC0 + CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK.range().count()
+        );
+    }
+
+    #[test]
+    fn test_without_splits_ranges() {
+        let mapping = GlyphMappingSource::Ranges(vec!['?'..='?', 'a'..='f', 'x'..='z']);
+        let excluded: BTreeSet<char> = "?acdfxyz".chars().collect();
+
+        match mapping.without(&excluded) {
+            GlyphMappingSource::Ranges(ranges) => {
+                assert_eq!(ranges, ['?'..='?', 'b'..='b', 'e'..='e'])
+            }
+            GlyphMappingSource::Sparse(_) => panic!("expected ranges"),
+        }
+    }
+
+    #[test]
+    fn test_without_after_surrogates() {
+        // `\u{E000}` is the first character after the surrogates
+        let mapping = GlyphMappingSource::Ranges(vec!['\u{D000}'..='\u{E0FF}']);
+        let excluded: BTreeSet<char> = ['\u{E000}'].into_iter().collect();
+
+        match mapping.without(&excluded) {
+            GlyphMappingSource::Ranges(ranges) => {
+                assert_eq!(ranges, ['\u{D000}'..='\u{D7FF}', '\u{E001}'..='\u{E0FF}'])
+            }
+            GlyphMappingSource::Sparse(_) => panic!("expected ranges"),
+        }
+
+        assert_eq!(previous_char('\u{E000}'), Some('\u{D7FF}'));
+        assert_eq!(previous_char('\u{0}'), None);
+    }
+
+    #[test]
+    fn test_without_sparse() {
+        let mapping = GlyphMappingSource::from_chars("一国大".chars());
+        let mapping = mapping.without(&"?国".chars().collect());
+
+        assert_eq!(mapping.chars().collect::<String>(), "?一大");
+        assert_eq!(mapping.default_index(), 0);
+    }
+
+    #[test]
+    fn test_half_width() {
+        let mapping = GlyphMappingSource::from_chars("aÅ°一ｱＡ".chars());
+
+        assert_eq!(mapping.half_width().chars().collect::<String>(), "?a°Åｱ");
+
+        let mapping = GlyphMappingSource::Ranges(vec!['?'..='?', '\u{3000}'..='\u{3003}']);
+
+        match mapping.half_width() {
+            GlyphMappingSource::Ranges(ranges) => assert_eq!(ranges, ['?'..='?']),
+            GlyphMappingSource::Sparse(_) => panic!("expected ranges"),
+        }
+    }
+
+    #[test]
+    fn test_ranges_source() {
+        let mapping = GlyphMappingSource::Ranges(vec!['?'..='?', '\u{2E80}'..='\u{2EF3}']);
+
+        assert_eq!(mapping.len(), 1 + 116);
+        assert_eq!(
+            mapping.rust_source(),
+            "#[rustfmt::skip]
+pub const GLYPH_MAPPING: IndexedRangeGlyphMapping<2> = IndexedRangeGlyphMapping::new(
+    [
+        '?'..='?',
+        '\\u{2E80}'..='\\u{2EF3}',
+    ],
+    0
+);
+"
+        );
+    }
+
+    #[test]
+    fn test_sparse_source() {
+        let mapping = GlyphMappingSource::from_chars("一'".chars());
+
+        assert_eq!(
+            mapping.rust_source(),
+            "#[rustfmt::skip]
+pub const GLYPH_MAPPING: SparseGlyphMapping = SparseGlyphMapping::new(
+    &[
+        '\\'', '?', '\\u{4E00}',
+    ],
+    1
+);
+"
+        );
+    }
+
+    #[test]
+    fn test_dedup_source() {
+        let mapping = DedupMappingSource {
+            mapping: GlyphMappingSource::Ranges(vec!['?'..='?', 'a'..='c']),
+            duplicates: vec![(2, 0)],
+        };
+
+        assert_eq!(
+            mapping.rust_type(),
+            "{DedupGlyphMapping, IndexedRangeGlyphMapping}"
+        );
+        assert_eq!(
+            mapping.rust_source(),
+            "#[rustfmt::skip]
+pub const GLYPH_MAPPING: DedupGlyphMapping<IndexedRangeGlyphMapping<2>> = DedupGlyphMapping::new(
+    IndexedRangeGlyphMapping::new(
+        [
+            '?'..='?',
+            'a'..='c',
+        ],
+        0
+    ),
+    &[
+        (2, 0),
+    ],
+);
+"
+        );
+
+        let mapping = DedupMappingSource {
+            duplicates: Vec::new(),
+            ..mapping
+        };
+
+        assert_eq!(mapping.rust_type(), "IndexedRangeGlyphMapping");
+        assert_eq!(mapping.rust_source(), mapping.mapping.rust_source());
+    }
+}
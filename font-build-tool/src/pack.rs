@@ -0,0 +1,416 @@
+use crate::{
+    builder::{FontMetrics, MonoFontData},
+    compressed::CompressedFontData,
+    error::BuildError,
+    mapping::DedupMappingSource,
+};
+use embedded_graphics_cjk_font_image::{
+    crc32, FontFormat, ENTRY_SIZE, HEADER_SIZE, MAGIC, MAX_NAME_LEN, VERSION,
+};
+use std::{fs, io, ops::Deref, path::Path};
+
+/// Default alignment of each font's glyph data, which is the size of an
+/// erase sector on most SPI flash chips.
+pub const DEFAULT_ALIGNMENT: u32 = 4096;
+
+/// Packs several fonts into a single image, that is written to flash, and
+/// opened with `FontImage` from the `embedded-graphics-cjk-font-image`
+/// crate. See `FontImage` for the format.
+pub struct FontImageBuilder {
+    alignment: u32,
+    fonts: Vec<PackedFontEntry>,
+}
+
+/// A font that was added to the image.
+struct PackedFontEntry {
+    name: String,
+    format: FontFormat,
+    bits_per_pixel: u8,
+
+    /// Cell size, baseline, and decorations.
+    metrics: [u8; 7],
+    glyph_mapping: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl FontImageBuilder {
+    /// Creates an empty image. Each font's glyph data starts at a multiple
+    /// of `alignment` bytes.
+    pub fn new(alignment: u32) -> Result<FontImageBuilder, BuildError> {
+        if alignment == 0 {
+            return Err(BuildError::InvalidAlignment(alignment));
+        }
+
+        Ok(FontImageBuilder {
+            alignment,
+            fonts: Vec::new(),
+        })
+    }
+
+    /// Number of fonts that were added.
+    pub fn len(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// Returns `true` if no fonts were added.
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    /// Adds a font with its raw bitmap, which is drawn as a `MonoFont`, or a
+    /// `GrayFont`, depending on its bit depth.
+    pub fn add_font<C>(&mut self, name: &str, font: &MonoFontData<C>) -> Result<(), BuildError>
+    where
+        C: Deref<Target = [u8]>,
+    {
+        self.add(PackedFontEntry {
+            name: name.to_string(),
+            format: FontFormat::Raw,
+            bits_per_pixel: font.bit_depth.bits(),
+            metrics: pack_metrics(name, font.glyph_width, font.glyph_height, &font.metrics)?,
+            glyph_mapping: pack_glyph_mapping(&font.glyph_mapping),
+            data: font.to_raw(),
+        })
+    }
+
+    /// Adds a font with compressed glyphs, which is drawn as a
+    /// `CompressedFont`.
+    pub fn add_compressed_font(
+        &mut self,
+        name: &str,
+        font: &CompressedFontData,
+    ) -> Result<(), BuildError> {
+        self.add(PackedFontEntry {
+            name: name.to_string(),
+            format: FontFormat::Compressed,
+            bits_per_pixel: 1,
+            metrics: pack_metrics(name, font.glyph_width, font.glyph_height, &font.metrics)?,
+            glyph_mapping: pack_glyph_mapping(&font.glyph_mapping),
+            data: font.data.clone(),
+        })
+    }
+
+    fn add(&mut self, font: PackedFontEntry) -> Result<(), BuildError> {
+        let name = &font.name;
+
+        if name.is_empty() || name.len() > MAX_NAME_LEN || name.contains('\0') {
+            return Err(BuildError::InvalidFontName(name.clone()));
+        }
+
+        if self.fonts.iter().any(|packed| packed.name == *name) {
+            return Err(BuildError::DuplicateFontName(name.clone()));
+        }
+
+        if self.fonts.len() >= usize::from(u16::MAX) {
+            return Err(too_large("font image can't contain more than 65535 fonts"));
+        }
+
+        self.fonts.push(font);
+
+        Ok(())
+    }
+
+    /// Lays out the header, directory, glyph mappings, and glyph data. The
+    /// padding before each font's glyph data is filled with `0xFF`, which is
+    /// what erased flash reads as.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BuildError> {
+        let directory_end = HEADER_SIZE + self.fonts.len() * ENTRY_SIZE;
+        let mut image = vec![0; directory_end];
+        let mut entries = Vec::with_capacity(self.fonts.len());
+
+        for font in &self.fonts {
+            let mapping_offset = image.len();
+
+            image.extend_from_slice(&font.glyph_mapping);
+            entries.push((mapping_offset, font.glyph_mapping.len()));
+        }
+
+        let metadata_end = image.len();
+
+        for (index, (font, (mapping_offset, mapping_len))) in
+            self.fonts.iter().zip(entries).enumerate()
+        {
+            let data_offset = image.len().next_multiple_of(self.alignment as usize);
+
+            image.resize(data_offset, 0xFF);
+            image.extend_from_slice(&font.data);
+
+            let directory_entry = &mut image[HEADER_SIZE + index * ENTRY_SIZE..][..ENTRY_SIZE];
+
+            directory_entry[..font.name.len()].copy_from_slice(font.name.as_bytes());
+            directory_entry[24] = font.format as u8;
+            directory_entry[25] = font.bits_per_pixel;
+            directory_entry[26..33].copy_from_slice(&font.metrics);
+
+            for (field, value) in [
+                (36, mapping_offset),
+                (40, mapping_len),
+                (44, data_offset),
+                (48, font.data.len()),
+            ] {
+                directory_entry[field..field + 4].copy_from_slice(&offset(value)?);
+            }
+
+            directory_entry[52..56].copy_from_slice(&crc32(&font.data).to_le_bytes());
+        }
+
+        let checksum = crc32(&image[HEADER_SIZE..metadata_end]);
+
+        image[..4].copy_from_slice(&MAGIC);
+        image[4..6].copy_from_slice(&VERSION.to_le_bytes());
+        image[6..8].copy_from_slice(&(self.fonts.len() as u16).to_le_bytes());
+        image[8..12].copy_from_slice(&self.alignment.to_le_bytes());
+        image[12..16].copy_from_slice(&checksum.to_le_bytes());
+
+        Ok(image)
+    }
+
+    /// Writes the image to a file.
+    pub fn save<P>(&self, image_file: P) -> Result<(), BuildError>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(image_file, self.to_bytes()?)?;
+
+        Ok(())
+    }
+}
+
+/// Offset, or length in the image.
+fn offset(offset: usize) -> Result<[u8; 4], BuildError> {
+    u32::try_from(offset)
+        .map(u32::to_le_bytes)
+        .map_err(|_| too_large("font image is larger than 4 GiB"))
+}
+
+fn too_large(message: &str) -> BuildError {
+    io::Error::new(io::ErrorKind::InvalidData, message).into()
+}
+
+/// Packs the cell size, baseline, and decorations into a byte each.
+fn pack_metrics(
+    name: &str,
+    glyph_width: usize,
+    glyph_height: usize,
+    metrics: &FontMetrics,
+) -> Result<[u8; 7], BuildError> {
+    let values = [
+        glyph_width,
+        glyph_height,
+        metrics.baseline,
+        metrics.underline.offset,
+        metrics.underline.height,
+        metrics.strikethrough.offset,
+        metrics.strikethrough.height,
+    ];
+    let mut packed = [0; 7];
+
+    for (byte, value) in packed.iter_mut().zip(values) {
+        *byte =
+            u8::try_from(value).map_err(|_| BuildError::FontTooLargeToPack(name.to_string()))?;
+    }
+
+    Ok(packed)
+}
+
+/// Packs a glyph mapping into the format read by `PackedGlyphMapping`.
+/// Consecutive characters are stored as a single range, whether the mapping
+/// has ranges, or is sparse.
+fn pack_glyph_mapping(glyph_mapping: &DedupMappingSource) -> Vec<u8> {
+    let mut ranges: Vec<[u32; 3]> = Vec::new();
+
+    for (slot, chr) in glyph_mapping.mapping.chars().enumerate() {
+        match ranges.last_mut() {
+            Some([_, end, _]) if *end + 1 == chr as u32 => *end = chr as u32,
+            _ => ranges.push([chr as u32, chr as u32, slot as u32]),
+        }
+    }
+
+    // Unicode blocks can be listed in any order, but ranges are looked up
+    // with a binary search.
+    ranges.sort_unstable_by_key(|[start, _, _]| *start);
+
+    let counts = [
+        glyph_mapping.mapping.default_index() as u32,
+        ranges.len() as u32,
+        glyph_mapping.duplicates.len() as u32,
+    ];
+    let duplicates = glyph_mapping
+        .duplicates
+        .iter()
+        .flat_map(|(slot, glyph)| [*slot as u32, *glyph as u32]);
+
+    counts
+        .into_iter()
+        .chain(ranges.into_iter().flatten())
+        .chain(duplicates)
+        .flat_map(u32::to_le_bytes)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_glyph_mapping, FontImageBuilder};
+    use crate::{
+        builder::{tests::test_font, BitDepth, FontOutputSettings, MissingGlyphPolicy},
+        error::BuildError,
+        mapping::{DedupMappingSource, GlyphMappingSource},
+        unicode::BASIC_LATIN,
+        MonoFontBuilder,
+    };
+    use embedded_graphics::{
+        image::ImageRaw,
+        mock_display::MockDisplay,
+        mono_font::{mapping::GlyphMapping, MonoFont, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        prelude::*,
+        text::Text,
+    };
+    use embedded_graphics_cjk_font_image::{FontFormat, FontImage, PackedGlyphMapping};
+    use embedded_graphics_cjk_glyph_mapping::{DedupGlyphMapping, IndexedRangeGlyphMapping};
+
+    #[test]
+    fn test_pack_glyph_mapping() {
+        let glyph_mapping = DedupMappingSource {
+            mapping: GlyphMappingSource::from_chars(['c', 'a', 'b', 'x']),
+            duplicates: vec![(2, 0)],
+        };
+        let packed = pack_glyph_mapping(&glyph_mapping);
+        let map = PackedGlyphMapping::parse(&packed).unwrap();
+
+        // `?`, `a..=c`, and `x`
+        assert_eq!(map.num_ranges(), 3);
+        assert_eq!(map.index('?'), 0);
+        assert_eq!(map.index('a'), 1);
+        assert_eq!(map.index('b'), 0);
+        assert_eq!(map.index('c'), 2);
+        assert_eq!(map.index('x'), 3);
+        assert_eq!(map.index('z'), 0);
+
+        // Ranges are sorted, but keep their slots
+        let glyph_mapping = DedupMappingSource {
+            mapping: GlyphMappingSource::Ranges(vec!['a'..='c', '?'..='?']),
+            duplicates: Vec::new(),
+        };
+        let packed = pack_glyph_mapping(&glyph_mapping);
+        let map = PackedGlyphMapping::parse(&packed).unwrap();
+
+        assert_eq!(map.index('a'), 0);
+        assert_eq!(map.index('?'), 3);
+        assert_eq!(map.index('z'), 3);
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let font_path = test_font();
+        let bitmap = MonoFontBuilder::new(font_path, &[BASIC_LATIN])
+            .unwrap()
+            .build(FontOutputSettings {
+                font_size: 12,
+                intensity_threshold: 128,
+                bit_depth: BitDepth::One,
+                missing_glyphs: MissingGlyphPolicy::Fallback,
+            })
+            .unwrap();
+        let mut image = FontImageBuilder::new(64).unwrap();
+
+        image.add_font("fira_12", &bitmap).unwrap();
+
+        assert!(matches!(
+            image.add_font("fira_12", &bitmap),
+            Err(BuildError::DuplicateFontName(_))
+        ));
+        assert!(matches!(
+            image.add_font("", &bitmap),
+            Err(BuildError::InvalidFontName(_))
+        ));
+        assert!(matches!(
+            image.add_font("fira_sans_regular_condensed_12", &bitmap),
+            Err(BuildError::InvalidFontName(_))
+        ));
+        assert_eq!(image.len(), 1);
+        assert!(matches!(
+            FontImageBuilder::new(0),
+            Err(BuildError::InvalidAlignment(0))
+        ));
+    }
+
+    #[test]
+    fn test_pack_and_parse() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::new(font_path, &[BASIC_LATIN]).unwrap();
+        let settings = FontOutputSettings {
+            font_size: 12,
+            intensity_threshold: 128,
+            bit_depth: BitDepth::One,
+            missing_glyphs: MissingGlyphPolicy::Fallback,
+        };
+        let mono = builder.build(settings).unwrap();
+        let gray = builder
+            .build(FontOutputSettings {
+                bit_depth: BitDepth::Four,
+                ..settings
+            })
+            .unwrap();
+        let compressed = mono.compress().unwrap();
+        let mut image = FontImageBuilder::new(256).unwrap();
+
+        image.add_font("fira_12", &mono).unwrap();
+        image.add_font("fira_gray4_12", &gray).unwrap();
+        image
+            .add_compressed_font("fira_compressed_12", &compressed)
+            .unwrap();
+
+        let bytes = image.to_bytes().unwrap();
+        let fonts = FontImage::parse(&bytes).unwrap();
+
+        assert_eq!(fonts.len(), 3);
+        assert_eq!(fonts.alignment(), 256);
+
+        for font in fonts.fonts() {
+            assert!(font.verify(), "{}", font.name());
+            assert_eq!(font.data_offset() % 256, 0);
+        }
+
+        let packed = fonts.font("fira_12").unwrap();
+
+        assert_eq!(packed.format(), FontFormat::Raw);
+        assert_eq!(packed.data(), mono.to_raw());
+        assert_eq!(packed.baseline(), mono.metrics.baseline as u32);
+        assert!(fonts.font("fira_gray4_12").unwrap().gray_font().is_some());
+        assert!(fonts
+            .font("fira_compressed_12")
+            .unwrap()
+            .compressed_font()
+            .is_some());
+
+        // The packed font draws the same as the generated source would
+        let duplicates = &mono.glyph_mapping.duplicates;
+        let raw = mono.to_raw();
+        let mono_font = packed.mono_font().unwrap();
+        let glyph_mapping = DedupGlyphMapping::new(
+            IndexedRangeGlyphMapping::new(['\0'..='\x7F'], '?' as usize),
+            duplicates,
+        );
+        let generated_font = MonoFont {
+            image: ImageRaw::new(&raw, 32 * mono.glyph_width as u32),
+            glyph_mapping: &glyph_mapping,
+            ..mono_font
+        };
+        let draw = |font| {
+            let mut display = MockDisplay::new();
+
+            Text::new(
+                "Ab?\u{3042}",
+                Point::new(0, 12),
+                MonoTextStyle::new(font, BinaryColor::On),
+            )
+            .draw(&mut display)
+            .unwrap();
+
+            display
+        };
+
+        draw(&mono_font).assert_eq(&draw(&generated_font));
+    }
+}
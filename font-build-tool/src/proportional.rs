@@ -0,0 +1,282 @@
+use crate::{
+    builder::{FontMetrics, FontOutputSettings, MonoFontBuilder},
+    error::BuildError,
+    mapping::GlyphMappingSource,
+};
+use std::{cmp::max, fmt::Display, fs, io, path::Path};
+
+/// Position, and size of a glyph, as stored in the generated `Glyph`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct GlyphMetrics {
+    /// Offset of the glyph's bitmap in the data, in bytes.
+    offset: u32,
+    width: u8,
+    height: u8,
+
+    /// Horizontal bearing, from the pen position to the left edge.
+    left: i8,
+
+    /// Vertical bearing, from the baseline up to the top edge.
+    top: i8,
+    advance: u8,
+}
+
+/// Glyph bitmaps, and metrics for a font where every glyph has its own
+/// advance, and bounding box.
+pub struct ProportionalFontData {
+    data: Vec<u8>,
+    glyphs: Vec<GlyphMetrics>,
+    line_height: usize,
+    metrics: FontMetrics,
+    glyph_mapping: GlyphMappingSource,
+}
+
+impl MonoFontBuilder {
+    /// Renders glyphs for each of the selected characters, keeping each
+    /// glyph's own advance, and bearing, rather than placing it in a cell
+    /// as wide as the widest glyph. The bitmaps only cover each glyph's
+    /// bounding box.
+    ///
+    /// Proportional fonts only support a bit depth of 1.
+    pub fn build_proportional(
+        &self,
+        settings: FontOutputSettings,
+    ) -> Result<ProportionalFontData, BuildError> {
+        if settings.bit_depth.is_gray() {
+            return Err(BuildError::UnsupportedBitDepth(settings.bit_depth.bits()));
+        }
+
+        let (glyph_mapping, glyph_char) = self.prepare(&settings)?;
+        let (mut ascent, mut descent) = self.size_metrics();
+        let mut data = Vec::new();
+        let mut glyphs = Vec::with_capacity(glyph_mapping.len());
+
+        for chr in glyph_mapping.chars() {
+            let glyph = self.load_char(glyph_char(chr))?;
+            let bitmap = glyph.bitmap();
+            let too_large = |_| BuildError::GlyphTooLarge { codepoint: chr };
+            let width = bitmap.width() as usize;
+            let rows = bitmap.rows() as usize;
+            let metrics = GlyphMetrics {
+                offset: u32::try_from(data.len()).map_err(too_large)?,
+                width: u8::try_from(width).map_err(too_large)?,
+                height: u8::try_from(rows).map_err(too_large)?,
+                left: i8::try_from(glyph.bitmap_left()).map_err(too_large)?,
+                top: i8::try_from(glyph.bitmap_top()).map_err(too_large)?,
+                advance: u8::try_from((glyph.metrics().horiAdvance + 32) / 64)
+                    .map_err(too_large)?,
+            };
+
+            ascent = max(ascent, glyph.bitmap_top().max(0) as usize);
+            descent = max(
+                descent,
+                (bitmap.rows() - glyph.bitmap_top()).max(0) as usize,
+            );
+
+            glyphs.push(metrics);
+
+            // Empty glyphs, like a space, don't have a buffer.
+            if width == 0 || rows == 0 {
+                continue;
+            }
+
+            // Packs the bitmap one bit per pixel, without padding each row.
+            let pitch = bitmap.pitch().unsigned_abs() as usize;
+            let buffer = bitmap.buffer();
+            let mut bits = (0..rows)
+                .flat_map(|y| (0..width).map(move |x| y * pitch + x))
+                .map(|i| buffer[i] > settings.intensity_threshold)
+                .peekable();
+
+            while bits.peek().is_some() {
+                data.push(
+                    bits.by_ref()
+                        .take(8)
+                        .enumerate()
+                        .fold(0, |byte, (i, bit)| byte | u8::from(bit) << (7 - i)),
+                );
+            }
+        }
+
+        let line_height = ascent + descent;
+        let metrics = self.font_metrics(ascent, line_height);
+
+        println!(
+            "line_height={} baseline={} data_size={}",
+            line_height,
+            metrics.baseline,
+            data.len()
+        );
+
+        Ok(ProportionalFontData {
+            data,
+            glyphs,
+            line_height,
+            metrics,
+            glyph_mapping,
+        })
+    }
+}
+
+impl ProportionalFontData {
+    /// Number of glyphs that were rendered.
+    pub fn num_glyphs(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Writes the packed glyph bitmaps.
+    pub fn save_raw<P>(&self, raw_file: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(raw_file, &self.data)
+    }
+
+    /// Writes the Rust source code for a `ProportionalFont` constant named
+    /// `FONT`, with the glyph metrics inline, and the bitmaps included from
+    /// the raw data file.
+    pub fn save_rust_source<P0, P1>(
+        &self,
+        rust_source_path: P0,
+        bin_data_path: P1,
+    ) -> io::Result<()>
+    where
+        P0: AsRef<Path>,
+        P1: AsRef<Path> + Display,
+    {
+        fs::write(rust_source_path, self.rust_source(bin_data_path))
+    }
+
+    fn rust_source<P>(&self, bin_data_path: P) -> String
+    where
+        P: Display,
+    {
+        let glyphs: String = self
+            .glyphs
+            .iter()
+            .map(|glyph| {
+                format!(
+                    "    Glyph::new({}, {}, {}, {}, {}, {}),\n",
+                    glyph.offset, glyph.width, glyph.height, glyph.left, glyph.top, glyph.advance
+                )
+            })
+            .collect();
+
+        #[rustfmt::skip]
+        let source = format!(
+r#"// This is generated code. Any modifications to this file will
+// be overwritten.
+use embedded_graphics_cjk_glyph_mapping::{{
+    embedded_graphics::mono_font::DecorationDimensions,
+    {glyph_mapping_type},
+}};
+use embedded_graphics_cjk_proportional_font::{{Glyph, ProportionalFont}};
+
+#[rustfmt::skip]
+const GLYPHS: &[Glyph] = &[
+{glyphs}];
+
+{glyph_mapping}
+#[rustfmt::skip]
+pub const FONT: ProportionalFont = ProportionalFont {{
+    data: include_bytes!("{bin_data_path}"),
+    glyphs: GLYPHS,
+    glyph_mapping: &GLYPH_MAPPING,
+    line_height: {line_height},
+    baseline: {baseline},
+    underline: DecorationDimensions::new({underline_offset}, {underline_height}),
+    strikethrough: DecorationDimensions::new({strikethrough_offset}, {strikethrough_height}),
+}};
+"#,
+            bin_data_path = bin_data_path,
+            glyphs = glyphs,
+            glyph_mapping_type = self.glyph_mapping.rust_type(),
+            glyph_mapping = self.glyph_mapping.rust_source(),
+            line_height = self.line_height,
+            baseline = self.metrics.baseline,
+            underline_offset = self.metrics.underline.offset,
+            underline_height = self.metrics.underline.height,
+            strikethrough_offset = self.metrics.strikethrough.offset,
+            strikethrough_height = self.metrics.strikethrough.height,
+        );
+
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        builder::{tests::test_font, BitDepth, FontOutputSettings, MissingGlyphPolicy},
+        error::BuildError,
+        unicode::BASIC_LATIN,
+        MonoFontBuilder,
+    };
+
+    fn settings(bit_depth: BitDepth) -> FontOutputSettings {
+        FontOutputSettings {
+            font_size: 16,
+            intensity_threshold: 128,
+            bit_depth,
+            missing_glyphs: MissingGlyphPolicy::Fallback,
+        }
+    }
+
+    #[test]
+    fn test_glyphs_are_tightly_packed() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::new(font_path, &[BASIC_LATIN]).unwrap();
+        let font = builder.build_proportional(settings(BitDepth::One)).unwrap();
+
+        assert_eq!(font.num_glyphs(), 128);
+
+        // Each bitmap starts where the previous one ends, rounded up to a
+        // whole byte.
+        for pair in font.glyphs.windows(2) {
+            let bits = usize::from(pair[0].width) * usize::from(pair[0].height);
+
+            assert_eq!(
+                pair[1].offset as usize,
+                pair[0].offset as usize + bits.div_ceil(8)
+            );
+        }
+
+        let last = font.glyphs.last().unwrap();
+        let bits = usize::from(last.width) * usize::from(last.height);
+
+        assert_eq!(font.data.len(), last.offset as usize + bits.div_ceil(8));
+
+        // A space has no bitmap, but still moves the pen.
+        let space = font.glyphs[' ' as usize];
+
+        assert_eq!((space.width, space.height), (0, 0));
+        assert!(space.advance > 0);
+
+        // `i` is narrower than its advance, and sits on the baseline.
+        let i = font.glyphs['i' as usize];
+
+        assert!(i.width < i.advance);
+        assert!(i.left > 0);
+        assert!(i.top > 0 && usize::from(i.height) >= i.top as usize);
+        assert!(font.line_height > font.metrics.baseline);
+
+        let source = font.rust_source("data/test-16.bin");
+
+        assert!(source.contains("pub const FONT: ProportionalFont = ProportionalFont {"));
+        assert!(source.contains(&format!(
+            "    Glyph::new({}, {}, {}, {}, {}, {}),\n",
+            i.offset, i.width, i.height, i.left, i.top, i.advance
+        )));
+    }
+
+    #[test]
+    fn test_gray_proportional_font() {
+        let font_path = test_font();
+        let builder = MonoFontBuilder::new(font_path, &[BASIC_LATIN]).unwrap();
+
+        assert!(matches!(
+            builder.build_proportional(settings(BitDepth::Four)),
+            Err(BuildError::UnsupportedBitDepth(4))
+        ));
+    }
+}
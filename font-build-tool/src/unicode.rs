@@ -1,19 +1,37 @@
+use crate::error::BuildError;
 use std::ops::RangeInclusive;
 
+/// An inclusive range of unicode code points.
+///
+/// Blocks are validated when they're constructed, so an invalid block used to
+/// initialize a constant fails to compile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct UnicodeCodeBlock {
     start: char,
     end: char,
 }
 
 impl UnicodeCodeBlock {
+    /// Returns a new `UnicodeCodeBlock` covering `start..=end`. A block can
+    /// contain a single character, in which case `start` and `end` are equal.
     pub const fn new(start: char, end: char) -> UnicodeCodeBlock {
-        if end > start {
-            panic!("end of unicode block must be greater than the start");
+        if end < start {
+            panic!("end of unicode block must not be less than the start");
         }
 
         UnicodeCodeBlock { start, end }
     }
 
+    /// First character in the block.
+    pub const fn start(&self) -> char {
+        self.start
+    }
+
+    /// Last character in the block (inclusive).
+    pub const fn end(&self) -> char {
+        self.end
+    }
+
     /// Unicode block expressed as an iterable range of characters.
     pub const fn range(&self) -> RangeInclusive<char> {
         RangeInclusive::new(self.start, self.end)
@@ -21,29 +39,111 @@ impl UnicodeCodeBlock {
 
     /// Number of characters covered by the code block.
     pub const fn block_size(&self) -> usize {
-        self.end as usize - self.start as usize
+        // Add 1, since the range is inclusive
+        self.end as usize - self.start as usize + 1
     }
 }
 
+/// Every predefined unicode code block, along with the name used to look it
+/// up, in code point order.
+pub const UNICODE_CODE_BLOCKS: &[(&str, UnicodeCodeBlock)] = &[
+    ("basic-latin", BASIC_LATIN),
+    ("hangul-jamo", HANGUL_JAMO),
+    ("general-punctuation", GENERAL_PUNCTUATION),
+    ("cjk-radicals-supplement", CJK_RADICALS_SUPPLEMENT),
+    ("kangxi-radicals", KANGXI_RADICALS),
+    ("cjk-symbols-and-punctuation", CJK_SYMBOLS_AND_PUNCTUATION),
+    ("hiragana", HIRAGANA),
+    ("katakana", KATAKANA),
+    ("bopomofo", BOPOMOFO),
+    ("hangul-compatibility-jamo", HANGUL_COMPATIBILITY_JAMO),
+    ("katakana-phonetic-extensions", KATAKANA_PHONETIC_EXTENSIONS),
+    (
+        "enclosed-cjk-letters-and-months",
+        ENCLOSED_CJK_LETTERS_AND_MONTHS,
+    ),
+    (
+        "cjk-unified-ideographs-extension-a",
+        CJK_UNIFIED_IDEOGRAPHS_EXTENSION_A,
+    ),
+    (
+        "cjk-unified-ideographs",
+        CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK,
+    ),
+    ("hangul-syllables", HANGUL_SYLLABLES),
+    ("cjk-compatibility-ideographs", CJK_COMPATIBILITY_IDEOGRAPHS),
+    (
+        "halfwidth-and-fullwidth-forms",
+        HALFWIDTH_AND_FULLWIDTH_FORMS,
+    ),
+    (
+        "cjk-unified-ideographs-extension-b",
+        CJK_UNIFIED_IDEOGRAPHS_EXTENSION_B,
+    ),
+    (
+        "cjk-unified-ideographs-extension-c",
+        CJK_UNIFIED_IDEOGRAPHS_EXTENSION_C,
+    ),
+    (
+        "cjk-unified-ideographs-extension-d",
+        CJK_UNIFIED_IDEOGRAPHS_EXTENSION_D,
+    ),
+    (
+        "cjk-unified-ideographs-extension-e",
+        CJK_UNIFIED_IDEOGRAPHS_EXTENSION_E,
+    ),
+    (
+        "cjk-unified-ideographs-extension-f",
+        CJK_UNIFIED_IDEOGRAPHS_EXTENSION_F,
+    ),
+    (
+        "cjk-unified-ideographs-extension-g",
+        CJK_UNIFIED_IDEOGRAPHS_EXTENSION_G,
+    ),
+];
+
+/// Looks up one of the predefined unicode code blocks by name, for example
+/// `cjk-unified-ideographs`. Names are case insensitive, and spaces or
+/// underscores can be used in place of hyphens.
+pub fn unicode_block_by_name(name: &str) -> Option<UnicodeCodeBlock> {
+    let name = name.to_ascii_lowercase().replace([' ', '_'], "-");
+
+    UNICODE_CODE_BLOCKS
+        .iter()
+        .find(|(block_name, _)| *block_name == name)
+        .map(|(_, block)| *block)
+}
+
+/// Looks up each of the named unicode code blocks, failing on the first name
+/// that doesn't match a predefined block.
+pub fn unicode_blocks_by_name<I, S>(names: I) -> Result<Vec<UnicodeCodeBlock>, BuildError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    names
+        .into_iter()
+        .map(|name| {
+            unicode_block_by_name(name.as_ref())
+                .ok_or_else(|| BuildError::UnknownUnicodeBlock(name.as_ref().to_string()))
+        })
+        .collect()
+}
+
 // Code Points           : 128
 // Wiki                  : https://en.wikipedia.org/wiki/Basic_Latin_(Unicode_block)
 // Unicode Version       : 1.0.0
 // Date Updated in Crate : 2022-03-13
 // Notes                 :
-pub const BASIC_LATIN: UnicodeCodeBlock = UnicodeCodeBlock {
-    start: '\u{0000}',
-    end: '\u{007F}',
-};
+pub const BASIC_LATIN: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{0000}', '\u{007F}');
 
 // Code Points           : 20992
 // Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_(Unicode_block)
 // Unicode Version       : 14.0
 // Date Updated in Crate : 2022-02-06
 // Notes                 :
-pub const CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK: UnicodeCodeBlock = UnicodeCodeBlock {
-    start: '\u{4E00}',
-    end: '\u{9FFF}',
-};
+pub const CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{4E00}', '\u{9FFF}');
 
 // Code Points           : 115
 // Wiki                  : https://en.wikipedia.org/wiki/CJK_Radicals_Supplement
@@ -53,7 +153,264 @@ pub const CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK: UnicodeCodeBlock = UnicodeCodeBl
 //   2022-02-06          :
 //     U+2E9A is unassigned, but is included in the range. Thus, this range
 //     covers 116 code points, even though technically only 115 are assigned.
-pub const CJK_RADICALS_SUPPLEMENT: UnicodeCodeBlock = UnicodeCodeBlock {
-    start: '\u{2E80}',
-    end: '\u{2EF3}',
-};
+pub const CJK_RADICALS_SUPPLEMENT: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{2E80}', '\u{2EF3}');
+
+// Code Points           : 256
+// Wiki                  : https://en.wikipedia.org/wiki/Hangul_Jamo_(Unicode_block)
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+pub const HANGUL_JAMO: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{1100}', '\u{11FF}');
+
+// Code Points           : 111
+// Wiki                  : https://en.wikipedia.org/wiki/General_Punctuation
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+2065 is unassigned, but is included in the range. Thus, this range
+//     covers 112 code points, even though technically only 111 are assigned.
+pub const GENERAL_PUNCTUATION: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{2000}', '\u{206F}');
+
+// Code Points           : 214
+// Wiki                  : https://en.wikipedia.org/wiki/Kangxi_Radicals_(Unicode_block)
+// Unicode Version       : 3.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+2FD6..U+2FDF are unassigned, but are included in the range. Thus, this
+//     range covers 224 code points, even though technically only 214 are
+//     assigned.
+pub const KANGXI_RADICALS: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{2F00}', '\u{2FDF}');
+
+// Code Points           : 64
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Symbols_and_Punctuation
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+pub const CJK_SYMBOLS_AND_PUNCTUATION: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{3000}', '\u{303F}');
+
+// Code Points           : 93
+// Wiki                  : https://en.wikipedia.org/wiki/Hiragana_(Unicode_block)
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+3040, U+3097, and U+3098 are unassigned, but are included in the range.
+//     Thus, this range covers 96 code points, even though technically only 93
+//     are assigned.
+pub const HIRAGANA: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{3040}', '\u{309F}');
+
+// Code Points           : 96
+// Wiki                  : https://en.wikipedia.org/wiki/Katakana_(Unicode_block)
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+pub const KATAKANA: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{30A0}', '\u{30FF}');
+
+// Code Points           : 43
+// Wiki                  : https://en.wikipedia.org/wiki/Bopomofo_(Unicode_block)
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+3100..U+3104 are unassigned, but are included in the range. Thus, this
+//     range covers 48 code points, even though technically only 43 are
+//     assigned.
+pub const BOPOMOFO: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{3100}', '\u{312F}');
+
+// Code Points           : 94
+// Wiki                  : https://en.wikipedia.org/wiki/Hangul_Compatibility_Jamo
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+3130 and U+318F are unassigned, but are included in the range. Thus,
+//     this range covers 96 code points, even though technically only 94 are
+//     assigned.
+pub const HANGUL_COMPATIBILITY_JAMO: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{3130}', '\u{318F}');
+
+// Code Points           : 16
+// Wiki                  : https://en.wikipedia.org/wiki/Katakana_Phonetic_Extensions
+// Unicode Version       : 3.2
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+pub const KATAKANA_PHONETIC_EXTENSIONS: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{31F0}', '\u{31FF}');
+
+// Code Points           : 255
+// Wiki                  : https://en.wikipedia.org/wiki/Enclosed_CJK_Letters_and_Months
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+321F is unassigned, but is included in the range. Thus, this range
+//     covers 256 code points, even though technically only 255 are assigned.
+pub const ENCLOSED_CJK_LETTERS_AND_MONTHS: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{3200}', '\u{32FF}');
+
+// Code Points           : 6592
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_Extension_A
+// Unicode Version       : 3.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+pub const CJK_UNIFIED_IDEOGRAPHS_EXTENSION_A: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{3400}', '\u{4DBF}');
+
+// Code Points           : 11172
+// Wiki                  : https://en.wikipedia.org/wiki/Hangul_Syllables
+// Unicode Version       : 2.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+D7A4..U+D7AF are unassigned, but are included in the range. Thus, this
+//     range covers 11184 code points, even though technically only 11172 are
+//     assigned.
+pub const HANGUL_SYLLABLES: UnicodeCodeBlock = UnicodeCodeBlock::new('\u{AC00}', '\u{D7AF}');
+
+// Code Points           : 472
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Compatibility_Ideographs
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+FA6E, U+FA6F, and U+FADA..U+FAFF are unassigned, but are included in
+//     the range. Thus, this range covers 512 code points, even though
+//     technically only 472 are assigned.
+pub const CJK_COMPATIBILITY_IDEOGRAPHS: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{F900}', '\u{FAFF}');
+
+// Code Points           : 225
+// Wiki                  : https://en.wikipedia.org/wiki/Halfwidth_and_Fullwidth_Forms_(Unicode_block)
+// Unicode Version       : 1.0.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     Several code points in this block are unassigned, but are included in
+//     the range. Thus, this range covers 240 code points, even though
+//     technically only 225 are assigned.
+pub const HALFWIDTH_AND_FULLWIDTH_FORMS: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{FF00}', '\u{FFEF}');
+
+// Code Points           : 42720
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_Extension_B
+// Unicode Version       : 3.1
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+pub const CJK_UNIFIED_IDEOGRAPHS_EXTENSION_B: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{20000}', '\u{2A6DF}');
+
+// Code Points           : 4154
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_Extension_C
+// Unicode Version       : 5.2
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+2B73A..U+2B73F are unassigned (as of Unicode 15.0), but are included
+//     in the range. Thus, this range covers 4160 code points, even though
+//     technically only 4154 are assigned.
+pub const CJK_UNIFIED_IDEOGRAPHS_EXTENSION_C: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{2A700}', '\u{2B73F}');
+
+// Code Points           : 222
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_Extension_D
+// Unicode Version       : 6.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+2B81E and U+2B81F are unassigned, but are included in the range. Thus,
+//     this range covers 224 code points, even though technically only 222 are
+//     assigned.
+pub const CJK_UNIFIED_IDEOGRAPHS_EXTENSION_D: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{2B740}', '\u{2B81F}');
+
+// Code Points           : 5762
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_Extension_E
+// Unicode Version       : 8.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+2CEA2..U+2CEAF are unassigned, but are included in the range. Thus,
+//     this range covers 5776 code points, even though technically only 5762
+//     are assigned.
+pub const CJK_UNIFIED_IDEOGRAPHS_EXTENSION_E: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{2B820}', '\u{2CEAF}');
+
+// Code Points           : 7473
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_Extension_F
+// Unicode Version       : 10.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+2EBE1..U+2EBEF are unassigned, but are included in the range. Thus,
+//     this range covers 7488 code points, even though technically only 7473
+//     are assigned.
+pub const CJK_UNIFIED_IDEOGRAPHS_EXTENSION_F: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{2CEB0}', '\u{2EBEF}');
+
+// Code Points           : 4939
+// Wiki                  : https://en.wikipedia.org/wiki/CJK_Unified_Ideographs_Extension_G
+// Unicode Version       : 13.0
+// Date Updated in Crate : 2026-10-18
+// Notes                 :
+//   2026-10-18          :
+//     U+3134B..U+3134F are unassigned, but are included in the range. Thus,
+//     this range covers 4944 code points, even though technically only 4939
+//     are assigned.
+pub const CJK_UNIFIED_IDEOGRAPHS_EXTENSION_G: UnicodeCodeBlock =
+    UnicodeCodeBlock::new('\u{30000}', '\u{3134F}');
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        unicode_block_by_name, unicode_blocks_by_name, UnicodeCodeBlock, BASIC_LATIN,
+        CJK_RADICALS_SUPPLEMENT, CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK, HANGUL_SYLLABLES,
+        UNICODE_CODE_BLOCKS,
+    };
+
+    #[test]
+    fn test_unicode_block_by_name() {
+        let hiragana = unicode_block_by_name("Hiragana").unwrap();
+
+        assert_eq!(hiragana.range(), '\u{3040}'..='\u{309F}');
+        assert!(unicode_block_by_name("halfwidth_and_fullwidth forms").is_some());
+        assert!(unicode_block_by_name("klingon").is_none());
+        assert!(unicode_blocks_by_name(["hiragana", "klingon"]).is_err());
+    }
+
+    #[test]
+    fn test_block_size_is_inclusive() {
+        assert_eq!(UnicodeCodeBlock::new('?', '?').block_size(), 1);
+        assert_eq!(BASIC_LATIN.block_size(), 128);
+        assert_eq!(CJK_RADICALS_SUPPLEMENT.block_size(), 116);
+        assert_eq!(CJK_UNIFIED_IDEOGRAPHS_UNICODE_BLOCK.block_size(), 20992);
+        assert_eq!(HANGUL_SYLLABLES.block_size(), 11184);
+    }
+
+    #[test]
+    fn test_block_size_matches_range() {
+        for (name, block) in UNICODE_CODE_BLOCKS {
+            assert_eq!(block.block_size(), block.range().count(), "{}", name);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be less than the start")]
+    fn test_reversed_block() {
+        UnicodeCodeBlock::new('\u{9FFF}', '\u{4E00}');
+    }
+
+    #[test]
+    fn test_unicode_code_blocks_sorted() {
+        for pair in UNICODE_CODE_BLOCKS.windows(2) {
+            assert!(
+                pair[0].1.range().end() < pair[1].1.range().start(),
+                "{} overlaps {}",
+                pair[0].0,
+                pair[1].0
+            );
+        }
+    }
+}
@@ -1,5 +1,15 @@
+// This is generated code. Any modifications to this file will
+// be overwritten.
+#[cfg(feature = "size-12")]
 mod fusion_pixel_12;
+#[cfg(feature = "size-24")]
 mod fusion_pixel_24;
 
+#[cfg(feature = "size-12")]
 pub use fusion_pixel_12::FONT as FUSION_PIXEL_12;
+#[cfg(feature = "size-12")]
+pub use fusion_pixel_12::GLYPH_MAPPING as FUSION_PIXEL_12_GLYPH_MAPPING;
+#[cfg(feature = "size-24")]
 pub use fusion_pixel_24::FONT as FUSION_PIXEL_24;
+#[cfg(feature = "size-24")]
+pub use fusion_pixel_24::GLYPH_MAPPING as FUSION_PIXEL_24_GLYPH_MAPPING;
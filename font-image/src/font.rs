@@ -0,0 +1,291 @@
+use crate::{crc32, read_u32, PackedGlyphMapping, ParseError, MAX_NAME_LEN};
+use embedded_graphics::{
+    geometry::Size,
+    mono_font::{DecorationDimensions, MonoFont},
+};
+use embedded_graphics_cjk_compressed_font::CompressedFont;
+use embedded_graphics_cjk_glyph_mapping::binary_image;
+use embedded_graphics_cjk_gray_font::GrayFont;
+
+/// The number of glyphs on a single line of a raw bitmap.
+const GLYPHS_PER_ROW: u32 = 32;
+
+/// How a font's glyphs are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FontFormat {
+    /// The bitmap written by `MonoFontBuilder`, with glyphs in rows of 32
+    /// cells.
+    Raw = 0,
+
+    /// Glyphs compressed on their own, in the format of a `CompressedFont`.
+    Compressed = 1,
+}
+
+impl TryFrom<u8> for FontFormat {
+    type Error = ParseError;
+
+    fn try_from(format: u8) -> Result<Self, ParseError> {
+        match format {
+            0 => Ok(FontFormat::Raw),
+            1 => Ok(FontFormat::Compressed),
+            _ => Err(ParseError::UnknownFormat(format)),
+        }
+    }
+}
+
+/// A font in a [`FontImage`](crate::FontImage).
+///
+/// Glyph data is borrowed from the image, so fonts in memory mapped flash
+/// can be drawn without copying them. For storage that has to be read
+/// explicitly, [`PackedFont::data_offset`] is where the glyph data starts.
+#[derive(Clone, Copy, Debug)]
+pub struct PackedFont<'a> {
+    name: &'a str,
+    format: FontFormat,
+    bits_per_pixel: u8,
+    character_size: Size,
+    baseline: u32,
+    underline: DecorationDimensions,
+    strikethrough: DecorationDimensions,
+    glyph_mapping: PackedGlyphMapping<'a>,
+    data: &'a [u8],
+    data_offset: u32,
+    data_checksum: u32,
+}
+
+impl<'a> PackedFont<'a> {
+    /// Reads a directory entry of a font in `image`.
+    pub(crate) fn parse(image: &'a [u8], entry: &'a [u8]) -> Result<Self, ParseError> {
+        let name = &entry[..MAX_NAME_LEN];
+        let name_len = name
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(name.len());
+        let name = core::str::from_utf8(&name[..name_len]).map_err(|_| ParseError::InvalidName)?;
+
+        let format = FontFormat::try_from(entry[24])?;
+        let bits_per_pixel = entry[25];
+
+        match (format, bits_per_pixel) {
+            (FontFormat::Raw, 1 | 2 | 4 | 8) | (FontFormat::Compressed, 1) => (),
+            _ => return Err(ParseError::InvalidBitDepth(bits_per_pixel)),
+        }
+
+        let [width, height, baseline] = [entry[26], entry[27], entry[28]].map(u32::from);
+        let decoration = |field: usize| {
+            DecorationDimensions::new(u32::from(entry[field]), u32::from(entry[field + 1]))
+        };
+
+        let mapping = table(image, entry, 36)?;
+        let data = table(image, entry, 44)?;
+
+        Ok(PackedFont {
+            name,
+            format,
+            bits_per_pixel,
+            character_size: Size::new(width, height),
+            baseline,
+            underline: decoration(29),
+            strikethrough: decoration(31),
+            glyph_mapping: PackedGlyphMapping::parse(mapping)?,
+            data,
+            data_offset: read_u32(entry, 44),
+            data_checksum: read_u32(entry, 52),
+        })
+    }
+
+    /// The name the font was packed with.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// How the font's glyphs are stored.
+    pub fn format(&self) -> FontFormat {
+        self.format
+    }
+
+    /// Number of bits used for each pixel.
+    pub fn bits_per_pixel(&self) -> u8 {
+        self.bits_per_pixel
+    }
+
+    /// Size of a single character in pixels.
+    pub fn character_size(&self) -> Size {
+        self.character_size
+    }
+
+    /// The baseline, as an offset from the top of a glyph.
+    pub fn baseline(&self) -> u32 {
+        self.baseline
+    }
+
+    /// Offset from top of a glyph to the top of the underline, and its
+    /// height.
+    pub fn underline(&self) -> DecorationDimensions {
+        self.underline
+    }
+
+    /// Offset from top of a glyph to the top of the strikethrough, and its
+    /// height.
+    pub fn strikethrough(&self) -> DecorationDimensions {
+        self.strikethrough
+    }
+
+    /// Glyph mapping.
+    pub fn glyph_mapping(&self) -> &PackedGlyphMapping<'a> {
+        &self.glyph_mapping
+    }
+
+    /// The font's glyph data.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Offset of the glyph data from the start of the image.
+    pub fn data_offset(&self) -> u32 {
+        self.data_offset
+    }
+
+    /// Returns `true` if the glyph data matches its checksum. This reads all
+    /// of the data, so it's usually only done when the image is written.
+    pub fn verify(&self) -> bool {
+        crc32(self.data) == self.data_checksum
+    }
+
+    /// Returns the font as a `MonoFont`, if it's a raw font with 1 bit per
+    /// pixel.
+    pub fn mono_font(&self) -> Option<MonoFont<'_>> {
+        if self.format != FontFormat::Raw || self.bits_per_pixel != 1 {
+            return None;
+        }
+
+        Some(MonoFont {
+            image: binary_image(self.data, self.image_width()),
+            glyph_mapping: &self.glyph_mapping,
+            character_size: self.character_size,
+            character_spacing: 0,
+            baseline: self.baseline,
+            underline: self.underline,
+            strikethrough: self.strikethrough,
+        })
+    }
+
+    /// Returns the font as a `GrayFont`, if it's a raw font with more than 1
+    /// bit per pixel.
+    pub fn gray_font(&self) -> Option<GrayFont<'_>> {
+        if self.format != FontFormat::Raw || self.bits_per_pixel == 1 {
+            return None;
+        }
+
+        Some(GrayFont {
+            data: self.data,
+            image_width: self.image_width(),
+            bits_per_pixel: self.bits_per_pixel,
+            glyph_mapping: &self.glyph_mapping,
+            character_size: self.character_size,
+            character_spacing: 0,
+            baseline: self.baseline,
+        })
+    }
+
+    /// Returns the font as a `CompressedFont`, if it's compressed.
+    pub fn compressed_font(&self) -> Option<CompressedFont<'_>> {
+        if self.format != FontFormat::Compressed {
+            return None;
+        }
+
+        Some(CompressedFont {
+            data: self.data,
+            glyph_mapping: &self.glyph_mapping,
+            character_size: self.character_size,
+            character_spacing: 0,
+            baseline: self.baseline,
+            underline: self.underline,
+            strikethrough: self.strikethrough,
+        })
+    }
+
+    /// Width of a raw bitmap, in pixels.
+    fn image_width(&self) -> u32 {
+        GLYPHS_PER_ROW * self.character_size.width
+    }
+}
+
+/// Returns the part of the image at the offset, and length stored at `field`
+/// in a directory entry.
+fn table<'a>(image: &'a [u8], entry: &[u8], field: usize) -> Result<&'a [u8], ParseError> {
+    let offset = read_u32(entry, field) as usize;
+    let len = read_u32(entry, field + 4) as usize;
+
+    offset
+        .checked_add(len)
+        .and_then(|end| image.get(offset..end))
+        .ok_or(ParseError::Truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FontFormat;
+    use crate::{tests::image, FontImage};
+    use embedded_graphics::{
+        geometry::Size,
+        mock_display::MockDisplay,
+        mono_font::MonoTextStyle,
+        pixelcolor::BinaryColor,
+        prelude::*,
+        text::{Baseline, Text},
+    };
+
+    #[test]
+    fn test_font() {
+        let image = image();
+        let fonts = FontImage::parse(&image).unwrap();
+        let font = fonts.font("test").unwrap();
+
+        assert_eq!(font.name(), "test");
+        assert_eq!(font.format(), FontFormat::Raw);
+        assert_eq!(font.bits_per_pixel(), 1);
+        assert_eq!(font.character_size(), Size::new(4, 2));
+        assert_eq!(font.baseline(), 1);
+        assert_eq!(font.underline().offset, 1);
+        assert_eq!(font.strikethrough().height, 1);
+        assert_eq!(font.glyph_mapping().num_duplicates(), 1);
+        assert_eq!(font.data_offset(), 128);
+        assert_eq!(font.data().len(), 32);
+        assert!(font.verify());
+        assert!(font.gray_font().is_none());
+        assert!(font.compressed_font().is_none());
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut image = image();
+
+        image[150] = 1;
+
+        let fonts = FontImage::parse(&image).unwrap();
+
+        assert!(!fonts.font("test").unwrap().verify());
+    }
+
+    #[test]
+    fn test_draw_mono_font() {
+        let image = image();
+        let fonts = FontImage::parse(&image).unwrap();
+        let font = fonts.font("test").unwrap();
+        let mono_font = font.mono_font().unwrap();
+        let style = MonoTextStyle::new(&mono_font, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        Text::with_baseline("ab", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        // `b` is drawn with the glyph of `?`
+        display.assert_pattern(&[
+            " #  ####", //
+            " #  #  #", //
+        ]);
+    }
+}
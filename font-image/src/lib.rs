@@ -0,0 +1,363 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod font;
+mod mapping;
+
+pub use font::{FontFormat, PackedFont};
+pub use mapping::PackedGlyphMapping;
+
+/// Identifies a font image.
+pub const MAGIC: [u8; 4] = *b"EGCJ";
+
+/// Version of the image format that is written, and can be read.
+pub const VERSION: u16 = 1;
+
+/// Size of the header at the start of the image, in bytes.
+pub const HEADER_SIZE: usize = 16;
+
+/// Size of each entry in the font directory, in bytes.
+pub const ENTRY_SIZE: usize = 56;
+
+/// Longest font name, in bytes.
+pub const MAX_NAME_LEN: usize = 24;
+
+/// Several fonts packed into a single image, to be written to flash.
+///
+/// All values are little-endian. The image starts with a header:
+///
+/// | Offset | Size | Field                                          |
+/// | ------ | ---- | ---------------------------------------------- |
+/// | 0      | 4    | [`MAGIC`]                                      |
+/// | 4      | 2    | [`VERSION`]                                    |
+/// | 6      | 2    | Number of fonts                                |
+/// | 8      | 4    | Alignment of each font's glyph data            |
+/// | 12     | 4    | CRC-32 of the directory, and glyph mappings    |
+///
+/// The header is followed by the font directory, with an entry of
+/// [`ENTRY_SIZE`] bytes for each font:
+///
+/// | Offset | Size | Field                                          |
+/// | ------ | ---- | ---------------------------------------------- |
+/// | 0      | 24   | Name, padded with zeros                        |
+/// | 24     | 1    | [`FontFormat`]                                 |
+/// | 25     | 1    | Bits per pixel                                 |
+/// | 26     | 2    | Glyph width, and height                        |
+/// | 28     | 1    | Baseline                                       |
+/// | 29     | 2    | Underline offset, and height                   |
+/// | 31     | 2    | Strikethrough offset, and height               |
+/// | 33     | 3    | Reserved                                       |
+/// | 36     | 8    | Offset, and length of the glyph mapping        |
+/// | 44     | 8    | Offset, and length of the glyph data           |
+/// | 52     | 4    | CRC-32 of the glyph data                       |
+///
+/// Glyph mappings follow the directory. See [`PackedGlyphMapping`] for
+/// their format. Each font's glyph data starts at a multiple of the
+/// alignment, so fonts can be erased, and written on their own.
+#[derive(Clone, Copy, Debug)]
+pub struct FontImage<'a> {
+    data: &'a [u8],
+    num_fonts: usize,
+}
+
+/// Errors opening a font image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The image doesn't start with [`MAGIC`].
+    BadMagic,
+
+    /// The image was written with a different version of the format.
+    UnsupportedVersion(u16),
+
+    /// A table, or glyph data extends past the end of the image.
+    Truncated,
+
+    /// The directory, or glyph mappings don't match their checksum.
+    ChecksumMismatch,
+
+    /// A font's name isn't valid UTF-8.
+    InvalidName,
+
+    /// A font has an unknown format.
+    UnknownFormat(u8),
+
+    /// A font's bit depth isn't supported by its format.
+    InvalidBitDepth(u8),
+
+    /// A glyph mapping's length doesn't match the number of ranges, and
+    /// duplicates it contains.
+    InvalidMapping,
+}
+
+impl<'a> FontImage<'a> {
+    /// Opens a font image, checking the header, and every directory entry.
+    /// Glyph data is only checked by [`PackedFont::verify`].
+    pub fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        let header = data.get(..HEADER_SIZE).ok_or(ParseError::Truncated)?;
+
+        if header[..4] != MAGIC {
+            return Err(ParseError::BadMagic);
+        }
+
+        let version = read_u16(header, 4);
+
+        if version != VERSION {
+            return Err(ParseError::UnsupportedVersion(version));
+        }
+
+        let num_fonts = usize::from(read_u16(header, 6));
+        let directory_end = HEADER_SIZE + num_fonts * ENTRY_SIZE;
+        let directory = data
+            .get(HEADER_SIZE..directory_end)
+            .ok_or(ParseError::Truncated)?;
+
+        // The checksum is checked before anything else in the directory, so
+        // a corrupt image is reported as corrupt.
+        let metadata_end = directory
+            .chunks_exact(ENTRY_SIZE)
+            .map(|entry| {
+                (read_u32(entry, 36) as usize).saturating_add(read_u32(entry, 40) as usize)
+            })
+            .fold(directory_end, usize::max);
+        let metadata = data
+            .get(HEADER_SIZE..metadata_end)
+            .ok_or(ParseError::Truncated)?;
+
+        if crc32(metadata) != read_u32(header, 12) {
+            return Err(ParseError::ChecksumMismatch);
+        }
+
+        let image = FontImage { data, num_fonts };
+
+        for index in 0..num_fonts {
+            image.font_at(index)?;
+        }
+
+        Ok(image)
+    }
+
+    /// Number of fonts in the image.
+    pub fn len(&self) -> usize {
+        self.num_fonts
+    }
+
+    /// Returns `true` if the image doesn't contain any fonts.
+    pub fn is_empty(&self) -> bool {
+        self.num_fonts == 0
+    }
+
+    /// Alignment of each font's glyph data, in bytes.
+    pub fn alignment(&self) -> u32 {
+        read_u32(self.data, 8)
+    }
+
+    /// Returns the font with the given name.
+    pub fn font(&self, name: &str) -> Option<PackedFont<'a>> {
+        self.fonts().find(|font| font.name() == name)
+    }
+
+    /// Returns an iterator over the fonts, in the order they were packed.
+    pub fn fonts(&self) -> impl Iterator<Item = PackedFont<'a>> + 'a {
+        let image = *self;
+
+        // Entries were checked by `parse`
+        (0..self.num_fonts).filter_map(move |index| image.font_at(index).ok())
+    }
+
+    fn font_at(&self, index: usize) -> Result<PackedFont<'a>, ParseError> {
+        let entry = &self.data[HEADER_SIZE + index * ENTRY_SIZE..][..ENTRY_SIZE];
+
+        PackedFont::parse(self.data, entry)
+    }
+}
+
+/// Computes the CRC-32 (IEEE 802.3) checksum used in font images.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in data {
+        crc ^= u32::from(*byte);
+
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+
+    !crc
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{crc32, read_u32, FontImage, ParseError, ENTRY_SIZE, HEADER_SIZE, MAGIC};
+
+    /// Size of [`image`].
+    pub(crate) const IMAGE_SIZE: usize = 160;
+
+    /// Updates the checksum of the directory, and glyph mapping.
+    fn update_checksum(image: &mut [u8]) {
+        let mapping_end = read_u32(image, 16 + 36) + read_u32(image, 16 + 40);
+        let checksum = crc32(&image[HEADER_SIZE..mapping_end as usize]);
+
+        image[12..16].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    /// An image with a single 1 bpp font named `test`, with 4x2 cells, and
+    /// glyphs for `?`, `a`, and `b`. `b` reuses the glyph of `?`. The glyph
+    /// data is aligned to 64 bytes.
+    pub(crate) fn image() -> [u8; IMAGE_SIZE] {
+        let mut image = [0; IMAGE_SIZE];
+        let mut put = |offset: usize, values: &[u32]| {
+            for (i, value) in values.iter().enumerate() {
+                image[offset + i * 4..][..4].copy_from_slice(&value.to_le_bytes());
+            }
+        };
+
+        // Header
+        put(8, &[64]);
+
+        // Directory entry: glyph mapping, and glyph data
+        put(16 + 36, &[72, 44, 128, 32]);
+
+        // Glyph mapping: default slot, ranges, duplicates
+        put(72, &[0, 2, 1]);
+        put(84, &['?' as u32, '?' as u32, 0]);
+        put(96, &['a' as u32, 'b' as u32, 1]);
+        put(108, &[2, 0]);
+
+        image[..4].copy_from_slice(&MAGIC);
+        image[4] = 1;
+        image[6] = 1;
+        image[16..20].copy_from_slice(b"test");
+
+        // Format, bits per pixel, cell size, baseline, and decorations
+        image[16 + 24..][..9].copy_from_slice(&[0, 1, 4, 2, 1, 1, 1, 0, 1]);
+
+        // Glyph data, with 32 glyphs on each line. `?` is a box, and `a` is
+        // a vertical line.
+        image[128] = 0b1111_0100;
+        image[128 + 16] = 0b1001_0100;
+
+        let data_checksum = crc32(&image[128..]);
+
+        image[16 + 52..][..4].copy_from_slice(&data_checksum.to_le_bytes());
+        update_checksum(&mut image);
+
+        image
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_parse() {
+        let image = image();
+        let fonts = FontImage::parse(&image).unwrap();
+
+        assert_eq!(fonts.len(), 1);
+        assert!(!fonts.is_empty());
+        assert_eq!(fonts.alignment(), 64);
+        assert_eq!(fonts.fonts().count(), 1);
+        assert!(fonts.font("test").is_some());
+        assert!(fonts.font("tes").is_none());
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let mut image = image();
+
+        image[6] = 0;
+        image[12..16].copy_from_slice(&crc32(&[]).to_le_bytes());
+
+        let fonts = FontImage::parse(&image[..HEADER_SIZE]).unwrap();
+
+        assert!(fonts.is_empty());
+        assert!(fonts.font("test").is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // Changes the image, keeping the checksum valid
+        let parse = |change: fn(&mut [u8; IMAGE_SIZE])| {
+            let mut image = image();
+
+            change(&mut image);
+            update_checksum(&mut image);
+            FontImage::parse(&image).err()
+        };
+
+        assert_eq!(parse(|image| image[0] = b'X'), Some(ParseError::BadMagic));
+        assert_eq!(
+            parse(|image| image[4] = 2),
+            Some(ParseError::UnsupportedVersion(2))
+        );
+        assert_eq!(parse(|image| image[6] = 3), Some(ParseError::Truncated));
+        assert_eq!(
+            parse(|image| image[16] = 0xFF),
+            Some(ParseError::InvalidName)
+        );
+        assert_eq!(
+            parse(|image| image[16 + 24] = 7),
+            Some(ParseError::UnknownFormat(7))
+        );
+        assert_eq!(
+            parse(|image| image[16 + 25] = 3),
+            Some(ParseError::InvalidBitDepth(3))
+        );
+        assert_eq!(
+            parse(|image| image[16 + 24..][..2].copy_from_slice(&[1, 4])),
+            Some(ParseError::InvalidBitDepth(4))
+        );
+        assert_eq!(
+            parse(|image| image[16 + 40] = 40),
+            Some(ParseError::InvalidMapping)
+        );
+        assert_eq!(
+            parse(|image| image[16 + 49] = 1),
+            Some(ParseError::Truncated)
+        );
+        assert_eq!(
+            FontImage::parse(&image()[..HEADER_SIZE + ENTRY_SIZE - 1]).err(),
+            Some(ParseError::Truncated)
+        );
+
+        let mut image = image();
+
+        image[16] = b'T';
+
+        assert_eq!(
+            FontImage::parse(&image).err(),
+            Some(ParseError::ChecksumMismatch)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{read_u32, ParseError};
+use embedded_graphics::mono_font::mapping::GlyphMapping;
+use embedded_graphics_cjk_glyph_mapping::TryGlyphMapping;
+
+/// Size of the counts at the start of a glyph mapping, in bytes.
+const COUNTS_SIZE: usize = 12;
+
+/// Size of a range of characters, in bytes.
+const RANGE_SIZE: usize = 12;
+
+/// Size of a duplicate glyph, in bytes.
+const DUPLICATE_SIZE: usize = 8;
+
+/// A glyph mapping read directly from a font image.
+///
+/// The mapping is a list of little-endian `u32`s. It starts with the slot
+/// used for characters that aren't covered, the number of ranges, and the
+/// number of duplicates. Each range is the first, and last character it
+/// covers, followed by the slot of the first character. Ranges are sorted,
+/// and don't overlap. Sparse mappings are stored as runs of consecutive
+/// characters.
+///
+/// Slots that reuse an earlier glyph follow the ranges, as pairs of the
+/// slot, and the index of the glyph, sorted by slot. Like
+/// `DedupGlyphMapping`, every other slot is moved down by the number of
+/// duplicates before it.
+#[derive(Clone, Copy, Debug)]
+pub struct PackedGlyphMapping<'a> {
+    data: &'a [u8],
+    num_ranges: usize,
+    num_duplicates: usize,
+}
+
+impl<'a> PackedGlyphMapping<'a> {
+    /// Reads a glyph mapping, checking that its length matches the number of
+    /// ranges, and duplicates.
+    pub fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        if data.len() < COUNTS_SIZE {
+            return Err(ParseError::InvalidMapping);
+        }
+
+        let num_ranges = read_u32(data, 4) as usize;
+        let num_duplicates = read_u32(data, 8) as usize;
+        let len = num_ranges
+            .checked_mul(RANGE_SIZE)
+            .zip(num_duplicates.checked_mul(DUPLICATE_SIZE))
+            .and_then(|(ranges, duplicates)| ranges.checked_add(duplicates))
+            .and_then(|len| len.checked_add(COUNTS_SIZE));
+
+        if len != Some(data.len()) {
+            return Err(ParseError::InvalidMapping);
+        }
+
+        Ok(PackedGlyphMapping {
+            data,
+            num_ranges,
+            num_duplicates,
+        })
+    }
+
+    /// Number of ranges of consecutive characters.
+    pub fn num_ranges(&self) -> usize {
+        self.num_ranges
+    }
+
+    /// Number of slots that reuse an earlier glyph.
+    pub fn num_duplicates(&self) -> usize {
+        self.num_duplicates
+    }
+
+    fn range(&self, index: usize) -> (u32, u32, usize) {
+        let offset = COUNTS_SIZE + index * RANGE_SIZE;
+
+        (
+            read_u32(self.data, offset),
+            read_u32(self.data, offset + 4),
+            read_u32(self.data, offset + 8) as usize,
+        )
+    }
+
+    fn duplicate(&self, index: usize) -> (usize, usize) {
+        let offset = COUNTS_SIZE + self.num_ranges * RANGE_SIZE + index * DUPLICATE_SIZE;
+
+        (
+            read_u32(self.data, offset) as usize,
+            read_u32(self.data, offset + 4) as usize,
+        )
+    }
+
+    /// Returns the slot of a character, if it's covered by one of the
+    /// ranges.
+    fn slot(&self, chr: char) -> Option<usize> {
+        let chr = chr as u32;
+        let after = partition_point(self.num_ranges, |index| self.range(index).0 <= chr);
+        let (start, end, first_slot) = self.range(after.checked_sub(1)?);
+
+        (chr <= end).then(|| first_slot.saturating_add((chr - start) as usize))
+    }
+
+    /// Returns the index of the stored glyph for a slot.
+    fn glyph_index(&self, slot: usize) -> usize {
+        let before = partition_point(self.num_duplicates, |index| self.duplicate(index).0 < slot);
+
+        match (before < self.num_duplicates).then(|| self.duplicate(before)) {
+            Some((duplicate, glyph)) if duplicate == slot => glyph,
+            _ => slot.saturating_sub(before),
+        }
+    }
+}
+
+/// Returns the number of indices, starting from 0, that `pred` is true for.
+/// `pred` has to be true for every index before the first one it's false
+/// for.
+fn partition_point<F>(len: usize, pred: F) -> usize
+where
+    F: Fn(usize) -> bool,
+{
+    let (mut low, mut high) = (0, len);
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if pred(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    low
+}
+
+impl GlyphMapping for PackedGlyphMapping<'_> {
+    fn index(&self, chr: char) -> usize {
+        let slot = self
+            .slot(chr)
+            .unwrap_or_else(|| read_u32(self.data, 0) as usize);
+
+        self.glyph_index(slot)
+    }
+}
+
+impl TryGlyphMapping for PackedGlyphMapping<'_> {
+    fn try_index(&self, chr: char) -> Option<usize> {
+        self.slot(chr).map(|slot| self.glyph_index(slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PackedGlyphMapping;
+    use crate::ParseError;
+    use embedded_graphics::mono_font::mapping::GlyphMapping;
+    use embedded_graphics_cjk_glyph_mapping::TryGlyphMapping;
+
+    /// Packs a list of `u32`s.
+    fn pack<const N: usize>(values: [u32; N]) -> [[u8; 4]; N] {
+        values.map(u32::to_le_bytes)
+    }
+
+    #[test]
+    fn test_ranges_and_duplicates() {
+        // `b`, and `d` look the same as `?`, and `e` looks the same as `c`,
+        // like the `DedupGlyphMapping` tests. `x` is in a range of its own.
+        let data = pack([
+            0, 3, 3, //
+            '?' as u32, '?' as u32, 0, //
+            'a' as u32, 'e' as u32, 1, //
+            'x' as u32, 'x' as u32, 6, //
+            2, 0, 4, 0, 5, 2,
+        ]);
+        let map = PackedGlyphMapping::parse(data.as_flattened()).unwrap();
+
+        assert_eq!(map.num_ranges(), 3);
+        assert_eq!(map.num_duplicates(), 3);
+        assert_eq!(map.index('?'), 0);
+        assert_eq!(map.index('a'), 1);
+        assert_eq!(map.index('b'), 0);
+        assert_eq!(map.index('c'), 2);
+        assert_eq!(map.index('d'), 0);
+        assert_eq!(map.index('e'), 2);
+        assert_eq!(map.index('x'), 3);
+        assert_eq!(map.index('z'), 0);
+        assert_eq!(map.index('>'), 0);
+        assert_eq!(map.try_index('x'), Some(3));
+        assert_eq!(map.try_index('f'), None);
+        assert_eq!(map.try_index('\0'), None);
+    }
+
+    #[test]
+    fn test_default_slot_is_deduplicated() {
+        // The fallback character is the third slot, but reuses the first
+        // glyph.
+        let data = pack([2, 1, 1, 'a' as u32, 'c' as u32, 0, 2, 0]);
+        let map = PackedGlyphMapping::parse(data.as_flattened()).unwrap();
+
+        assert_eq!(map.index('b'), 1);
+        assert_eq!(map.index('z'), 0);
+    }
+
+    #[test]
+    fn test_invalid_length() {
+        let data = pack([0, 1, 0, 'a' as u32, 'c' as u32, 0]);
+
+        assert!(PackedGlyphMapping::parse(data.as_flattened()).is_ok());
+        assert_eq!(
+            PackedGlyphMapping::parse(&data.as_flattened()[..20]).err(),
+            Some(ParseError::InvalidMapping)
+        );
+        assert_eq!(
+            PackedGlyphMapping::parse(&[0; 8]).err(),
+            Some(ParseError::InvalidMapping)
+        );
+
+        let data = pack([0, u32::MAX, u32::MAX]);
+
+        assert_eq!(
+            PackedGlyphMapping::parse(data.as_flattened()).err(),
+            Some(ParseError::InvalidMapping)
+        );
+    }
+}
@@ -1,7 +1,21 @@
+// This is generated code. Any modifications to this file will
+// be overwritten.
+#[cfg(feature = "size-24")]
 mod noto_sans_mono_sc_regular_24;
+#[cfg(feature = "size-32")]
 mod noto_sans_mono_sc_regular_32;
+#[cfg(feature = "size-36")]
 mod noto_sans_mono_sc_regular_36;
 
+#[cfg(feature = "size-24")]
 pub use noto_sans_mono_sc_regular_24::FONT as NOTO_SANS_MONO_CJK_SC_REGULAR_24;
+#[cfg(feature = "size-24")]
+pub use noto_sans_mono_sc_regular_24::GLYPH_MAPPING as NOTO_SANS_MONO_CJK_SC_REGULAR_24_GLYPH_MAPPING;
+#[cfg(feature = "size-32")]
 pub use noto_sans_mono_sc_regular_32::FONT as NOTO_SANS_MONO_CJK_SC_REGULAR_32;
+#[cfg(feature = "size-32")]
+pub use noto_sans_mono_sc_regular_32::GLYPH_MAPPING as NOTO_SANS_MONO_CJK_SC_REGULAR_32_GLYPH_MAPPING;
+#[cfg(feature = "size-36")]
 pub use noto_sans_mono_sc_regular_36::FONT as NOTO_SANS_MONO_CJK_SC_REGULAR_36;
+#[cfg(feature = "size-36")]
+pub use noto_sans_mono_sc_regular_36::GLYPH_MAPPING as NOTO_SANS_MONO_CJK_SC_REGULAR_36_GLYPH_MAPPING;
@@ -1,26 +1,31 @@
 // This is generated code. Any modifications to this file will
 // be overwritten.
-use embedded_graphics::{
-    geometry::Size,
-    image::ImageRaw,
-    mono_font::{DecorationDimensions, MonoFont},
+use embedded_graphics_cjk_glyph_mapping::{
+    binary_image,
+    embedded_graphics::{
+        geometry::Size,
+        mono_font::{DecorationDimensions, MonoFont},
+    },
+    IndexedRangeGlyphMapping,
 };
-use embedded_graphics_cjk_glyph_mapping::RangeGlyphMapping;
+
+#[rustfmt::skip]
+pub const GLYPH_MAPPING: IndexedRangeGlyphMapping<3> = IndexedRangeGlyphMapping::new(
+    [
+        '?'..='?',
+        '\u{2E80}'..='\u{2EF3}',
+        '\u{4E00}'..='\u{9FFF}',
+    ],
+    0
+);
 
 #[rustfmt::skip]
 pub const FONT: MonoFont = MonoFont {
-    image: ImageRaw::new_binary(
+    image: binary_image(
         include_bytes!("data/noto_sans_mono_sc_regular-36.bin"),
         32 * 36,
     ),
-    glyph_mapping: &RangeGlyphMapping::new_unchecked(
-        [
-            '?'..='?',                      // ?
-            '\u{2E80}'..='\u{2EF3}',    // CJK Radicals Supplement
-            '\u{4E00}'..='\u{9FFF}',    // CJK Unified Ideographs
-        ],
-        0
-    ),
+    glyph_mapping: &GLYPH_MAPPING,
     character_size: Size::new(36, 40),
     character_spacing: 0,
     baseline: 0,
@@ -1,7 +1,21 @@
+// This is generated code. Any modifications to this file will
+// be overwritten.
+#[cfg(feature = "size-24")]
 mod sarasa_mono_sc_light_24;
+#[cfg(feature = "size-32")]
 mod sarasa_mono_sc_light_32;
+#[cfg(feature = "size-36")]
 mod sarasa_mono_sc_light_36;
 
+#[cfg(feature = "size-24")]
 pub use sarasa_mono_sc_light_24::FONT as SARASA_MONO_SC_LIGHT_24;
+#[cfg(feature = "size-24")]
+pub use sarasa_mono_sc_light_24::GLYPH_MAPPING as SARASA_MONO_SC_LIGHT_24_GLYPH_MAPPING;
+#[cfg(feature = "size-32")]
 pub use sarasa_mono_sc_light_32::FONT as SARASA_MONO_SC_LIGHT_32;
+#[cfg(feature = "size-32")]
+pub use sarasa_mono_sc_light_32::GLYPH_MAPPING as SARASA_MONO_SC_LIGHT_32_GLYPH_MAPPING;
+#[cfg(feature = "size-36")]
 pub use sarasa_mono_sc_light_36::FONT as SARASA_MONO_SC_LIGHT_36;
+#[cfg(feature = "size-36")]
+pub use sarasa_mono_sc_light_36::GLYPH_MAPPING as SARASA_MONO_SC_LIGHT_36_GLYPH_MAPPING;
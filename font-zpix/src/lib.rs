@@ -1,5 +1,15 @@
+// This is generated code. Any modifications to this file will
+// be overwritten.
+#[cfg(feature = "size-12")]
 mod zpix_12;
+#[cfg(feature = "size-24")]
 mod zpix_24;
 
+#[cfg(feature = "size-12")]
 pub use zpix_12::FONT as ZPIX_12;
+#[cfg(feature = "size-12")]
+pub use zpix_12::GLYPH_MAPPING as ZPIX_12_GLYPH_MAPPING;
+#[cfg(feature = "size-24")]
 pub use zpix_24::FONT as ZPIX_24;
+#[cfg(feature = "size-24")]
+pub use zpix_24::GLYPH_MAPPING as ZPIX_24_GLYPH_MAPPING;
@@ -0,0 +1,41 @@
+#![cfg(all(feature = "size-12", feature = "size-24"))]
+
+use embedded_graphics_cjk_font_zpix::{ZPIX_12, ZPIX_24};
+use embedded_graphics_cjk_glyph_mapping::embedded_graphics::{
+    geometry::Point,
+    mock_display::MockDisplay,
+    mono_font::{MonoFont, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    text::{Baseline, Text},
+    Drawable,
+};
+
+/// Draws text at the top left corner, and returns the display, and the
+/// position after the text.
+fn draw(text: &str, font: &MonoFont) -> (MockDisplay<BinaryColor>, Point) {
+    let style = MonoTextStyle::new(font, BinaryColor::On);
+    let mut display = MockDisplay::new();
+
+    display.set_allow_out_of_bounds_drawing(true);
+
+    let next = Text::with_baseline(text, Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+
+    (display, next)
+}
+
+#[test]
+fn test_draw_ideographs() {
+    for font in [&ZPIX_12, &ZPIX_24] {
+        let (display, next) = draw("中文", font);
+
+        assert_eq!(next, Point::new(2 * font.character_size.width as i32, 0));
+        assert!(!display.affected_area().is_zero_sized());
+
+        // Each character is drawn with its own glyph, not the fallback glyph
+        assert_ne!(draw("中", font).0, draw("文", font).0);
+        assert_ne!(draw("中", font).0, draw("?", font).0);
+        assert_eq!(draw("\u{FFFF}", font).0, draw("?", font).0);
+    }
+}
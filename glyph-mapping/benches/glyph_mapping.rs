@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use embedded_graphics_cjk_glyph_mapping::{
+    embedded_graphics::mono_font::mapping::GlyphMapping, IndexedRangeGlyphMapping,
+    RangeGlyphMapping,
+};
+use std::ops::RangeInclusive;
+
+/// A mix of blocks that a font covering Chinese, Japanese, and Korean text
+/// might include.
+const RANGES: [RangeInclusive<char>; 16] = [
+    '?'..='?',
+    '\u{1100}'..='\u{11FF}', // Hangul Jamo
+    '\u{2000}'..='\u{206F}', // General Punctuation
+    '\u{2E80}'..='\u{2EF3}', // CJK Radicals Supplement
+    '\u{2F00}'..='\u{2FDF}', // Kangxi Radicals
+    '\u{3000}'..='\u{303F}', // CJK Symbols and Punctuation
+    '\u{3040}'..='\u{309F}', // Hiragana
+    '\u{30A0}'..='\u{30FF}', // Katakana
+    '\u{3100}'..='\u{312F}', // Bopomofo
+    '\u{3130}'..='\u{318F}', // Hangul Compatibility Jamo
+    '\u{31F0}'..='\u{31FF}', // Katakana Phonetic Extensions
+    '\u{3400}'..='\u{4DBF}', // CJK Unified Ideographs Extension A
+    '\u{4E00}'..='\u{9FFF}', // CJK Unified Ideographs
+    '\u{AC00}'..='\u{D7AF}', // Hangul Syllables
+    '\u{F900}'..='\u{FAFF}', // CJK Compatibility Ideographs
+    '\u{FF00}'..='\u{FFEF}', // Halfwidth and Fullwidth Forms
+];
+
+const TEXT: &str = "大沼澤地國家公園はフロリダ州にある。대한민국 ｈｅｌｌｏ、「世界」！";
+
+fn index_all<M: GlyphMapping>(map: &M, text: &str) -> usize {
+    text.chars().map(|chr| map.index(black_box(chr))).sum()
+}
+
+fn bench_index(c: &mut Criterion) {
+    let linear = RangeGlyphMapping::new(RANGES, 0);
+    let indexed = IndexedRangeGlyphMapping::new(RANGES, 0);
+
+    let mut group = c.benchmark_group("index");
+
+    group.bench_function("RangeGlyphMapping", |b| b.iter(|| index_all(&linear, TEXT)));
+    group.bench_function("IndexedRangeGlyphMapping", |b| {
+        b.iter(|| index_all(&indexed, TEXT))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_index);
+criterion_main!(benches);
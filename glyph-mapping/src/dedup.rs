@@ -0,0 +1,134 @@
+use crate::TryGlyphMapping;
+use embedded_graphics::mono_font::mapping::GlyphMapping;
+
+/// Wraps another glyph mapping, for fonts where characters with identical
+/// glyphs share a single bitmap.
+///
+/// The wrapped mapping assigns each character a slot, as if every glyph was
+/// stored. `duplicates` lists the slots that reuse an earlier glyph, sorted
+/// by slot, along with the index of that glyph. Every other slot is moved
+/// down by the number of duplicates before it.
+pub struct DedupGlyphMapping<'a, M> {
+    mapping: M,
+    duplicates: &'a [(usize, usize)],
+}
+
+impl<'a, M> DedupGlyphMapping<'a, M> {
+    /// Returns a new `DedupGlyphMapping`.
+    ///
+    /// Duplicates must be sorted by slot, can't contain the same slot twice,
+    /// and have to refer to an earlier glyph. When used to initialize a
+    /// constant, an invalid mapping fails to compile.
+    pub const fn new(mapping: M, duplicates: &'a [(usize, usize)]) -> Self {
+        let mut i = 0;
+
+        while i < duplicates.len() {
+            if i > 0 && duplicates[i].0 <= duplicates[i - 1].0 {
+                panic!("Duplicates must be sorted, and can't contain the same slot twice");
+            }
+
+            if duplicates[i].1 >= duplicates[i].0 - i {
+                panic!("Duplicates have to refer to an earlier glyph");
+            }
+
+            i += 1;
+        }
+
+        Self {
+            mapping,
+            duplicates,
+        }
+    }
+
+    /// Number of slots that reuse an earlier glyph.
+    pub const fn num_duplicates(&self) -> usize {
+        self.duplicates.len()
+    }
+
+    /// Returns the index of the stored glyph for a slot of the wrapped
+    /// mapping.
+    fn glyph_index(&self, slot: usize) -> usize {
+        let before = self
+            .duplicates
+            .partition_point(|(duplicate, _)| *duplicate < slot);
+
+        match self.duplicates.get(before) {
+            Some((duplicate, glyph)) if *duplicate == slot => *glyph,
+            _ => slot - before,
+        }
+    }
+}
+
+impl<M> GlyphMapping for DedupGlyphMapping<'_, M>
+where
+    M: GlyphMapping,
+{
+    fn index(&self, chr: char) -> usize {
+        self.glyph_index(self.mapping.index(chr))
+    }
+}
+
+impl<M> TryGlyphMapping for DedupGlyphMapping<'_, M>
+where
+    M: TryGlyphMapping,
+{
+    fn try_index(&self, chr: char) -> Option<usize> {
+        self.mapping
+            .try_index(chr)
+            .map(|slot| self.glyph_index(slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupGlyphMapping;
+    use crate::{IndexedRangeGlyphMapping, TryGlyphMapping};
+    use embedded_graphics::mono_font::mapping::GlyphMapping;
+
+    #[test]
+    fn test_dedup_shifts_later_glyphs() {
+        // `b`, and `d` look the same as `?`, and `e` looks the same as `c`.
+        let map = DedupGlyphMapping::new(
+            IndexedRangeGlyphMapping::new(['?'..='?', 'a'..='f'], 0),
+            &[(2, 0), (4, 0), (5, 2)],
+        );
+
+        assert_eq!(map.num_duplicates(), 3);
+        assert_eq!(map.index('?'), 0);
+        assert_eq!(map.index('a'), 1);
+        assert_eq!(map.index('b'), 0);
+        assert_eq!(map.index('c'), 2);
+        assert_eq!(map.index('d'), 0);
+        assert_eq!(map.index('e'), 2);
+        assert_eq!(map.index('f'), 3);
+        assert_eq!(map.index('z'), 0);
+        assert_eq!(map.try_index('f'), Some(3));
+        assert_eq!(map.try_index('z'), None);
+    }
+
+    #[test]
+    fn test_dedup_without_duplicates() {
+        let map = DedupGlyphMapping::new(IndexedRangeGlyphMapping::new(['?'..='?'], 0), &[]);
+
+        assert_eq!(map.index('?'), 0);
+        assert_eq!(map.try_index('?'), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted")]
+    fn test_dedup_unsorted() {
+        DedupGlyphMapping::new(
+            IndexedRangeGlyphMapping::new(['?'..='?', 'a'..='f'], 0),
+            &[(4, 0), (2, 0)],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "earlier glyph")]
+    fn test_dedup_later_glyph() {
+        DedupGlyphMapping::new(
+            IndexedRangeGlyphMapping::new(['?'..='?', 'a'..='f'], 0),
+            &[(2, 0), (4, 3)],
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::TryGlyphMapping;
+use core::ops::RangeInclusive;
+use embedded_graphics::mono_font::mapping::GlyphMapping;
+
+/// Maps characters to glyph indices using the same layout as
+/// [`RangeGlyphMapping`](crate::RangeGlyphMapping), but with the index of the
+/// first glyph in each range computed ahead of time.
+///
+/// Ranges are required to be sorted, so looking up a character is a binary
+/// search over the ranges, rather than a linear scan.
+pub struct IndexedRangeGlyphMapping<const SIZE: usize> {
+    ranges: [RangeInclusive<char>; SIZE],
+
+    /// The glyph index of the first character in each range.
+    offsets: [usize; SIZE],
+
+    default_idx: usize,
+}
+
+impl<const SIZE: usize> IndexedRangeGlyphMapping<SIZE> {
+    /// Returns a new `IndexedRangeGlyphMapping`.
+    ///
+    /// Ranges must be non-empty, sorted, and can't overlap. `default_idx` is
+    /// validated to ensure that it references a valid index. When used to
+    /// initialize a constant, an invalid mapping fails to compile.
+    pub const fn new(ranges: [RangeInclusive<char>; SIZE], default_idx: usize) -> Self {
+        let mut offsets = [0; SIZE];
+        let mut index = 0;
+        let mut i = 0;
+
+        while i < SIZE {
+            let start = *ranges[i].start();
+            let end = *ranges[i].end();
+
+            if end < start {
+                panic!("Ranges can't be empty");
+            }
+
+            if i > 0 && start <= *ranges[i - 1].end() {
+                panic!("Ranges must be sorted, and can't overlap");
+            }
+
+            offsets[i] = index;
+            // Add 1, since the range is inclusive
+            index += end as usize - start as usize + 1;
+            i += 1;
+        }
+
+        if default_idx >= index {
+            panic!("Default mapping index exceeded number of characters in the range")
+        }
+
+        Self {
+            ranges,
+            offsets,
+            default_idx,
+        }
+    }
+
+    /// Determines how many characters are covered by all of the ranges
+    /// specified in the `IndexedRangeGlyphMapping`.
+    pub const fn chars_in_range(&self) -> usize {
+        if SIZE == 0 {
+            return 0;
+        }
+
+        let last = &self.ranges[SIZE - 1];
+
+        self.offsets[SIZE - 1] + (*last.end() as usize - *last.start() as usize + 1)
+    }
+}
+
+impl<const SIZE: usize> GlyphMapping for IndexedRangeGlyphMapping<SIZE> {
+    fn index(&self, chr: char) -> usize {
+        self.try_index(chr).unwrap_or(self.default_idx)
+    }
+}
+
+impl<const SIZE: usize> TryGlyphMapping for IndexedRangeGlyphMapping<SIZE> {
+    fn try_index(&self, chr: char) -> Option<usize> {
+        // Number of ranges that start at, or before the character. The
+        // character can only be in the last of those ranges.
+        let candidates = self.ranges.partition_point(|range| *range.start() <= chr);
+
+        match candidates.checked_sub(1) {
+            Some(i) if chr <= *self.ranges[i].end() => {
+                Some(self.offsets[i] + chr as usize - *self.ranges[i].start() as usize)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexedRangeGlyphMapping;
+    use crate::{RangeGlyphMapping, TryGlyphMapping};
+    use embedded_graphics::mono_font::mapping::GlyphMapping;
+
+    #[test]
+    fn test_indexed_range_one_element() {
+        let map = IndexedRangeGlyphMapping::new(['?'..='?'], 0);
+
+        assert_eq!(map.chars_in_range(), 1);
+        assert_eq!(map.index('?'), 0);
+        assert_eq!(map.index('A'), 0);
+    }
+
+    #[test]
+    fn test_indexed_range_cjk_radicals_supplement() {
+        let map = IndexedRangeGlyphMapping::new(['?'..='?', '\u{2E80}'..='\u{2EF3}'], 0);
+
+        assert_eq!(map.chars_in_range(), 1 + 116);
+        assert_eq!(map.index('A'), 0);
+        assert_eq!(map.index('\u{2E89}'), 1 + 9);
+        assert_eq!(map.index('\u{2EBA}'), 1 + 58);
+        assert_eq!(map.index('\u{2EF4}'), 0);
+        assert_eq!(map.try_index('\u{2EF4}'), None);
+        assert_eq!(map.try_index('?'), Some(0));
+    }
+
+    #[test]
+    fn test_indexed_range_matches_range_glyph_mapping() {
+        let ranges = [
+            '?'..='?',
+            '\u{2E80}'..='\u{2EF3}',
+            '\u{3040}'..='\u{309F}',
+            '\u{30A0}'..='\u{30FF}',
+            '\u{4E00}'..='\u{9FFF}',
+            '\u{AC00}'..='\u{D7AF}',
+        ];
+        let indexed = IndexedRangeGlyphMapping::new(ranges.clone(), 0);
+        let linear = RangeGlyphMapping::new(ranges, 0);
+
+        assert_eq!(indexed.chars_in_range(), linear.chars_in_range());
+
+        for chr in ('\u{0}'..='\u{FFFF}').step_by(7) {
+            assert_eq!(indexed.index(chr), linear.index(chr), "{:?}", chr);
+            assert_eq!(indexed.try_index(chr), linear.try_index(chr), "{:?}", chr);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted")]
+    fn test_indexed_range_unsorted() {
+        IndexedRangeGlyphMapping::new(['\u{4E00}'..='\u{9FFF}', '?'..='?'], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Default mapping index")]
+    fn test_indexed_range_invalid_default() {
+        IndexedRangeGlyphMapping::new(['?'..='?'], 1);
+    }
+}
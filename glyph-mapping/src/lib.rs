@@ -1,5 +1,66 @@
-use embedded_graphics::mono_font::mapping::GlyphMapping;
-use std::ops::RangeInclusive;
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+/// The version of embedded-graphics selected with the `embedded-graphics-07`,
+/// or `embedded-graphics-08` feature. Generated fonts import it from here, so
+/// the same source builds with either version.
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+pub extern crate embedded_graphics_07 as embedded_graphics;
+
+mod dedup;
+mod indexed;
+mod sparse;
+
+use core::ops::RangeInclusive;
+use embedded_graphics::{
+    image::ImageRaw, mono_font::mapping::GlyphMapping, pixelcolor::BinaryColor,
+};
+
+pub use dedup::DedupGlyphMapping;
+pub use indexed::IndexedRangeGlyphMapping;
+pub use sparse::SparseGlyphMapping;
+
+/// The version of embedded-graphics selected with the `embedded-graphics-08`
+/// feature.
+#[cfg(feature = "embedded-graphics-08")]
+pub use embedded_graphics;
+
+/// Creates the image of a `MonoFont` in a constant, with either version of
+/// embedded-graphics.
+pub const fn binary_image(data: &[u8], width: u32) -> ImageRaw<'_, BinaryColor> {
+    #[cfg(feature = "embedded-graphics-08")]
+    let image = ImageRaw::new(data, width);
+
+    #[cfg(not(feature = "embedded-graphics-08"))]
+    let image = ImageRaw::new_binary(data, width);
+
+    image
+}
+
+/// A glyph mapping that can report characters it doesn't cover, rather than
+/// mapping them to the default glyph.
+pub trait TryGlyphMapping: GlyphMapping {
+    /// Returns the index of the glyph for a character, or `None` if the
+    /// character isn't covered by the mapping.
+    fn try_index(&self, chr: char) -> Option<usize>;
+
+    /// Returns `true` if the character is covered by the mapping.
+    fn contains(&self, chr: char) -> bool {
+        self.try_index(chr).is_some()
+    }
+}
 
 pub struct RangeGlyphMapping<const SIZE: usize> {
     ranges: [RangeInclusive<char>; SIZE],
@@ -45,22 +106,47 @@ impl<const SIZE: usize> RangeGlyphMapping<SIZE> {
 
 impl<const SIZE: usize> GlyphMapping for RangeGlyphMapping<SIZE> {
     fn index(&self, chr: char) -> usize {
+        self.try_index(chr).unwrap_or(self.default_idx)
+    }
+}
+
+impl<const SIZE: usize> TryGlyphMapping for RangeGlyphMapping<SIZE> {
+    fn try_index(&self, chr: char) -> Option<usize> {
         let mut index = 0;
         for range in &self.ranges {
             if range.contains(&chr) {
-                return index + chr as usize - *range.start() as usize;
+                return Some(index + chr as usize - *range.start() as usize);
             }
 
             index += *range.end() as usize - *range.start() as usize + 1;
         }
-        self.default_idx
+        None
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::RangeGlyphMapping;
-    use embedded_graphics::mono_font::mapping::GlyphMapping;
+    use super::{binary_image, IndexedRangeGlyphMapping, RangeGlyphMapping, TryGlyphMapping};
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{mapping::GlyphMapping, DecorationDimensions, MonoFont, MonoTextStyle},
+        pixelcolor::BinaryColor,
+        text::{Baseline, Text},
+        Drawable,
+    };
+
+    /// A font laid out like a generated one, with a 4x2 box for `a`, and a
+    /// vertical line for `b`.
+    const FONT: MonoFont = MonoFont {
+        image: binary_image(&[0b1111_0100, 0b1001_0100], 8),
+        glyph_mapping: &IndexedRangeGlyphMapping::new(['a'..='b'], 0),
+        character_size: Size::new(4, 2),
+        character_spacing: 0,
+        baseline: 1,
+        underline: DecorationDimensions::new(1, 1),
+        strikethrough: DecorationDimensions::new(0, 1),
+    };
 
     #[test]
     fn test_range_one_element() {
@@ -94,4 +180,30 @@ mod tests {
         assert_eq!(map.index('\u{2E89}'), 1 + 9);
         assert_eq!(map.index('\u{2EBA}'), 1 + 58);
     }
+
+    #[test]
+    fn test_range_try_index() {
+        let map = RangeGlyphMapping::new(['?'..='?', '\u{2E80}'..='\u{2EF3}'], 0);
+
+        assert_eq!(map.try_index('?'), Some(0));
+        assert_eq!(map.try_index('\u{2E89}'), Some(1 + 9));
+        assert_eq!(map.try_index('A'), None);
+        assert!(!map.contains('A'));
+        assert!(map.contains('\u{2EF3}'));
+    }
+
+    #[test]
+    fn test_draw_binary_image() {
+        let style = MonoTextStyle::new(&FONT, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        Text::with_baseline("ab", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(&[
+            "#### #  ", //
+            "#  # #  ", //
+        ]);
+    }
 }
@@ -0,0 +1,104 @@
+use crate::TryGlyphMapping;
+use embedded_graphics::mono_font::mapping::GlyphMapping;
+
+/// Maps an arbitrary, sorted list of characters to glyph indices.
+///
+/// Each character is mapped to its position in the list. This is useful for
+/// fonts that only contain a subset of the characters in a Unicode block,
+/// where a [`RangeGlyphMapping`](crate::RangeGlyphMapping) would have to
+/// include every character in the block.
+pub struct SparseGlyphMapping<'a> {
+    chars: &'a [char],
+    default_idx: usize,
+}
+
+impl<'a> SparseGlyphMapping<'a> {
+    /// Returns a new `SparseGlyphMapping`.
+    ///
+    /// Characters must be sorted, and can't contain duplicates. `default_idx`
+    /// is validated to ensure that it references a valid index. When used to
+    /// initialize a constant, an invalid mapping fails to compile.
+    pub const fn new(chars: &'a [char], default_idx: usize) -> Self {
+        let mut i = 1;
+
+        while i < chars.len() {
+            if chars[i] <= chars[i - 1] {
+                panic!("Characters must be sorted, and can't contain duplicates");
+            }
+
+            i += 1;
+        }
+
+        if default_idx >= chars.len() {
+            panic!("Default mapping index exceeded number of characters in the mapping")
+        }
+
+        Self { chars, default_idx }
+    }
+
+    /// Returns a new `SparseGlyphMapping`, but characters aren't checked to
+    /// ensure they're sorted, and `default_idx` isn't validated.
+    pub const fn new_unchecked(chars: &'a [char], default_idx: usize) -> Self {
+        Self { chars, default_idx }
+    }
+
+    /// Number of characters in the mapping.
+    pub const fn chars_in_range(&self) -> usize {
+        self.chars.len()
+    }
+}
+
+impl GlyphMapping for SparseGlyphMapping<'_> {
+    fn index(&self, chr: char) -> usize {
+        self.try_index(chr).unwrap_or(self.default_idx)
+    }
+}
+
+impl TryGlyphMapping for SparseGlyphMapping<'_> {
+    fn try_index(&self, chr: char) -> Option<usize> {
+        self.chars.binary_search(&chr).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseGlyphMapping;
+    use crate::TryGlyphMapping;
+    use embedded_graphics::mono_font::mapping::GlyphMapping;
+
+    #[test]
+    fn test_sparse_one_element() {
+        let map = SparseGlyphMapping::new(&['?'], 0);
+
+        assert_eq!(map.chars_in_range(), 1);
+        assert_eq!(map.index('?'), 0);
+        assert_eq!(map.index('A'), 0);
+    }
+
+    #[test]
+    fn test_sparse_common_hanzi() {
+        //   0: Question Mark
+        // 1..: A handful of common hanzi
+        let map = SparseGlyphMapping::new(&['?', '一', '人', '国', '大', '的'], 0);
+
+        assert_eq!(map.chars_in_range(), 6);
+        assert_eq!(map.index('一'), 1);
+        assert_eq!(map.index('国'), 3);
+        assert_eq!(map.index('的'), 5);
+        assert_eq!(map.index('丁'), 0);
+        assert_eq!(map.try_index('丁'), None);
+        assert_eq!(map.try_index('国'), Some(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted")]
+    fn test_sparse_duplicates() {
+        SparseGlyphMapping::new(&['?', '一', '一'], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Default mapping index")]
+    fn test_sparse_invalid_default() {
+        SparseGlyphMapping::new(&['?'], 1);
+    }
+}
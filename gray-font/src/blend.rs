@@ -0,0 +1,77 @@
+use embedded_graphics::pixelcolor::{
+    Bgr555, Bgr565, Bgr666, Bgr888, Gray2, Gray4, Gray8, GrayColor, PixelColor, Rgb555, Rgb565,
+    Rgb666, Rgb888, RgbColor,
+};
+
+/// Colors that anti-aliased glyphs can be blended with.
+pub trait BlendColor: PixelColor {
+    /// Mixes the color with `foreground`, where an `alpha` of 0 returns
+    /// `self`, and 255 returns `foreground`.
+    fn blend(self, foreground: Self, alpha: u8) -> Self;
+}
+
+/// Linearly interpolates a single channel.
+fn blend_channel(background: u8, foreground: u8, alpha: u8) -> u8 {
+    let alpha = u16::from(alpha);
+    let value = u16::from(background) * (0xFF - alpha) + u16::from(foreground) * alpha;
+
+    ((value + 0x7F) / 0xFF) as u8
+}
+
+macro_rules! impl_gray {
+    ($($color:ident),*) => {
+        $(
+            impl BlendColor for $color {
+                fn blend(self, foreground: Self, alpha: u8) -> Self {
+                    $color::new(blend_channel(self.luma(), foreground.luma(), alpha))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_rgb {
+    ($($color:ident),*) => {
+        $(
+            impl BlendColor for $color {
+                fn blend(self, foreground: Self, alpha: u8) -> Self {
+                    $color::new(
+                        blend_channel(self.r(), foreground.r(), alpha),
+                        blend_channel(self.g(), foreground.g(), alpha),
+                        blend_channel(self.b(), foreground.b(), alpha),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_gray!(Gray2, Gray4, Gray8);
+impl_rgb!(Rgb555, Bgr555, Rgb565, Bgr565, Rgb666, Bgr666, Rgb888, Bgr888);
+
+#[cfg(test)]
+mod tests {
+    use super::BlendColor;
+    use embedded_graphics::pixelcolor::{Gray4, Gray8, GrayColor, Rgb565, Rgb888, RgbColor};
+
+    #[test]
+    fn test_blend_gray() {
+        assert_eq!(Gray8::BLACK.blend(Gray8::WHITE, 0), Gray8::BLACK);
+        assert_eq!(Gray8::BLACK.blend(Gray8::WHITE, 0xFF), Gray8::WHITE);
+        assert_eq!(Gray8::BLACK.blend(Gray8::WHITE, 0x80), Gray8::new(0x80));
+        assert_eq!(Gray4::WHITE.blend(Gray4::BLACK, 0x80), Gray4::new(0x07));
+    }
+
+    #[test]
+    fn test_blend_rgb() {
+        assert_eq!(
+            Rgb888::new(0x00, 0x40, 0xFF).blend(Rgb888::new(0xFF, 0x40, 0x00), 0x80),
+            Rgb888::new(0x80, 0x40, 0x7F)
+        );
+        assert_eq!(Rgb565::BLACK.blend(Rgb565::RED, 0xFF), Rgb565::RED);
+        assert_eq!(
+            Rgb565::BLACK.blend(Rgb565::WHITE, 0x80),
+            Rgb565::new(16, 32, 16)
+        );
+    }
+}
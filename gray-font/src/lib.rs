@@ -0,0 +1,113 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod blend;
+mod style;
+
+use embedded_graphics::{
+    geometry::{Point, Size},
+    mono_font::mapping::GlyphMapping,
+};
+
+pub use blend::BlendColor;
+pub use style::GrayTextStyle;
+
+/// An anti-aliased, monospaced font.
+///
+/// Glyphs are stored like the image of a
+/// [`MonoFont`](embedded_graphics::mono_font::MonoFont), but each pixel is a
+/// 2, 4, or 8 bit coverage value, rather than a single bit. A coverage of 0 is
+/// fully transparent, and the maximum value is fully covered by the glyph.
+pub struct GrayFont<'a> {
+    /// Glyph coverage data, packed with the first pixel in the most
+    /// significant bits of each byte.
+    pub data: &'a [u8],
+
+    /// Width of the image containing every glyph, in pixels.
+    pub image_width: u32,
+
+    /// Number of bits used for each pixel (2, 4, or 8).
+    pub bits_per_pixel: u8,
+
+    /// Glyph mapping.
+    pub glyph_mapping: &'a dyn GlyphMapping,
+
+    /// Size of a single character in pixel.
+    pub character_size: Size,
+
+    /// Spacing between characters.
+    pub character_spacing: u32,
+
+    /// The baseline, as an offset from the top of a glyph.
+    pub baseline: u32,
+}
+
+impl GrayFont<'_> {
+    /// Returns the coverage of a pixel in the glyph for a character, scaled
+    /// to `0..=255`. `point` is relative to the top left corner of the glyph.
+    pub fn coverage(&self, chr: char, point: Point) -> u8 {
+        let glyphs_per_row = self.image_width / self.character_size.width.max(1);
+        let index = self.glyph_mapping.index(chr) as u32;
+        let x = (index % glyphs_per_row) * self.character_size.width + point.x as u32;
+        let y = (index / glyphs_per_row) * self.character_size.height + point.y as u32;
+
+        let bpp = u32::from(self.bits_per_pixel);
+        let offset = (y * self.image_width + x) * bpp;
+        let byte = match self.data.get((offset / 8) as usize) {
+            Some(byte) => *byte,
+            None => return 0,
+        };
+        let max = (1u16 << bpp) - 1;
+        let value = u16::from(byte >> (8 - bpp - offset % 8)) & max;
+
+        (value * 0xFF / max) as u8
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::GrayFont;
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mono_font::mapping::StrGlyphMapping,
+    };
+
+    /// A 4 bpp font with 2x2 glyphs for `a`, and `b`. `a` fades from
+    /// transparent to fully covered, and `b` is fully covered.
+    pub(crate) const FONT: GrayFont = GrayFont {
+        data: &[0x05, 0xFF, 0xAF, 0xFF],
+        image_width: 4,
+        bits_per_pixel: 4,
+        glyph_mapping: &StrGlyphMapping::new("ab", 0),
+        character_size: Size::new(2, 2),
+        character_spacing: 0,
+        baseline: 1,
+    };
+
+    #[test]
+    fn test_coverage() {
+        assert_eq!(FONT.coverage('a', Point::new(0, 0)), 0x00);
+        assert_eq!(FONT.coverage('a', Point::new(1, 0)), 0x55);
+        assert_eq!(FONT.coverage('a', Point::new(0, 1)), 0xAA);
+        assert_eq!(FONT.coverage('a', Point::new(1, 1)), 0xFF);
+        assert_eq!(FONT.coverage('b', Point::new(1, 1)), 0xFF);
+
+        // Characters that aren't in the font use the default glyph
+        assert_eq!(FONT.coverage('c', Point::new(1, 0)), 0x55);
+    }
+}
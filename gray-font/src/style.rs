@@ -0,0 +1,268 @@
+use crate::{blend::BlendColor, GrayFont};
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline,
+    },
+    Pixel,
+};
+
+/// Coverage at which a pixel is considered part of the glyph, when there's
+/// no color to blend it with.
+const COVERAGE_THRESHOLD: u8 = 0x80;
+
+/// Style for text drawn with a [`GrayFont`].
+///
+/// Anti-aliasing needs both a text, and a background color, since a
+/// `DrawTarget` can't be read back to blend with. Without a background
+/// color, pixels that are at least half covered are drawn with the text
+/// color, and everything else is left transparent.
+#[derive(Copy, Clone)]
+pub struct GrayTextStyle<'a, C> {
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Font.
+    pub font: &'a GrayFont<'a>,
+}
+
+impl<'a, C> GrayTextStyle<'a, C>
+where
+    C: BlendColor,
+{
+    /// Creates a text style with a transparent background.
+    pub const fn new(font: &'a GrayFont<'a>, text_color: C) -> Self {
+        GrayTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            font,
+        }
+    }
+
+    /// Creates a text style that blends glyphs onto a solid background.
+    pub const fn with_background(
+        font: &'a GrayFont<'a>,
+        text_color: C,
+        background_color: C,
+    ) -> Self {
+        GrayTextStyle {
+            text_color: Some(text_color),
+            background_color: Some(background_color),
+            font,
+        }
+    }
+
+    /// Width of a character, including the spacing after it.
+    fn advance(&self) -> u32 {
+        self.font.character_size.width + self.font.character_spacing
+    }
+
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => self.font.character_size.height.saturating_sub(1) as i32,
+            Baseline::Middle => (self.font.character_size.height.saturating_sub(1) / 2) as i32,
+            Baseline::Alphabetic => self.font.baseline as i32,
+        }
+    }
+
+    /// Returns the color of a pixel, given how much of it is covered by the
+    /// glyph.
+    fn pixel_color(&self, coverage: u8) -> Option<C> {
+        match (self.text_color, self.background_color) {
+            (Some(text_color), Some(background_color)) => {
+                Some(background_color.blend(text_color, coverage))
+            }
+            (Some(text_color), None) if coverage >= COVERAGE_THRESHOLD => Some(text_color),
+            (None, Some(background_color)) if coverage < COVERAGE_THRESHOLD => {
+                Some(background_color)
+            }
+            _ => None,
+        }
+    }
+
+    fn draw_glyph<D>(&self, chr: char, position: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let Size { width, height } = self.font.character_size;
+        let pixels = (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| Point::new(x, y)))
+            .filter_map(|point| {
+                self.pixel_color(self.font.coverage(chr, point))
+                    .map(|color| Pixel(position + point, color))
+            });
+
+        target.draw_iter(pixels)
+    }
+}
+
+impl<C> TextRenderer for GrayTextStyle<'_, C>
+where
+    C: BlendColor,
+{
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let mut position = position - Point::new(0, self.baseline_offset(baseline));
+
+        for chr in text.chars() {
+            if self.text_color.is_some() || self.background_color.is_some() {
+                self.draw_glyph(chr, position, target)?;
+
+                if let (Some(background_color), true) =
+                    (self.background_color, self.font.character_spacing > 0)
+                {
+                    target.fill_solid(
+                        &Rectangle::new(
+                            position + Size::new(self.font.character_size.width, 0),
+                            Size::new(self.font.character_spacing, self.font.character_size.height),
+                        ),
+                        background_color,
+                    )?;
+                }
+            }
+
+            position += Size::new(self.advance(), 0);
+        }
+
+        Ok(position + Point::new(0, self.baseline_offset(baseline)))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let position = position - Point::new(0, self.baseline_offset(baseline));
+
+        if let (Some(background_color), true) = (self.background_color, width > 0) {
+            target.fill_solid(
+                &Rectangle::new(position, Size::new(width, self.font.character_size.height)),
+                background_color,
+            )?;
+        }
+
+        Ok(position + Point::new(width as i32, self.baseline_offset(baseline)))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let bb_position = position - Point::new(0, self.baseline_offset(baseline));
+        let bb_width = (text.chars().count() as u32 * self.advance())
+            .saturating_sub(self.font.character_spacing);
+        let bb_size = Size::new(bb_width, self.font.character_size.height);
+
+        TextMetrics {
+            bounding_box: Rectangle::new(bb_position, bb_size),
+            next_position: position + bb_size.x_axis(),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.character_size.height
+    }
+}
+
+impl<C> CharacterStyle for GrayTextStyle<'_, C>
+where
+    C: BlendColor,
+{
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GrayTextStyle;
+    use crate::tests::FONT;
+    use embedded_graphics::{
+        geometry::Point,
+        mock_display::MockDisplay,
+        pixelcolor::{Gray8, GrayColor, Rgb888, RgbColor},
+        text::{renderer::TextRenderer, Baseline, Text},
+        Drawable,
+    };
+
+    #[test]
+    fn test_blend_with_background() {
+        let style = GrayTextStyle::with_background(&FONT, Gray8::WHITE, Gray8::BLACK);
+        let mut display = MockDisplay::new();
+
+        let next = Text::with_baseline("ab", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(4, 0));
+        assert_eq!(display.get_pixel(Point::new(0, 0)), Some(Gray8::BLACK));
+        assert_eq!(display.get_pixel(Point::new(1, 0)), Some(Gray8::new(0x55)));
+        assert_eq!(display.get_pixel(Point::new(0, 1)), Some(Gray8::new(0xAA)));
+        assert_eq!(display.get_pixel(Point::new(1, 1)), Some(Gray8::WHITE));
+        assert_eq!(display.get_pixel(Point::new(2, 0)), Some(Gray8::WHITE));
+    }
+
+    #[test]
+    fn test_blend_rgb() {
+        let style = GrayTextStyle::with_background(&FONT, Rgb888::RED, Rgb888::BLUE);
+        let mut display = MockDisplay::new();
+
+        Text::with_baseline("a", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display.get_pixel(Point::new(0, 1)),
+            Some(Rgb888::new(0xAA, 0x00, 0x55))
+        );
+    }
+
+    #[test]
+    fn test_transparent_background() {
+        let style = GrayTextStyle::new(&FONT, Gray8::WHITE);
+        let mut display = MockDisplay::new();
+
+        Text::with_baseline("a", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(display.get_pixel(Point::new(0, 0)), None);
+        assert_eq!(display.get_pixel(Point::new(1, 0)), None);
+        assert_eq!(display.get_pixel(Point::new(0, 1)), Some(Gray8::WHITE));
+        assert_eq!(display.get_pixel(Point::new(1, 1)), Some(Gray8::WHITE));
+    }
+
+    #[test]
+    fn test_measure_string() {
+        let style = GrayTextStyle::new(&FONT, Gray8::WHITE);
+        let metrics = style.measure_string("一二三", Point::new(0, 1), Baseline::Alphabetic);
+
+        assert_eq!(metrics.next_position, Point::new(6, 1));
+        assert_eq!(metrics.bounding_box.top_left, Point::zero());
+    }
+}
@@ -0,0 +1,446 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    mono_font::{DecorationDimensions, MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::PixelColor,
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+use unicode_width::UnicodeWidthChar;
+
+/// Text style that draws CJK characters with a full-width font, and
+/// everything else with a half-width font.
+///
+/// Characters are full-width if their Unicode East Asian Width is Wide, or
+/// Fullwidth. Both fonts are drawn on a shared baseline, so fonts with
+/// different cell heights line up. Underlines, and strikethroughs use the
+/// dimensions of the full-width font, so they're continuous across both.
+#[derive(Copy, Clone)]
+pub struct MixedWidthTextStyle<'a, C> {
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Underline color.
+    pub underline_color: DecorationColor<C>,
+
+    /// Strikethrough color.
+    pub strikethrough_color: DecorationColor<C>,
+
+    /// Font used for characters that aren't full-width.
+    pub half_width_font: &'a MonoFont<'a>,
+
+    /// Font used for full-width characters.
+    pub full_width_font: &'a MonoFont<'a>,
+
+    /// Treat characters with an ambiguous East Asian Width (for example `°`,
+    /// or Greek, and Cyrillic letters) as full-width. This matches how legacy
+    /// CJK encodings display them.
+    pub ambiguous_is_full_width: bool,
+}
+
+/// Vertical layout shared by both fonts.
+struct LineMetrics {
+    /// Distance from the top of the line to the baseline.
+    baseline: u32,
+
+    /// Height of the line, which fits both fonts.
+    height: u32,
+}
+
+impl<'a, C> MixedWidthTextStyle<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a text style with a transparent background.
+    pub fn new(
+        half_width_font: &'a MonoFont<'a>,
+        full_width_font: &'a MonoFont<'a>,
+        text_color: C,
+    ) -> Self {
+        MixedWidthTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            underline_color: DecorationColor::None,
+            strikethrough_color: DecorationColor::None,
+            half_width_font,
+            full_width_font,
+            ambiguous_is_full_width: false,
+        }
+    }
+
+    /// Returns `true` if the character is drawn with the full-width font.
+    pub fn is_full_width(&self, chr: char) -> bool {
+        let width = if self.ambiguous_is_full_width {
+            chr.width_cjk()
+        } else {
+            chr.width()
+        };
+
+        width == Some(2)
+    }
+
+    /// Returns the font that's used to draw a character.
+    pub fn font(&self, chr: char) -> &'a MonoFont<'a> {
+        if self.is_full_width(chr) {
+            self.full_width_font
+        } else {
+            self.half_width_font
+        }
+    }
+
+    fn line_metrics(&self) -> LineMetrics {
+        let half = self.half_width_font;
+        let full = self.full_width_font;
+        let baseline = half.baseline.max(full.baseline);
+        let descent = (half.character_size.height - half.baseline)
+            .max(full.character_size.height - full.baseline);
+
+        LineMetrics {
+            baseline,
+            height: baseline + descent,
+        }
+    }
+
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        let metrics = self.line_metrics();
+
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => metrics.height.saturating_sub(1) as i32,
+            Baseline::Middle => (metrics.height.saturating_sub(1) / 2) as i32,
+            Baseline::Alphabetic => metrics.baseline as i32,
+        }
+    }
+
+    /// Horizontal distance from the start of one character to the next.
+    fn advance(&self, chr: char) -> u32 {
+        let font = self.font(chr);
+
+        font.character_size.width + font.character_spacing
+    }
+
+    /// Width of a string, excluding the spacing after the last character.
+    fn string_width(&self, text: &str) -> u32 {
+        let width: u32 = text.chars().map(|chr| self.advance(chr)).sum();
+        let trailing_spacing = text
+            .chars()
+            .last()
+            .map(|chr| self.font(chr).character_spacing)
+            .unwrap_or_default();
+
+        width - trailing_spacing
+    }
+
+    /// Returns a style that draws a run of characters with one of the fonts.
+    /// Decorations are drawn separately, across the whole string.
+    fn run_style(&self, font: &'a MonoFont<'a>) -> MonoTextStyle<'a, C> {
+        let mut style = MonoTextStyleBuilder::new().font(font).build();
+
+        style.set_text_color(self.text_color);
+        style.set_background_color(self.background_color);
+
+        style
+    }
+
+    /// Draws an underline, or strikethrough, positioned relative to the
+    /// full-width font's baseline.
+    fn draw_decoration<D>(
+        &self,
+        decoration: &DecorationDimensions,
+        color: DecorationColor<C>,
+        width: u32,
+        baseline_position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let color = match color {
+            DecorationColor::None => None,
+            DecorationColor::TextColor => self.text_color,
+            DecorationColor::Custom(color) => Some(color),
+        };
+
+        match color {
+            Some(color) => {
+                let top = decoration.offset as i32 - self.full_width_font.baseline as i32;
+
+                target.fill_solid(
+                    &Rectangle::new(
+                        baseline_position + Point::new(0, top),
+                        Size::new(width, decoration.height),
+                    ),
+                    color,
+                )
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn draw_decorations<D>(
+        &self,
+        width: u32,
+        baseline_position: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if width == 0 {
+            return Ok(());
+        }
+
+        self.draw_decoration(
+            &self.full_width_font.strikethrough,
+            self.strikethrough_color,
+            width,
+            baseline_position,
+            target,
+        )?;
+        self.draw_decoration(
+            &self.full_width_font.underline,
+            self.underline_color,
+            width,
+            baseline_position,
+            target,
+        )
+    }
+}
+
+impl<C> TextRenderer for MixedWidthTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let metrics = self.line_metrics();
+        let line_top = position - Point::new(0, self.baseline_offset(baseline));
+        let start = line_top + Point::new(0, metrics.baseline as i32);
+        let width = self.string_width(text);
+
+        // Fonts with a shorter cell than the line leave gaps above, and below
+        // their glyphs.
+        if let Some(background_color) = self.background_color {
+            target.fill_solid(
+                &Rectangle::new(line_top, Size::new(width, metrics.height)),
+                background_color,
+            )?;
+        }
+
+        let mut next = start;
+        let mut rest = text;
+
+        // Draws runs of characters that use the same font.
+        while let Some(first) = rest.chars().next() {
+            let full_width = self.is_full_width(first);
+            let run_len = rest
+                .char_indices()
+                .find(|(_, chr)| self.is_full_width(*chr) != full_width)
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len());
+            let (run, remaining) = rest.split_at(run_len);
+            let style = self.run_style(self.font(first));
+
+            style.draw_string(run, next, Baseline::Alphabetic, target)?;
+
+            next += Point::new(
+                run.chars().map(|chr| self.advance(chr)).sum::<u32>() as i32,
+                0,
+            );
+            rest = remaining;
+        }
+
+        self.draw_decorations(width, start, target)?;
+
+        Ok(next + position - start)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let metrics = self.line_metrics();
+        let line_top = position - Point::new(0, self.baseline_offset(baseline));
+
+        if width != 0 {
+            if let Some(background_color) = self.background_color {
+                target.fill_solid(
+                    &Rectangle::new(line_top, Size::new(width, metrics.height)),
+                    background_color,
+                )?;
+            }
+
+            self.draw_decorations(
+                width,
+                line_top + Point::new(0, metrics.baseline as i32),
+                target,
+            )?;
+        }
+
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let bb_position = position - Point::new(0, self.baseline_offset(baseline));
+        let bb_size = Size::new(self.string_width(text), self.line_metrics().height);
+
+        TextMetrics {
+            bounding_box: Rectangle::new(bb_position, bb_size),
+            next_position: position
+                + Point::new(text.chars().map(|c| self.advance(c)).sum::<u32>() as i32, 0),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.line_metrics().height
+    }
+}
+
+impl<C> CharacterStyle for MixedWidthTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.underline_color = underline_color;
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.strikethrough_color = strikethrough_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MixedWidthTextStyle;
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X10, mapping::StrGlyphMapping, DecorationDimensions, MonoFont},
+        pixelcolor::BinaryColor,
+        text::{renderer::TextRenderer, Baseline, Text},
+        Drawable,
+    };
+    use embedded_graphics_cjk_glyph_mapping::binary_image;
+
+    /// A full-width font with a single, filled 8x12 glyph for `中`. The
+    /// baseline is one row lower than `FONT_6X10`'s.
+    const FULL_WIDTH: MonoFont = MonoFont {
+        image: binary_image(&[0xFF; 12], 8),
+        glyph_mapping: &StrGlyphMapping::new("中", 0),
+        character_size: Size::new(8, 12),
+        character_spacing: 0,
+        baseline: 8,
+        underline: DecorationDimensions::new(10, 1),
+        strikethrough: DecorationDimensions::new(5, 1),
+    };
+
+    #[test]
+    fn test_is_full_width() {
+        let mut style = MixedWidthTextStyle::new(&FONT_6X10, &FULL_WIDTH, BinaryColor::On);
+
+        assert!(style.is_full_width('中'));
+        assert!(style.is_full_width('あ'));
+        assert!(style.is_full_width('한'));
+        assert!(style.is_full_width('Ａ'));
+        assert!(style.is_full_width('。'));
+        assert!(!style.is_full_width('A'));
+        assert!(!style.is_full_width('ｱ'));
+        assert!(!style.is_full_width('°'));
+
+        style.ambiguous_is_full_width = true;
+
+        assert!(style.is_full_width('°'));
+    }
+
+    #[test]
+    fn test_measure_mixed_string() {
+        let style = MixedWidthTextStyle::new(&FONT_6X10, &FULL_WIDTH, BinaryColor::On);
+        let metrics = style.measure_string("A中B", Point::new(0, 8), Baseline::Alphabetic);
+
+        assert_eq!(metrics.next_position, Point::new(6 + 8 + 6, 8));
+        assert_eq!(metrics.bounding_box.top_left, Point::zero());
+        // 8 rows above the baseline, and 4 below it (from the full-width font)
+        assert_eq!(metrics.bounding_box.size, Size::new(20, 12));
+        assert_eq!(style.line_height(), 12);
+    }
+
+    #[test]
+    fn test_shared_baseline() {
+        let style = MixedWidthTextStyle::new(&FONT_6X10, &FULL_WIDTH, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        let next = Text::with_baseline("中I", Point::new(0, 8), style, Baseline::Alphabetic)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(14, 8));
+
+        // The full-width glyph covers rows 0..12, and the bottom of `I` sits
+        // on the same baseline (row 8), even though `FONT_6X10`'s baseline is
+        // at row 7 of its cell.
+        display.assert_pattern(&[
+            "########    ",
+            "########    ",
+            "######## ###",
+            "########  # ",
+            "########  # ",
+            "########  # ",
+            "########  # ",
+            "########  # ",
+            "######## ###",
+            "########    ",
+            "########    ",
+            "########    ",
+        ]);
+    }
+}
@@ -0,0 +1,160 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod style;
+
+use embedded_graphics::mono_font::{mapping::GlyphMapping, DecorationDimensions};
+
+pub use style::ProportionalTextStyle;
+
+/// A font where every glyph has its own advance, and bounding box.
+///
+/// Each glyph's bitmap only covers its bounding box. Bitmaps are stored one
+/// after another, with one bit for each pixel, and rows that aren't padded to
+/// a whole byte. Every bitmap starts on a new byte.
+pub struct ProportionalFont<'a> {
+    /// Glyph bitmaps, packed with the first pixel in the most significant bit
+    /// of each byte.
+    pub data: &'a [u8],
+
+    /// Metrics for each glyph, in glyph index order.
+    pub glyphs: &'a [Glyph],
+
+    /// Glyph mapping.
+    pub glyph_mapping: &'a dyn GlyphMapping,
+
+    /// Height of a line of text.
+    pub line_height: u32,
+
+    /// The baseline, as an offset from the top of a line.
+    pub baseline: u32,
+
+    /// Offset from top of a line to the top of the underline, and its
+    /// height.
+    pub underline: DecorationDimensions,
+
+    /// Offset from top of a line to the top of the strikethrough, and its
+    /// height.
+    pub strikethrough: DecorationDimensions,
+}
+
+/// Position, and size of a single glyph.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Glyph {
+    /// Offset of the glyph's bitmap in [`ProportionalFont::data`], in bytes.
+    pub offset: u32,
+
+    /// Width of the bitmap.
+    pub width: u8,
+
+    /// Height of the bitmap.
+    pub height: u8,
+
+    /// Horizontal distance from the pen position to the left edge of the
+    /// bitmap.
+    pub left: i8,
+
+    /// Vertical distance from the baseline up to the top edge of the bitmap.
+    /// The bottom of a glyph that sits on the baseline is on the row above
+    /// the baseline offset, the same as with a `MonoFont`.
+    pub top: i8,
+
+    /// Horizontal distance the pen moves after drawing the glyph.
+    pub advance: u8,
+}
+
+impl Glyph {
+    /// Creates a glyph.
+    pub const fn new(offset: u32, width: u8, height: u8, left: i8, top: i8, advance: u8) -> Self {
+        Glyph {
+            offset,
+            width,
+            height,
+            left,
+            top,
+            advance,
+        }
+    }
+
+    /// Returns `true` if the pixel at `(x, y)`, relative to the top left
+    /// corner of the bitmap, is part of the glyph.
+    pub fn pixel(&self, data: &[u8], x: u32, y: u32) -> bool {
+        if x >= u32::from(self.width) || y >= u32::from(self.height) {
+            return false;
+        }
+
+        let bit = self.offset as usize * 8 + (y * u32::from(self.width) + x) as usize;
+
+        match data.get(bit / 8) {
+            Some(byte) => byte & (0x80 >> (bit % 8)) != 0,
+            None => false,
+        }
+    }
+}
+
+impl ProportionalFont<'_> {
+    /// Returns the glyph for a character. Characters without a glyph are
+    /// drawn as empty space.
+    pub fn glyph(&self, chr: char) -> Glyph {
+        self.glyphs
+            .get(self.glyph_mapping.index(chr))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Glyph, ProportionalFont};
+    use embedded_graphics::mono_font::{mapping::StrGlyphMapping, DecorationDimensions};
+
+    /// A font with a 1x3 `i`, a 3x2 `o`, and a 2x2 `,` that hangs below the
+    /// baseline. The bitmaps are packed as `111`, `101111`, and `0110`.
+    pub(crate) const FONT: ProportionalFont = ProportionalFont {
+        data: &[0b1110_0000, 0b1011_1100, 0b0110_0000],
+        glyphs: &[
+            Glyph::new(0, 1, 3, 1, 3, 3),
+            Glyph::new(1, 3, 2, 0, 2, 4),
+            Glyph::new(2, 2, 2, 0, 1, 2),
+        ],
+        glyph_mapping: &StrGlyphMapping::new("io,", 0),
+        line_height: 5,
+        baseline: 3,
+        underline: DecorationDimensions::new(4, 1),
+        strikethrough: DecorationDimensions::new(2, 1),
+    };
+
+    #[test]
+    fn test_glyph() {
+        assert_eq!(FONT.glyph('o'), Glyph::new(1, 3, 2, 0, 2, 4));
+
+        // Characters that aren't in the font use the default glyph
+        assert_eq!(FONT.glyph('x'), FONT.glyph('i'));
+    }
+
+    #[test]
+    fn test_pixel() {
+        let o = FONT.glyph('o');
+
+        assert!(o.pixel(FONT.data, 0, 0));
+        assert!(!o.pixel(FONT.data, 1, 0));
+        assert!(o.pixel(FONT.data, 1, 1));
+        assert!(!o.pixel(FONT.data, 3, 0));
+        assert!(!o.pixel(FONT.data, 0, 2));
+    }
+}
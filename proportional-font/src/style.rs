@@ -0,0 +1,313 @@
+use crate::{Glyph, ProportionalFont};
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    mono_font::DecorationDimensions,
+    pixelcolor::PixelColor,
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+    Pixel,
+};
+
+/// Style for text drawn with a [`ProportionalFont`].
+#[derive(Copy, Clone)]
+pub struct ProportionalTextStyle<'a, C> {
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Underline color.
+    pub underline_color: DecorationColor<C>,
+
+    /// Strikethrough color.
+    pub strikethrough_color: DecorationColor<C>,
+
+    /// Font.
+    pub font: &'a ProportionalFont<'a>,
+}
+
+impl<'a, C> ProportionalTextStyle<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a text style with a transparent background.
+    pub const fn new(font: &'a ProportionalFont<'a>, text_color: C) -> Self {
+        ProportionalTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            underline_color: DecorationColor::None,
+            strikethrough_color: DecorationColor::None,
+            font,
+        }
+    }
+
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => self.font.line_height.saturating_sub(1) as i32,
+            Baseline::Middle => (self.font.line_height.saturating_sub(1) / 2) as i32,
+            Baseline::Alphabetic => self.font.baseline as i32,
+        }
+    }
+
+    /// Sum of the advances of every character in the string.
+    fn string_width(&self, text: &str) -> u32 {
+        text.chars()
+            .map(|chr| u32::from(self.font.glyph(chr).advance))
+            .sum()
+    }
+
+    /// Draws a glyph, with `line_top` being the pen position moved up to the
+    /// top of the line.
+    fn draw_glyph<D>(
+        &self,
+        glyph: Glyph,
+        line_top: Point,
+        text_color: C,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let top_left = line_top
+            + Point::new(
+                i32::from(glyph.left),
+                self.font.baseline as i32 + 1 - i32::from(glyph.top),
+            );
+        let data = self.font.data;
+        let pixels = (0..u32::from(glyph.height))
+            .flat_map(|y| (0..u32::from(glyph.width)).map(move |x| (x, y)))
+            .filter(|(x, y)| glyph.pixel(data, *x, *y))
+            .map(|(x, y)| Pixel(top_left + Point::new(x as i32, y as i32), text_color));
+
+        target.draw_iter(pixels)
+    }
+
+    fn draw_decoration<D>(
+        &self,
+        decoration: &DecorationDimensions,
+        color: DecorationColor<C>,
+        width: u32,
+        line_top: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let color = match color {
+            DecorationColor::None => None,
+            DecorationColor::TextColor => self.text_color,
+            DecorationColor::Custom(color) => Some(color),
+        };
+
+        match color {
+            Some(color) => target.fill_solid(
+                &Rectangle::new(
+                    line_top + Point::new(0, decoration.offset as i32),
+                    Size::new(width, decoration.height),
+                ),
+                color,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn draw_decorations<D>(
+        &self,
+        width: u32,
+        line_top: Point,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if width == 0 {
+            return Ok(());
+        }
+
+        self.draw_decoration(
+            &self.font.strikethrough,
+            self.strikethrough_color,
+            width,
+            line_top,
+            target,
+        )?;
+        self.draw_decoration(
+            &self.font.underline,
+            self.underline_color,
+            width,
+            line_top,
+            target,
+        )
+    }
+}
+
+impl<C> TextRenderer for ProportionalTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let line_top = position - Point::new(0, self.baseline_offset(baseline));
+        let mut pen = line_top;
+
+        for chr in text.chars() {
+            let glyph = self.font.glyph(chr);
+
+            if let Some(background_color) = self.background_color {
+                target.fill_solid(
+                    &Rectangle::new(
+                        pen,
+                        Size::new(u32::from(glyph.advance), self.font.line_height),
+                    ),
+                    background_color,
+                )?;
+            }
+
+            if let Some(text_color) = self.text_color {
+                self.draw_glyph(glyph, pen, text_color, target)?;
+            }
+
+            pen += Point::new(i32::from(glyph.advance), 0);
+        }
+
+        self.draw_decorations((pen.x - line_top.x) as u32, line_top, target)?;
+
+        Ok(Point::new(pen.x, position.y))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let line_top = position - Point::new(0, self.baseline_offset(baseline));
+
+        if let (Some(background_color), true) = (self.background_color, width > 0) {
+            target.fill_solid(
+                &Rectangle::new(line_top, Size::new(width, self.font.line_height)),
+                background_color,
+            )?;
+        }
+
+        self.draw_decorations(width, line_top, target)?;
+
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let bb_position = position - Point::new(0, self.baseline_offset(baseline));
+        let bb_size = Size::new(self.string_width(text), self.font.line_height);
+
+        TextMetrics {
+            bounding_box: Rectangle::new(bb_position, bb_size),
+            next_position: position + bb_size.x_axis(),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.line_height
+    }
+}
+
+impl<C> CharacterStyle for ProportionalTextStyle<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.underline_color = underline_color;
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.strikethrough_color = strikethrough_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProportionalTextStyle;
+    use crate::tests::FONT;
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        pixelcolor::BinaryColor,
+        text::{renderer::TextRenderer, Baseline, DecorationColor, Text},
+        Drawable,
+    };
+
+    #[test]
+    fn test_draw_string() {
+        let style = ProportionalTextStyle::new(&FONT, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        let next = Text::with_baseline("io,i", Point::new(0, 3), style, Baseline::Alphabetic)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(3 + 4 + 2 + 3, 3));
+        display.assert_pattern(&[
+            "            ",
+            " #        # ",
+            " # # #    # ",
+            " # ###  # # ",
+            "       #    ",
+        ]);
+    }
+
+    #[test]
+    fn test_background_and_underline() {
+        let mut style = ProportionalTextStyle::new(&FONT, BinaryColor::On);
+        style.background_color = Some(BinaryColor::Off);
+        style.underline_color = DecorationColor::TextColor;
+
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        Text::with_baseline("oi", Point::zero(), style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(&[".......", ".....#.", "#.#..#.", "###..#.", "#######"]);
+    }
+
+    #[test]
+    fn test_measure_string() {
+        let style = ProportionalTextStyle::new(&FONT, BinaryColor::On);
+        let metrics = style.measure_string("oi,", Point::new(0, 3), Baseline::Alphabetic);
+
+        assert_eq!(metrics.next_position, Point::new(9, 3));
+        assert_eq!(metrics.bounding_box.top_left, Point::zero());
+        assert_eq!(metrics.bounding_box.size, Size::new(9, 5));
+        assert_eq!(style.line_height(), 5);
+    }
+}
@@ -0,0 +1,393 @@
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod markup;
+
+pub use markup::{Markup, Segment};
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Point,
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::PixelColor,
+    text::{renderer::CharacterStyle, Baseline, Text},
+    Drawable,
+};
+
+/// Style of text with ruby annotations.
+#[derive(Copy, Clone)]
+pub struct RubyTextStyle<'a, C> {
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Font used for the base text.
+    pub base_font: &'a MonoFont<'a>,
+
+    /// Font used for the annotations, usually one with about half the
+    /// height of the base font, like a Latin font for pinyin.
+    pub ruby_font: &'a MonoFont<'a>,
+
+    /// Space between the annotations, and the base text, in pixels.
+    pub ruby_spacing: u32,
+}
+
+impl<'a, C> RubyTextStyle<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a text style with a transparent background.
+    pub fn new(base_font: &'a MonoFont<'a>, ruby_font: &'a MonoFont<'a>, text_color: C) -> Self {
+        RubyTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            base_font,
+            ruby_font,
+            ruby_spacing: 0,
+        }
+    }
+
+    /// Height of a line, with the annotations, and the base text.
+    pub fn line_height(&self) -> u32 {
+        self.ruby_height() + self.base_font.character_size.height
+    }
+
+    /// Distance from the top of a line to the top of the base text.
+    fn ruby_height(&self) -> u32 {
+        self.ruby_font.character_size.height + self.ruby_spacing
+    }
+
+    /// Returns the width of a segment, which is the advance of its base
+    /// text, or the width of its annotation, if that is wider.
+    pub fn segment_width(&self, segment: &Segment<'_>) -> u32 {
+        let base = self.base_advance(segment.base);
+        let ruby = segment.ruby.map_or(0, |ruby| self.ruby_width(ruby));
+
+        base.max(ruby)
+    }
+
+    /// Distance from the start of base text, to the position after it,
+    /// including the spacing after the last character.
+    fn base_advance(&self, base: &str) -> u32 {
+        let font = self.base_font;
+
+        base.chars().count() as u32 * (font.character_size.width + font.character_spacing)
+    }
+
+    /// Width of an annotation, without the spacing after the last
+    /// character.
+    fn ruby_width(&self, ruby: &str) -> u32 {
+        let font = self.ruby_font;
+        let advance =
+            ruby.chars().count() as u32 * (font.character_size.width + font.character_spacing);
+
+        advance.saturating_sub(font.character_spacing)
+    }
+
+    fn font_style(&self, font: &'a MonoFont<'a>) -> MonoTextStyle<'a, C> {
+        let mut style = MonoTextStyleBuilder::new().font(font).build();
+
+        style.set_text_color(self.text_color);
+        style.set_background_color(self.background_color);
+
+        style
+    }
+}
+
+/// Where the segments of [`RubyText`] come from.
+#[derive(Copy, Clone)]
+enum Content<'a> {
+    Markup(&'a str),
+    Segments(&'a [Segment<'a>]),
+}
+
+/// Text with ruby annotations, like furigana, drawn above runs of the base
+/// text.
+///
+/// Each annotation is centered over its base text. Annotations that are
+/// wider than their base text widen the run instead, and the base text is
+/// centered below them. Line breaks in the text start a new line, below the
+/// annotations, and base text of the previous one.
+///
+/// ```
+/// # use embedded_graphics_cjk_glyph_mapping::embedded_graphics;
+/// use embedded_graphics::{
+///     geometry::Point, mock_display::MockDisplay, mono_font::iso_8859_1::FONT_6X10,
+///     pixelcolor::BinaryColor, Drawable,
+/// };
+/// use embedded_graphics_cjk_font_zpix::ZPIX_24;
+/// use embedded_graphics_cjk_ruby::{RubyText, RubyTextStyle};
+///
+/// // Pinyin above Simplified Chinese
+/// let style = RubyTextStyle::new(&ZPIX_24, &FONT_6X10, BinaryColor::On);
+/// let mut display = MockDisplay::new();
+///
+/// display.set_allow_out_of_bounds_drawing(true);
+///
+/// let next = RubyText::new("{汉字|hàn zì}", Point::zero(), style)
+///     .draw(&mut display)
+///     .unwrap();
+///
+/// // Two 26 pixel wide `ZPIX_24` characters are wider than the six 6 pixel
+/// // wide `FONT_6X10` characters of the annotation
+/// assert_eq!(next, Point::new(2 * 26, 0));
+/// ```
+#[derive(Copy, Clone)]
+pub struct RubyText<'a, C> {
+    content: Content<'a>,
+
+    /// Top left corner of the first line, above the annotations.
+    pub position: Point,
+
+    /// Text style.
+    pub style: RubyTextStyle<'a, C>,
+}
+
+impl<'a, C> RubyText<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates text from `{base|ruby}` markup. See [`Markup`].
+    pub fn new(markup: &'a str, position: Point, style: RubyTextStyle<'a, C>) -> Self {
+        RubyText {
+            content: Content::Markup(markup),
+            position,
+            style,
+        }
+    }
+
+    /// Creates text from segments.
+    pub fn with_segments(
+        segments: &'a [Segment<'a>],
+        position: Point,
+        style: RubyTextStyle<'a, C>,
+    ) -> Self {
+        RubyText {
+            content: Content::Segments(segments),
+            position,
+            style,
+        }
+    }
+
+    /// Returns an iterator over the segments of the text.
+    pub fn segments(&self) -> impl Iterator<Item = Segment<'a>> + 'a {
+        let (markup, segments) = match self.content {
+            Content::Markup(markup) => (Some(Markup::new(markup)), None),
+            Content::Segments(segments) => (None, Some(segments.iter().copied())),
+        };
+
+        markup
+            .into_iter()
+            .flatten()
+            .chain(segments.into_iter().flatten())
+    }
+
+    /// Draws a segment, with its top left corner at `position`, and
+    /// returns its width.
+    fn draw_segment<D>(
+        &self,
+        segment: &Segment<'a>,
+        position: Point,
+        target: &mut D,
+    ) -> Result<u32, D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let style = &self.style;
+        let width = style.segment_width(segment);
+        let base_width = style.base_advance(segment.base);
+
+        if let Some(ruby) = segment.ruby {
+            let ruby_width = style.ruby_width(ruby);
+            let ruby_position = position + Point::new(((width - ruby_width) / 2) as i32, 0);
+
+            Text::with_baseline(
+                ruby,
+                ruby_position,
+                style.font_style(style.ruby_font),
+                Baseline::Top,
+            )
+            .draw(target)?;
+        }
+
+        let base_position = position
+            + Point::new(
+                ((width - base_width) / 2) as i32,
+                style.ruby_height() as i32,
+            );
+
+        Text::with_baseline(
+            segment.base,
+            base_position,
+            style.font_style(style.base_font),
+            Baseline::Top,
+        )
+        .draw(target)?;
+
+        Ok(width)
+    }
+}
+
+impl<C> Drawable for RubyText<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    /// Top left corner of the position after the last segment.
+    type Output = Point;
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let line_height = self.style.line_height() as i32;
+        let mut position = self.position;
+
+        for segment in self.segments() {
+            for (index, base) in segment.base.split('\n').enumerate() {
+                if index > 0 {
+                    position = Point::new(self.position.x, position.y + line_height);
+                }
+
+                // The annotation is only drawn over the first line of its
+                // base text
+                let segment = Segment {
+                    base,
+                    ruby: segment.ruby.filter(|_| index == 0),
+                };
+
+                position.x += self.draw_segment(&segment, position, target)? as i32;
+            }
+        }
+
+        Ok(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RubyText, RubyTextStyle, Segment};
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{mapping::StrGlyphMapping, DecorationDimensions, MonoFont},
+        pixelcolor::BinaryColor,
+        Drawable,
+    };
+    use embedded_graphics_cjk_glyph_mapping::binary_image;
+
+    /// A base font with a 4x3 glyph for `中`, that's a box.
+    const BASE: MonoFont = MonoFont {
+        image: binary_image(&[0xF0, 0x90, 0xF0], 4),
+        glyph_mapping: &StrGlyphMapping::new("中", 0),
+        character_size: Size::new(4, 3),
+        character_spacing: 0,
+        baseline: 2,
+        underline: DecorationDimensions::new(2, 1),
+        strikethrough: DecorationDimensions::new(1, 1),
+    };
+
+    /// A ruby font with a single pixel 1x1 glyph for `か`.
+    const RUBY: MonoFont = MonoFont {
+        image: binary_image(&[0x80], 1),
+        glyph_mapping: &StrGlyphMapping::new("か", 0),
+        character_size: Size::new(1, 1),
+        character_spacing: 1,
+        baseline: 0,
+        underline: DecorationDimensions::new(0, 1),
+        strikethrough: DecorationDimensions::new(0, 1),
+    };
+
+    #[test]
+    fn test_segment_width() {
+        let style = RubyTextStyle::new(&BASE, &RUBY, BinaryColor::On);
+
+        assert_eq!(style.segment_width(&Segment::plain("中中")), 8);
+        assert_eq!(style.segment_width(&Segment::annotated("中", "かか")), 4);
+        // 3 glyphs, and the spacing between them
+        assert_eq!(style.segment_width(&Segment::annotated("中", "かかか")), 5);
+        assert_eq!(style.line_height(), 4);
+    }
+
+    #[test]
+    fn test_center_ruby() {
+        let style = RubyTextStyle::new(&BASE, &RUBY, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        let next = RubyText::new("{中中|か}中", Point::zero(), style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(12, 0));
+
+        display.assert_pattern(&[
+            "   #        ", //
+            "############",
+            "#  ##  ##  #",
+            "############",
+        ]);
+    }
+
+    #[test]
+    fn test_wide_ruby() {
+        let mut style = RubyTextStyle::new(&BASE, &RUBY, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        style.ruby_spacing = 1;
+
+        // The annotation is wider than the base text, which is centered
+        // below it.
+        let next = RubyText::with_segments(
+            &[Segment::annotated("中", "かかかか"), Segment::plain("中")],
+            Point::zero(),
+            style,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(next, Point::new(7 + 4, 0));
+
+        display.assert_pattern(&[
+            "# # # #    ", //
+            "           ",
+            " ####  ####",
+            " #  #  #  #",
+            " ####  ####",
+        ]);
+    }
+
+    #[test]
+    fn test_line_break() {
+        let style = RubyTextStyle::new(&BASE, &RUBY, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        let next = RubyText::new("{中|か}\n中", Point::zero(), style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(4, 4));
+
+        display.assert_pattern(&[
+            " #  ", //
+            "####", "#  #", "####", "    ", "####", "#  #", "####",
+        ]);
+    }
+}
@@ -0,0 +1,137 @@
+/// A run of base text, with an optional annotation that is drawn above it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment<'a> {
+    /// Base text.
+    pub base: &'a str,
+
+    /// Annotation, usually the reading of the base text in kana.
+    pub ruby: Option<&'a str>,
+}
+
+impl<'a> Segment<'a> {
+    /// Creates a segment of base text without an annotation.
+    pub const fn plain(base: &'a str) -> Self {
+        Segment { base, ruby: None }
+    }
+
+    /// Creates a segment of base text with an annotation.
+    pub const fn annotated(base: &'a str, ruby: &'a str) -> Self {
+        Segment {
+            base,
+            ruby: Some(ruby),
+        }
+    }
+}
+
+/// Iterator over the segments of text with `{base|ruby}` markup.
+///
+/// Braces that don't enclose a `|`, like `{a}`, or a `{` without a closing
+/// `}`, are kept as plain text.
+///
+/// ```
+/// use embedded_graphics_cjk_ruby::{Markup, Segment};
+///
+/// let segments: Vec<_> = Markup::new("{漢字|かんじ}を読む").collect();
+///
+/// assert_eq!(
+///     segments,
+///     [Segment::annotated("漢字", "かんじ"), Segment::plain("を読む")]
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Markup<'a> {
+    text: &'a str,
+}
+
+impl<'a> Markup<'a> {
+    /// Creates an iterator over the segments of the text.
+    pub const fn new(text: &'a str) -> Self {
+        Markup { text }
+    }
+
+    /// Returns the annotated segment at the start of the text, and its
+    /// length, if the text starts with valid markup.
+    fn annotated(text: &'a str) -> Option<(Segment<'a>, usize)> {
+        let inner = text.strip_prefix('{')?;
+        let end = inner.find('}')?;
+        let (base, ruby) = inner[..end].split_once('|')?;
+
+        if base.is_empty() || base.contains('{') {
+            return None;
+        }
+
+        Some((Segment::annotated(base, ruby), end + 2))
+    }
+}
+
+impl<'a> Iterator for Markup<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.text.is_empty() {
+            return None;
+        }
+
+        if let Some((segment, len)) = Self::annotated(self.text) {
+            self.text = &self.text[len..];
+
+            return Some(segment);
+        }
+
+        // Plain text runs until the next `{` that starts valid markup
+        let mut end = self.text.len();
+
+        for (index, _) in self.text.match_indices('{').filter(|(index, _)| *index > 0) {
+            if Self::annotated(&self.text[index..]).is_some() {
+                end = index;
+                break;
+            }
+        }
+
+        let (base, rest) = self.text.split_at(end);
+
+        self.text = rest;
+
+        Some(Segment::plain(base))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Markup, Segment};
+
+    fn parse(text: &str) -> Vec<Segment<'_>> {
+        Markup::new(text).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("今日は{日曜日|にちようび}です。"),
+            [
+                Segment::plain("今日は"),
+                Segment::annotated("日曜日", "にちようび"),
+                Segment::plain("です。"),
+            ]
+        );
+        assert_eq!(
+            parse("{東|とう}{京|きょう}"),
+            [
+                Segment::annotated("東", "とう"),
+                Segment::annotated("京", "きょう"),
+            ]
+        );
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_markup() {
+        assert_eq!(parse("{a}b"), [Segment::plain("{a}b")]);
+        assert_eq!(parse("a{b|c"), [Segment::plain("a{b|c")]);
+        assert_eq!(parse("{|c}"), [Segment::plain("{|c}")]);
+        assert_eq!(
+            parse("{a{b|c}"),
+            [Segment::plain("{a"), Segment::annotated("b", "c")]
+        );
+    }
+}
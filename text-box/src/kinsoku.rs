@@ -0,0 +1,90 @@
+/// Returns `true` for characters that can't start a line, like closing
+/// brackets, `、`, `。`, small kana, and `ー`.
+pub fn is_line_start_prohibited(chr: char) -> bool {
+    matches!(
+        chr,
+        // Closing brackets, and quotation marks
+        ')' | ']' | '}' | '»' | '’' | '”' | '〉' | '》' | '」' | '』' | '】' | '〕' | '〗' | '〙'
+            | '〛' | '〞' | '〟' | '）' | '］' | '｝' | '｠' | '｣'
+            // Commas, full stops, and other punctuation
+            | ',' | '.' | ':' | ';' | '!' | '?' | '、' | '。' | '，' | '．' | '：' | '；' | '！'
+            | '？' | '､' | '｡' | '‼' | '⁇' | '⁈' | '⁉' | '・' | '･' | '‐' | '゠' | '–' | '〜'
+            | '～' | '…' | '‥'
+            // Iteration marks, and the long vowel mark
+            | '々' | '〻' | 'ゝ' | 'ゞ' | 'ヽ' | 'ヾ' | 'ー' | 'ｰ' | '゛' | '゜' | 'ﾞ' | 'ﾟ'
+            // Small kana
+            | 'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'っ' | 'ゃ' | 'ゅ' | 'ょ' | 'ゎ' | 'ゕ' | 'ゖ'
+            | 'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' | 'ッ' | 'ャ' | 'ュ' | 'ョ' | 'ヮ' | 'ヵ' | 'ヶ'
+            | '\u{31F0}'..='\u{31FF}'
+            | '\u{FF67}'..='\u{FF6F}'
+    )
+}
+
+/// Returns `true` for characters that can't end a line, like opening
+/// brackets, and quotation marks.
+pub fn is_line_end_prohibited(chr: char) -> bool {
+    matches!(
+        chr,
+        '(' | '['
+            | '{'
+            | '«'
+            | '‘'
+            | '“'
+            | '〈'
+            | '《'
+            | '「'
+            | '『'
+            | '【'
+            | '〔'
+            | '〖'
+            | '〘'
+            | '〚'
+            | '〝'
+            | '（'
+            | '［'
+            | '｛'
+            | '｟'
+            | '｢'
+    )
+}
+
+/// Returns `true` for the commas, and full stops that can hang past the end
+/// of a line, instead of starting the next one.
+pub fn is_hanging_punctuation(chr: char) -> bool {
+    matches!(chr, '、' | '。' | '，' | '．' | '､' | '｡' | ',' | '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_hanging_punctuation, is_line_end_prohibited, is_line_start_prohibited};
+
+    #[test]
+    fn test_line_start_prohibited() {
+        for chr in ['、', '。', '」', 'ー', 'ゃ', 'ッ', '！', '）', '，', '…'] {
+            assert!(is_line_start_prohibited(chr), "{}", chr);
+        }
+
+        for chr in ['中', 'あ', 'ア', '「', '（', 'a'] {
+            assert!(!is_line_start_prohibited(chr), "{}", chr);
+        }
+    }
+
+    #[test]
+    fn test_line_end_prohibited() {
+        for chr in ['「', '『', '（', '【', '“', '('] {
+            assert!(is_line_end_prohibited(chr), "{}", chr);
+        }
+
+        for chr in ['中', '」', '。', 'a'] {
+            assert!(!is_line_end_prohibited(chr), "{}", chr);
+        }
+    }
+
+    #[test]
+    fn test_hanging_punctuation() {
+        assert!(is_hanging_punctuation('、'));
+        assert!(is_hanging_punctuation('。'));
+        assert!(!is_hanging_punctuation('」'));
+        assert!(!is_hanging_punctuation('！'));
+    }
+}
@@ -0,0 +1,440 @@
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod kinsoku;
+mod line_break;
+
+pub use kinsoku::{is_hanging_punctuation, is_line_end_prohibited, is_line_start_prohibited};
+pub use line_break::{BreakOpportunity, LineBreakClass, LineBreaks};
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Point,
+    pixelcolor::PixelColor,
+    primitives::Rectangle,
+    text::{renderer::TextRenderer, Baseline, Text},
+    Drawable,
+};
+use embedded_graphics_cjk_mixed_width::MixedWidthTextStyle;
+
+/// Text wrapped into a rectangle.
+///
+/// Lines are broken where UAX #14 allows it, so Latin words are kept
+/// together, and CJK text can be broken between any two characters. Kinsoku
+/// rules are applied on top: no line starts with a character like `、`, `。`,
+/// `」`, or `ー`, and no line ends with a character like `「`, or `（`. Words
+/// that are wider than the rectangle are broken between characters.
+///
+/// Characters are measured with the `character_size` of the style's fonts,
+/// so text is laid out without drawing it.
+///
+/// ```
+/// # use embedded_graphics_cjk_glyph_mapping::embedded_graphics;
+/// use embedded_graphics::{
+///     geometry::{Point, Size},
+///     mono_font::ascii::FONT_6X10,
+///     pixelcolor::BinaryColor,
+///     primitives::Rectangle,
+/// };
+/// use embedded_graphics_cjk_font_zpix::ZPIX_12;
+/// use embedded_graphics_cjk_mixed_width::MixedWidthTextStyle;
+/// use embedded_graphics_cjk_text_box::TextBox;
+///
+/// let style = MixedWidthTextStyle::new(&FONT_6X10, &ZPIX_12, BinaryColor::On);
+/// let bounds = Rectangle::new(Point::zero(), Size::new(13 * 4, 60));
+/// let text_box = TextBox::new("「こんにちは」と言った。", bounds, style);
+/// let lines: Vec<_> = text_box.lines().map(|line| line.text).collect();
+///
+/// assert_eq!(lines, ["「こんに", "ちは」と", "言った。"]);
+/// ```
+#[derive(Copy, Clone)]
+pub struct TextBox<'a, C> {
+    /// Text.
+    pub text: &'a str,
+
+    /// Rectangle the text is wrapped into.
+    pub bounds: Rectangle,
+
+    /// Text style.
+    pub style: MixedWidthTextStyle<'a, C>,
+
+    /// Let a `、`, or `。` that doesn't fit at the end of a line hang past
+    /// the right edge of the rectangle, instead of moving it, and the
+    /// character before it to the next line.
+    pub hanging_punctuation: bool,
+}
+
+/// A line of wrapped text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// Text of the line, without the spaces, and line break at its end.
+    pub text: &'a str,
+
+    /// Width of the line, in pixels. Lines that end in hanging punctuation
+    /// are wider than the rectangle.
+    pub width: u32,
+}
+
+/// Iterator over the lines of a [`TextBox`].
+pub struct Lines<'a, C> {
+    /// Text that hasn't been laid out yet.
+    text: &'a str,
+
+    style: MixedWidthTextStyle<'a, C>,
+    width: u32,
+    hanging_punctuation: bool,
+}
+
+impl<'a, C> TextBox<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a text box without hanging punctuation.
+    pub fn new(text: &'a str, bounds: Rectangle, style: MixedWidthTextStyle<'a, C>) -> Self {
+        TextBox {
+            text,
+            bounds,
+            style,
+            hanging_punctuation: false,
+        }
+    }
+
+    /// Returns an iterator over the wrapped lines. Lines aren't limited to
+    /// the height of the rectangle.
+    pub fn lines(&self) -> Lines<'a, C> {
+        Lines {
+            text: self.text,
+            style: self.style,
+            width: self.bounds.size.width,
+            hanging_punctuation: self.hanging_punctuation,
+        }
+    }
+}
+
+impl<'a, C> Lines<'a, C>
+where
+    C: PixelColor,
+{
+    /// Returns the text that hasn't been returned as a line yet.
+    pub fn remainder(&self) -> &'a str {
+        self.text
+    }
+
+    fn char_width(&self, chr: char) -> u32 {
+        let font = self.style.font(chr);
+
+        font.character_size.width + font.character_spacing
+    }
+
+    fn measure(&self, text: &str) -> u32 {
+        text.chars().map(|chr| self.char_width(chr)).sum()
+    }
+
+    /// Returns `true` if a line that ends in `line` fits in the rectangle,
+    /// or only a hanging comma, or full stop doesn't.
+    fn fits(&self, line: &str, width: u32) -> bool {
+        if width <= self.width {
+            return true;
+        }
+
+        match line.chars().next_back() {
+            Some(last) if self.hanging_punctuation && is_hanging_punctuation(last) => {
+                width - self.char_width(last) <= self.width
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `false` if breaking the line before `index` starts the next
+    /// line, or ends this line with a prohibited character.
+    ///
+    /// UAX #14 already keeps most of these characters attached to their
+    /// neighbours, but allows breaks around some of them, like quotation
+    /// marks.
+    fn is_kinsoku_allowed(text: &str, index: usize) -> bool {
+        let before = text[..index].chars().next_back();
+        let after = text[index..].chars().next();
+
+        !before.is_some_and(is_line_end_prohibited) && !after.is_some_and(is_line_start_prohibited)
+    }
+
+    /// Breaks a word that is wider than the rectangle between characters.
+    fn break_word(&mut self, word: &'a str) -> Line<'a> {
+        let mut width = 0;
+        let mut end = 0;
+
+        for (index, chr) in word.char_indices() {
+            let char_width = self.char_width(chr);
+
+            if index > 0 && width + char_width > self.width {
+                break;
+            }
+
+            width += char_width;
+            end = index + chr.len_utf8();
+        }
+
+        self.text = &self.text[end..];
+
+        Line {
+            text: &word[..end],
+            width,
+        }
+    }
+}
+
+impl<'a, C> Iterator for Lines<'a, C>
+where
+    C: PixelColor,
+{
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.text;
+        let mut line = None;
+        let mut width = 0;
+        let mut segment_start = 0;
+
+        for (index, opportunity) in LineBreaks::new(text) {
+            if opportunity == BreakOpportunity::Allowed && !Self::is_kinsoku_allowed(text, index) {
+                continue;
+            }
+
+            let segment = &text[segment_start..index];
+            let visible = trim_line_end(segment);
+            let line_width = width + self.measure(visible);
+
+            if !self.fits(visible, line_width) {
+                if line.is_some() {
+                    break;
+                }
+
+                return Some(self.break_word(visible));
+            }
+
+            line = Some(Line {
+                text: &text[..segment_start + visible.len()],
+                width: line_width,
+            });
+            width += self.measure(segment);
+            segment_start = index;
+
+            if opportunity == BreakOpportunity::Mandatory {
+                break;
+            }
+        }
+
+        self.text = &text[segment_start..];
+
+        line
+    }
+}
+
+/// Removes the spaces, and line break at the end of a line.
+fn trim_line_end(text: &str) -> &str {
+    text.trim_end_matches(|chr| {
+        matches!(
+            LineBreakClass::of(chr),
+            LineBreakClass::Space
+                | LineBreakClass::Mandatory
+                | LineBreakClass::CarriageReturn
+                | LineBreakClass::LineFeed
+                | LineBreakClass::NextLine
+        )
+    })
+}
+
+impl<'a, C> Drawable for TextBox<'a, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    /// Text that didn't fit in the rectangle.
+    type Output = &'a str;
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let line_height = self.style.line_height() as i32;
+        let bottom = self.bounds.top_left.y + self.bounds.size.height as i32;
+        let mut lines = self.lines();
+        let mut position = self.bounds.top_left;
+
+        while position.y + line_height <= bottom {
+            let Some(line) = lines.next() else {
+                break;
+            };
+
+            Text::with_baseline(line.text, position, self.style, Baseline::Top).draw(target)?;
+
+            position += Point::new(0, line_height);
+        }
+
+        Ok(lines.remainder())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Line, TextBox};
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X10, mapping::StrGlyphMapping, DecorationDimensions, MonoFont},
+        pixelcolor::BinaryColor,
+        primitives::Rectangle,
+        Drawable,
+    };
+    use embedded_graphics_cjk_font_zpix::ZPIX_12;
+    use embedded_graphics_cjk_glyph_mapping::binary_image;
+    use embedded_graphics_cjk_mixed_width::MixedWidthTextStyle;
+
+    /// From "I Am a Cat", by Natsume Sōseki.
+    const JAPANESE: &str = "吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。何でも薄暗いじめじめした所でニャーニャー泣いていた事だけは記憶している。\n「embedded-graphics」で、横書きの文を表示。";
+
+    const CHINESE: &str = "嵌入式设备屏幕都很小，每一行只能显示几个汉字。换行时，句号和逗号不能出现在行首，“引号”和（括号）也要和文字放在一起。\n使用Rust 1.75编写。";
+
+    /// A font with a single, filled 4x4 glyph.
+    const SQUARE: MonoFont = MonoFont {
+        image: binary_image(&[0xF0; 4], 4),
+        glyph_mapping: &StrGlyphMapping::new("中", 0),
+        character_size: Size::new(4, 4),
+        character_spacing: 0,
+        baseline: 3,
+        underline: DecorationDimensions::new(3, 1),
+        strikethrough: DecorationDimensions::new(2, 1),
+    };
+
+    /// Wraps text into a box of the given width, with `FONT_6X10` for
+    /// Latin text.
+    fn lines(text: &str, width: u32) -> Vec<Line<'_>> {
+        let style = MixedWidthTextStyle::new(&FONT_6X10, &ZPIX_12, BinaryColor::On);
+        let bounds = Rectangle::new(Point::zero(), Size::new(width, 100));
+
+        TextBox::new(text, bounds, style).lines().collect()
+    }
+
+    fn line_texts(text: &str, width: u32) -> Vec<&str> {
+        lines(text, width).iter().map(|line| line.text).collect()
+    }
+
+    #[test]
+    fn test_spaces_at_line_end() {
+        assert_eq!(
+            lines("hello world", 6 * 7),
+            [
+                Line {
+                    text: "hello",
+                    width: 6 * 5,
+                },
+                Line {
+                    text: "world",
+                    width: 6 * 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        assert_eq!(line_texts("a\n\nb c\n", 100), ["a", "", "b c"]);
+        assert!(line_texts("", 100).is_empty());
+    }
+
+    #[test]
+    fn test_break_long_word() {
+        assert_eq!(
+            line_texts("embedded-graphics", 6 * 5),
+            ["embed", "ded-", "graph", "ics"]
+        );
+
+        // At least one character is placed on each line
+        assert_eq!(line_texts("中文", 6), ["中", "文"]);
+    }
+
+    #[test]
+    fn test_kinsoku() {
+        // `。`, and `ー` can't start a line, so the characters before them
+        // move to the next line too
+        assert_eq!(line_texts("あいう。", 13 * 3), ["あい", "う。"]);
+        assert_eq!(line_texts("あいコー", 13 * 3), ["あい", "コー"]);
+
+        // `「` can't end a line
+        assert_eq!(line_texts("あい「う」", 13 * 3), ["あい", "「う」"]);
+    }
+
+    #[test]
+    fn test_draw() {
+        let style = MixedWidthTextStyle::new(&SQUARE, &SQUARE, BinaryColor::On);
+        let bounds = Rectangle::new(Point::new(1, 0), Size::new(8, 11));
+        let mut display = MockDisplay::new();
+
+        // Only two lines fit in the box
+        let remainder = TextBox::new("中中中中中", bounds, style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(remainder, "中");
+
+        display.assert_pattern(&[
+            " ########", //
+            " ########",
+            " ########",
+            " ########",
+            " ########",
+            " ########",
+            " ########",
+            " ########",
+        ]);
+    }
+
+    /// Wraps text into a box that fits ten `ZPIX_12` characters in each
+    /// line, and formats each line, followed by its width.
+    fn layout(text: &str, hanging_punctuation: bool) -> String {
+        let style = MixedWidthTextStyle::new(&FONT_6X10, &ZPIX_12, BinaryColor::On);
+        let bounds = Rectangle::new(Point::zero(), Size::new(13 * 10, 120));
+        let mut text_box = TextBox::new(text, bounds, style);
+
+        text_box.hanging_punctuation = hanging_punctuation;
+
+        text_box
+            .lines()
+            .map(|line| format!("{:<3} {}\n", line.width, line.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_golden_japanese() {
+        assert_eq!(layout(JAPANESE, false), include_str!("../golden/ja.txt"));
+        assert_eq!(
+            layout(JAPANESE, true),
+            include_str!("../golden/ja-hanging.txt")
+        );
+    }
+
+    #[test]
+    fn test_golden_chinese() {
+        assert_eq!(layout(CHINESE, false), include_str!("../golden/zh.txt"));
+        assert_eq!(
+            layout(CHINESE, true),
+            include_str!("../golden/zh-hanging.txt")
+        );
+    }
+}
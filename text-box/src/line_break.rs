@@ -0,0 +1,540 @@
+use core::str::CharIndices;
+use unicode_width::UnicodeWidthChar;
+
+/// Line breaking class of a character, from
+/// [UAX #14](https://www.unicode.org/reports/tr14/).
+///
+/// Classes that are resolved to another class before any rules are applied
+/// (like `AI`, `SA`, and `XX`, which are resolved to `AL`) aren't included.
+/// Conditional Japanese starters (`CJ`), like small kana, and `ー`, are
+/// resolved to `NS`, as in strict line breaking, which is what kinsoku rules
+/// expect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineBreakClass {
+    /// `BK`: mandatory break, like U+2028.
+    Mandatory,
+
+    /// `CR`: carriage return.
+    CarriageReturn,
+
+    /// `LF`: line feed.
+    LineFeed,
+
+    /// `NL`: next line, U+0085.
+    NextLine,
+
+    /// `SP`: space.
+    Space,
+
+    /// `ZW`: zero width space.
+    ZeroWidthSpace,
+
+    /// `WJ`: word joiner, which prevents breaks on either side.
+    WordJoiner,
+
+    /// `GL`: non-breaking glue, like a no-break space.
+    Glue,
+
+    /// `CM`: combining mark, which takes the class of the character before
+    /// it.
+    CombiningMark,
+
+    /// `OP`: opening punctuation, like `(`, and `「`.
+    OpenPunctuation,
+
+    /// `CL`: closing punctuation, like `}`, `」`, and `。`.
+    ClosePunctuation,
+
+    /// `CP`: closing parenthesis, `)`, and `]`.
+    CloseParenthesis,
+
+    /// `QU`: ambiguous quotation, like `"`, and `“`.
+    Quotation,
+
+    /// `EX`: exclamation, or interrogation, like `!`, and `？`.
+    Exclamation,
+
+    /// `IS`: infix numeric separator, like `,`, and `.`.
+    InfixSeparator,
+
+    /// `SY`: symbols allowing a break after, `/`.
+    Symbol,
+
+    /// `NS`: nonstarter, like `々`, `・`, small kana, and `ー`.
+    Nonstarter,
+
+    /// `PR`: prefix numeric, like `$`, and `¥`.
+    PrefixNumeric,
+
+    /// `PO`: postfix numeric, like `%`, and `°`.
+    PostfixNumeric,
+
+    /// `NU`: digits.
+    Numeric,
+
+    /// `AL`: alphabetic, and most symbols.
+    Alphabetic,
+
+    /// `ID`: ideographic, like Han characters, kana, and fullwidth letters.
+    Ideographic,
+
+    /// `IN`: inseparable characters, like `…`.
+    Inseparable,
+
+    /// `HY`: hyphen-minus.
+    Hyphen,
+
+    /// `BA`: break after, like hyphens, and the ideographic space.
+    BreakAfter,
+
+    /// `BB`: break before, like `´`.
+    BreakBefore,
+
+    /// `B2`: break on either side, but not between, the em dash.
+    BreakBoth,
+
+    /// `H2`: Hangul LV syllable.
+    HangulLv,
+
+    /// `H3`: Hangul LVT syllable.
+    HangulLvt,
+
+    /// `JL`: Hangul leading jamo.
+    HangulL,
+
+    /// `JV`: Hangul vowel jamo.
+    HangulV,
+
+    /// `JT`: Hangul trailing jamo.
+    HangulT,
+}
+
+impl LineBreakClass {
+    /// Returns the line breaking class of a character.
+    ///
+    /// Scripts that aren't used alongside CJK text are mostly resolved to
+    /// `AL`.
+    pub fn of(chr: char) -> Self {
+        use LineBreakClass::*;
+
+        match chr {
+            '\n' => LineFeed,
+            '\r' => CarriageReturn,
+            '\u{0B}' | '\u{0C}' | '\u{2028}' | '\u{2029}' => Mandatory,
+            '\u{85}' => NextLine,
+            '\t' => BreakAfter,
+            '\0'..='\u{1F}' | '\u{7F}'..='\u{9F}' => CombiningMark,
+
+            // ASCII
+            ' ' => Space,
+            '!' | '?' => Exclamation,
+            '"' | '\'' => Quotation,
+            '$' | '+' | '\\' => PrefixNumeric,
+            '%' => PostfixNumeric,
+            '(' | '[' | '{' => OpenPunctuation,
+            ')' | ']' => CloseParenthesis,
+            '}' => ClosePunctuation,
+            ',' | '.' | ':' | ';' => InfixSeparator,
+            '-' => Hyphen,
+            '/' => Symbol,
+            '0'..='9' => Numeric,
+            '|' => BreakAfter,
+
+            // Latin-1
+            '\u{A0}' => Glue,
+            '\u{AD}' => BreakAfter,
+            '¡' | '¿' => OpenPunctuation,
+            '¢' | '°' | '‰' | '′' | '″' | '℃' => PostfixNumeric,
+            '£' | '¤' | '¥' | '±' | '№' => PrefixNumeric,
+            '«' | '»' | '‘' | '’' | '‛' | '“' | '”' | '‟' | '‹' | '›' => {
+                Quotation
+            }
+            '´' => BreakBefore,
+            '\u{300}'..='\u{36F}' => CombiningMark,
+
+            // General Punctuation
+            '\u{2000}'..='\u{2006}' | '\u{2008}'..='\u{200A}' => BreakAfter,
+            '\u{2007}' | '\u{2011}' | '\u{202F}' => Glue,
+            '\u{200B}' => ZeroWidthSpace,
+            '\u{200C}' | '\u{200D}' => CombiningMark,
+            '‐' | '‒' | '–' => BreakAfter,
+            '—' => BreakBoth,
+            '‚' | '„' => OpenPunctuation,
+            '․' | '‥' | '…' => Inseparable,
+            '‼' | '‽' | '⁇' | '⁈' | '⁉' => Nonstarter,
+            '⁄' => InfixSeparator,
+            '\u{2060}' | '\u{FEFF}' => WordJoiner,
+            '\u{20A0}'..='\u{20CF}' => PrefixNumeric,
+
+            // Hangul jamo, and syllables
+            '\u{1100}'..='\u{115F}' | '\u{A960}'..='\u{A97C}' => HangulL,
+            '\u{1160}'..='\u{11A7}' | '\u{D7B0}'..='\u{D7C6}' => HangulV,
+            '\u{11A8}'..='\u{11FF}' | '\u{D7CB}'..='\u{D7FB}' => HangulT,
+            '\u{AC00}'..='\u{D7A3}' if (chr as u32 - 0xAC00).is_multiple_of(28) => HangulLv,
+            '\u{AC00}'..='\u{D7A3}' => HangulLvt,
+
+            // CJK Symbols and Punctuation
+            '\u{3000}' => BreakAfter,
+            '、' | '。' => ClosePunctuation,
+            '〈' | '《' | '「' | '『' | '【' | '〔' | '〖' | '〘' | '〚' | '〝' => {
+                OpenPunctuation
+            }
+            '〉' | '》' | '」' | '』' | '】' | '〕' | '〗' | '〙' | '〛' | '〞' | '〟' => {
+                ClosePunctuation
+            }
+            '々' | '〻' | '〜' => Nonstarter,
+            '\u{302A}'..='\u{302F}' | '\u{3099}' | '\u{309A}' => CombiningMark,
+
+            // Small kana, which are conditional Japanese starters
+            'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'っ' | 'ゃ' | 'ゅ' | 'ょ' | 'ゎ' | 'ゕ' | 'ゖ' => {
+                Nonstarter
+            }
+            'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' | 'ッ' | 'ャ' | 'ュ' | 'ョ' | 'ヮ' | 'ヵ' | 'ヶ' => {
+                Nonstarter
+            }
+            'ー' | '\u{31F0}'..='\u{31FF}' | '\u{FF67}'..='\u{FF70}' => Nonstarter,
+
+            // Kana iteration marks, and other nonstarters
+            '゛' | '゜' | 'ゝ' | 'ゞ' | '゠' | '・' | 'ヽ' | 'ヾ' => Nonstarter,
+
+            // Small, and vertical forms
+            '︐' | '︓' | '︔' => InfixSeparator,
+            '︑' | '︒' | '﹐' | '﹒' => ClosePunctuation,
+            '︕' | '︖' | '﹖' | '﹗' => Exclamation,
+            '︗' | '﹙' | '﹛' | '﹝' => OpenPunctuation,
+            '︘' | '﹚' | '﹜' | '﹞' => ClosePunctuation,
+            '﹔' | '﹕' => Nonstarter,
+
+            // Fullwidth, and halfwidth forms
+            '！' | '？' => Exclamation,
+            '＄' | '￡' | '￥' | '￦' => PrefixNumeric,
+            '％' | '￠' => PostfixNumeric,
+            '（' | '［' | '｛' | '｟' | '｢' => OpenPunctuation,
+            '）' | '，' | '．' | '］' | '｝' | '｠' | '｡' | '｣' | '､' => {
+                ClosePunctuation
+            }
+            '：' | '；' | '･' | 'ﾞ' | 'ﾟ' => Nonstarter,
+
+            // Ideographs, kana, fullwidth letters, and emoji
+            '\u{2E80}'..='\u{2FFF}'
+            | '\u{3000}'..='\u{33FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{A000}'..='\u{A4CF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FE30}'..='\u{FE4F}'
+            | '\u{FF01}'..='\u{FF60}'
+            | '\u{FF66}'..='\u{FF9F}'
+            | '\u{1F000}'..='\u{1FAFF}'
+            | '\u{20000}'..='\u{3FFFD}' => Ideographic,
+
+            _ => Alphabetic,
+        }
+    }
+}
+
+/// A position where a line can, or has to be broken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakOpportunity {
+    /// The line has to be broken, after a line break, or at the end of the
+    /// text.
+    Mandatory,
+
+    /// The line can be broken.
+    Allowed,
+}
+
+/// Iterator over the positions where text can be broken into lines, using
+/// the pair rules of UAX #14.
+///
+/// Each item is the byte index of the character that starts the next line.
+/// The end of the text is always a mandatory break. Breaks are never
+/// reported before the first character.
+pub struct LineBreaks<'a> {
+    text: &'a str,
+    chars: CharIndices<'a>,
+
+    /// Class of the previous character, after combining marks are resolved.
+    previous: Option<LineBreakClass>,
+
+    /// Class of the last character before any spaces.
+    before_spaces: LineBreakClass,
+
+    /// Previous character, which isn't a combining mark.
+    previous_char: char,
+
+    /// Whether a zero width space was followed only by spaces.
+    after_zero_width_space: bool,
+
+    done: bool,
+}
+
+impl<'a> LineBreaks<'a> {
+    /// Creates an iterator over the break opportunities in the text.
+    pub fn new(text: &'a str) -> Self {
+        LineBreaks {
+            text,
+            chars: text.char_indices(),
+            previous: None,
+            before_spaces: LineBreakClass::Alphabetic,
+            previous_char: '\0',
+            after_zero_width_space: false,
+            done: false,
+        }
+    }
+
+    /// Returns the break opportunity between two characters, or `None` if
+    /// the line can't be broken between them.
+    fn pair(
+        &self,
+        previous: LineBreakClass,
+        class: LineBreakClass,
+        chr: char,
+    ) -> Option<BreakOpportunity> {
+        use LineBreakClass::*;
+
+        // LB4, and LB5: break after line breaks, but not within CR LF
+        match (previous, class) {
+            (CarriageReturn, LineFeed) => return None,
+            (Mandatory | CarriageReturn | LineFeed | NextLine, _) => {
+                return Some(BreakOpportunity::Mandatory)
+            }
+            _ => {}
+        }
+
+        let prohibited = match (self.before_spaces, previous, class) {
+            // LB6, and LB7: don't break before line breaks, or spaces
+            (_, _, Mandatory | CarriageReturn | LineFeed | NextLine | Space | ZeroWidthSpace) => {
+                true
+            }
+
+            // LB8: break after a zero width space, even if it's followed by
+            // spaces
+            _ if self.after_zero_width_space => false,
+
+            // LB11, LB12, and LB12a: word joiners, and glue
+            (_, WordJoiner, _) | (_, _, WordJoiner) | (_, Glue, _) => true,
+            (_, previous, Glue) => !matches!(previous, Space | BreakAfter | Hyphen),
+
+            // LB13: don't break before closing punctuation, even after spaces
+            (_, _, ClosePunctuation | CloseParenthesis | Exclamation | InfixSeparator | Symbol) => {
+                true
+            }
+
+            // LB14 to LB17: don't break after opening punctuation, even
+            // before spaces
+            (OpenPunctuation, _, _)
+            | (Quotation, _, OpenPunctuation)
+            | (ClosePunctuation | CloseParenthesis, _, Nonstarter)
+            | (BreakBoth, _, BreakBoth) => true,
+
+            // LB18: break after spaces
+            (_, Space, _) => false,
+
+            // LB19, and LB19a: don't break around quotation marks, except
+            // before opening, and after closing quotation marks that are
+            // next to an East Asian character
+            (_, Quotation, _) => !is_final_quote(self.previous_char) || chr.width() != Some(2),
+            (_, _, Quotation) => !is_initial_quote(chr) || self.previous_char.width() != Some(2),
+
+            // LB21, and LB22: don't break before hyphens, nonstarters, and
+            // inseparable characters
+            (_, _, BreakAfter | Hyphen | Nonstarter | Inseparable) | (_, BreakBefore, _) => true,
+
+            // LB23 to LB25: keep numbers together with letters, and their
+            // prefixes, and postfixes
+            (_, Alphabetic, Numeric)
+            | (_, Numeric, Alphabetic)
+            | (_, PrefixNumeric, Ideographic)
+            | (_, Ideographic, PostfixNumeric)
+            | (_, PrefixNumeric | PostfixNumeric, Alphabetic)
+            | (_, Alphabetic, PrefixNumeric | PostfixNumeric)
+            | (_, ClosePunctuation | CloseParenthesis | Numeric, PrefixNumeric | PostfixNumeric)
+            | (_, PrefixNumeric | PostfixNumeric, OpenPunctuation | Numeric)
+            | (_, Hyphen | InfixSeparator | Numeric | Symbol, Numeric) => true,
+
+            // LB26, and LB27: keep Hangul syllables together
+            (_, HangulL, HangulL | HangulV | HangulLv | HangulLvt)
+            | (_, HangulV | HangulLv, HangulV | HangulT)
+            | (_, HangulT | HangulLvt, HangulT)
+            | (_, HangulL | HangulV | HangulT | HangulLv | HangulLvt, PostfixNumeric)
+            | (_, PrefixNumeric, HangulL | HangulV | HangulT | HangulLv | HangulLvt) => true,
+
+            // LB28, and LB29: don't break within words
+            (_, Alphabetic, Alphabetic) | (_, InfixSeparator, Alphabetic) => true,
+
+            // LB30: don't break between words, and narrow parentheses
+            (_, Alphabetic | Numeric, OpenPunctuation) => chr.width() != Some(2),
+            (_, CloseParenthesis, Alphabetic | Numeric) => true,
+
+            // LB31: break everywhere else
+            _ => false,
+        };
+
+        (!prohibited).then_some(BreakOpportunity::Allowed)
+    }
+}
+
+impl Iterator for LineBreaks<'_> {
+    type Item = (usize, BreakOpportunity);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use LineBreakClass::*;
+
+        while !self.done {
+            let Some((index, chr)) = self.chars.next() else {
+                // LB3: always break at the end of the text
+                self.done = true;
+
+                return self
+                    .previous
+                    .map(|_| (self.text.len(), BreakOpportunity::Mandatory));
+            };
+            let mut class = LineBreakClass::of(chr);
+
+            let Some(previous) = self.previous else {
+                // LB2: never break at the start of the text
+                if class == CombiningMark {
+                    class = Alphabetic;
+                }
+
+                self.previous = Some(class);
+                self.before_spaces = class;
+                self.previous_char = chr;
+                self.after_zero_width_space = class == ZeroWidthSpace;
+
+                continue;
+            };
+
+            // LB9, and LB10: combining marks take the class of the character
+            // before them, unless it's a space, or a line break
+            if class == CombiningMark {
+                if !matches!(
+                    previous,
+                    Mandatory | CarriageReturn | LineFeed | NextLine | Space | ZeroWidthSpace
+                ) {
+                    continue;
+                }
+
+                class = Alphabetic;
+            }
+
+            let opportunity = self.pair(previous, class, chr);
+
+            self.previous = Some(class);
+            self.previous_char = chr;
+
+            if class != Space {
+                self.before_spaces = class;
+            }
+
+            if class == ZeroWidthSpace {
+                self.after_zero_width_space = true;
+            } else if class != Space {
+                self.after_zero_width_space = false;
+            }
+
+            if let Some(opportunity) = opportunity {
+                return Some((index, opportunity));
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns `true` for opening quotation marks, in the Initial Punctuation
+/// (`Pi`) category.
+fn is_initial_quote(chr: char) -> bool {
+    matches!(chr, '«' | '‘' | '‛' | '“' | '‟' | '‹')
+}
+
+/// Returns `true` for closing quotation marks, in the Final Punctuation
+/// (`Pf`) category.
+fn is_final_quote(chr: char) -> bool {
+    matches!(chr, '»' | '’' | '”' | '›')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BreakOpportunity, LineBreakClass, LineBreaks};
+
+    /// Splits text at every break opportunity, marking mandatory breaks.
+    fn split(text: &str) -> Vec<String> {
+        let mut start = 0;
+
+        LineBreaks::new(text)
+            .map(|(index, opportunity)| {
+                let segment = &text[start..index];
+
+                start = index;
+
+                match opportunity {
+                    BreakOpportunity::Mandatory => format!("{}!", segment.trim_end()),
+                    BreakOpportunity::Allowed => segment.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_classes() {
+        assert_eq!(LineBreakClass::of('中'), LineBreakClass::Ideographic);
+        assert_eq!(LineBreakClass::of('あ'), LineBreakClass::Ideographic);
+        assert_eq!(LineBreakClass::of('ゃ'), LineBreakClass::Nonstarter);
+        assert_eq!(LineBreakClass::of('ー'), LineBreakClass::Nonstarter);
+        assert_eq!(LineBreakClass::of('。'), LineBreakClass::ClosePunctuation);
+        assert_eq!(LineBreakClass::of('「'), LineBreakClass::OpenPunctuation);
+        assert_eq!(LineBreakClass::of('（'), LineBreakClass::OpenPunctuation);
+        assert_eq!(LineBreakClass::of('？'), LineBreakClass::Exclamation);
+        assert_eq!(LineBreakClass::of('한'), LineBreakClass::HangulLvt);
+        assert_eq!(LineBreakClass::of('하'), LineBreakClass::HangulLv);
+        assert_eq!(LineBreakClass::of('a'), LineBreakClass::Alphabetic);
+        assert_eq!(LineBreakClass::of('\u{3000}'), LineBreakClass::BreakAfter);
+    }
+
+    #[test]
+    fn test_latin() {
+        assert_eq!(
+            split("Hello, world! (It's 100%.)"),
+            ["Hello, ", "world! ", "(It's ", "100%.)!"]
+        );
+        assert_eq!(split("e-mail a/b"), ["e-", "mail ", "a/", "b!"]);
+    }
+
+    #[test]
+    fn test_cjk() {
+        assert_eq!(split("中文。"), ["中", "文。!"]);
+        assert_eq!(split("「日本」です"), ["「日", "本」", "で", "す!"]);
+        assert_eq!(split("ニャー"), ["ニャー!"]);
+        assert_eq!(split("한국어"), ["한", "국", "어!"]);
+
+        // Latin words, and numbers in CJK text
+        assert_eq!(split("用Rust写"), ["用", "Rust", "写!"]);
+        assert_eq!(split("￥100です"), ["￥100", "で", "す!"]);
+
+        // Quotation marks can be broken around next to CJK characters
+        assert_eq!(split("说“好”了"), ["说", "“好”", "了!"]);
+        assert_eq!(split("say \"hi\" now"), ["say ", "\"hi\" ", "now!"]);
+    }
+
+    #[test]
+    fn test_mandatory_breaks() {
+        assert_eq!(split("a\nb\r\n\nc"), ["a!", "b!", "!", "c!"]);
+        assert_eq!(split("a\n"), ["a!"]);
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn test_glue() {
+        assert_eq!(split("a\u{A0}b c"), ["a\u{A0}b ", "c!"]);
+        assert_eq!(split("a\u{200B}b"), ["a\u{200B}", "b!"]);
+        assert_eq!(split("中\u{2060}文"), ["中\u{2060}文!"]);
+    }
+
+    #[test]
+    fn test_combining_marks() {
+        // `が` written with a combining voiced sound mark
+        assert_eq!(split("か\u{3099}き"), ["か\u{3099}", "き!"]);
+    }
+}
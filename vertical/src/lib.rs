@@ -0,0 +1,525 @@
+#![no_std]
+
+#[cfg(all(feature = "embedded-graphics-07", feature = "embedded-graphics-08"))]
+compile_error!(
+    "Only one of the `embedded-graphics-07`, and `embedded-graphics-08` features can be enabled"
+);
+
+#[cfg(not(any(feature = "embedded-graphics-07", feature = "embedded-graphics-08")))]
+compile_error!(
+    "Either the `embedded-graphics-07`, or `embedded-graphics-08` feature has to be enabled"
+);
+
+#[cfg(all(
+    feature = "embedded-graphics-07",
+    not(feature = "embedded-graphics-08")
+))]
+extern crate embedded_graphics_07 as embedded_graphics;
+
+mod rotated;
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Point,
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::PixelColor,
+    text::{renderer::CharacterStyle, Baseline, Text},
+    Drawable,
+};
+use embedded_graphics_cjk_glyph_mapping::TryGlyphMapping;
+use rotated::Rotated;
+use unicode_width::UnicodeWidthChar;
+
+/// A font with the vertical forms of punctuation, brackets, and the long
+/// vowel mark, and the glyph mapping used to check which characters it
+/// covers.
+///
+/// `ttf2bits generate --vertical` (or `vertical = true` in a `fonts.toml`)
+/// generates these from a font's `vert`, and `vrt2` features, as a
+/// `{FONT}_VERTICAL` font, and a `{FONT}_VERTICAL_GLYPH_MAPPING` constant.
+#[derive(Copy, Clone)]
+pub struct VerticalAlternates<'a> {
+    /// Font with the vertical forms.
+    pub font: &'a MonoFont<'a>,
+
+    /// Characters covered by the font.
+    pub glyph_mapping: &'a dyn TryGlyphMapping,
+}
+
+impl<'a> VerticalAlternates<'a> {
+    /// Creates vertical alternates that are used for the characters covered
+    /// by the glyph mapping.
+    pub const fn new(font: &'a MonoFont<'a>, glyph_mapping: &'a dyn TryGlyphMapping) -> Self {
+        VerticalAlternates {
+            font,
+            glyph_mapping,
+        }
+    }
+}
+
+/// Style of vertical text.
+///
+/// Full-width characters are drawn upright, centered in the column, with
+/// their vertical alternate if there is one. Runs of half-width characters,
+/// like Latin words, and numbers, are rotated 90° clockwise.
+#[derive(Copy, Clone)]
+pub struct VerticalTextStyle<'a, C> {
+    /// Text color.
+    pub text_color: Option<C>,
+
+    /// Background color.
+    pub background_color: Option<C>,
+
+    /// Font used for runs of characters that aren't full-width.
+    pub half_width_font: &'a MonoFont<'a>,
+
+    /// Font used for full-width characters.
+    pub full_width_font: &'a MonoFont<'a>,
+
+    /// Vertical forms of the full-width font.
+    pub vertical_alternates: Option<VerticalAlternates<'a>>,
+
+    /// Space between columns, in pixels.
+    pub column_spacing: u32,
+}
+
+impl<'a, C> VerticalTextStyle<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates a text style with a transparent background, and without
+    /// vertical alternates.
+    pub fn new(
+        half_width_font: &'a MonoFont<'a>,
+        full_width_font: &'a MonoFont<'a>,
+        text_color: C,
+    ) -> Self {
+        VerticalTextStyle {
+            text_color: Some(text_color),
+            background_color: None,
+            half_width_font,
+            full_width_font,
+            vertical_alternates: None,
+            column_spacing: 0,
+        }
+    }
+
+    /// Returns the font that draws a character upright, or `None` if the
+    /// character is half-width, and is rotated.
+    pub fn upright_font(&self, chr: char) -> Option<&'a MonoFont<'a>> {
+        if let Some(alternates) = self
+            .vertical_alternates
+            .filter(|alternates| alternates.glyph_mapping.contains(chr))
+        {
+            Some(alternates.font)
+        } else if chr.width() == Some(2) {
+            Some(self.full_width_font)
+        } else {
+            None
+        }
+    }
+
+    /// Width of a column, which fits a full-width character, and the height
+    /// of a rotated half-width character.
+    pub fn column_width(&self) -> u32 {
+        self.full_width_font
+            .character_size
+            .width
+            .max(self.half_width_font.character_size.height)
+    }
+
+    fn font_style(&self, font: &'a MonoFont<'a>) -> MonoTextStyle<'a, C> {
+        let mut style = MonoTextStyleBuilder::new().font(font).build();
+
+        style.set_text_color(self.text_color);
+        style.set_background_color(self.background_color);
+
+        style
+    }
+}
+
+/// Text drawn in columns, from top to bottom, with the columns running from
+/// right to left.
+///
+/// A line break starts a new column. If the text has a height, characters
+/// that don't fit in a column continue at the top of the next one.
+///
+/// ```
+/// # use embedded_graphics_cjk_glyph_mapping::embedded_graphics;
+/// use embedded_graphics::{
+///     geometry::Point, mock_display::MockDisplay, mono_font::ascii::FONT_6X10,
+///     pixelcolor::BinaryColor, Drawable,
+/// };
+/// use embedded_graphics_cjk_font_zpix::ZPIX_12;
+/// use embedded_graphics_cjk_vertical::{VerticalText, VerticalTextStyle};
+///
+/// let style = VerticalTextStyle::new(&FONT_6X10, &ZPIX_12, BinaryColor::On);
+/// let mut display = MockDisplay::new();
+///
+/// // Two columns, the first of which ends in a rotated `OK`
+/// let next = VerticalText::new("中文OK\n文", Point::new(63, 0), style)
+///     .draw(&mut display)
+///     .unwrap();
+///
+/// // `ZPIX_12` has 13x15 cells
+/// assert_eq!(style.column_width(), 13);
+/// assert_eq!(next, Point::new(63 - 13, 15));
+/// ```
+#[derive(Copy, Clone)]
+pub struct VerticalText<'a, C> {
+    /// Text.
+    pub text: &'a str,
+
+    /// Top right corner of the first column.
+    pub position: Point,
+
+    /// Text style.
+    pub style: VerticalTextStyle<'a, C>,
+
+    /// Height of each column, or `None` to only start new columns at line
+    /// breaks.
+    pub height: Option<u32>,
+}
+
+/// Position of the next character.
+struct Cursor {
+    /// Top right corner of the column.
+    column: Point,
+
+    /// Distance from the top of the column.
+    offset: u32,
+}
+
+impl<'a, C> VerticalText<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates vertical text, with columns that only end at line breaks.
+    pub fn new(text: &'a str, position: Point, style: VerticalTextStyle<'a, C>) -> Self {
+        VerticalText {
+            text,
+            position,
+            style,
+            height: None,
+        }
+    }
+
+    /// Creates vertical text, with columns that are at most `height` pixels
+    /// high.
+    pub fn with_height(
+        text: &'a str,
+        position: Point,
+        style: VerticalTextStyle<'a, C>,
+        height: u32,
+    ) -> Self {
+        VerticalText {
+            text,
+            position,
+            style,
+            height: Some(height),
+        }
+    }
+
+    fn next_column(&self, cursor: &mut Cursor) {
+        let advance = self.style.column_width() + self.style.column_spacing;
+
+        cursor.column.x -= advance as i32;
+        cursor.offset = 0;
+    }
+
+    /// Number of pixels left in the current column.
+    fn remaining(&self, cursor: &Cursor) -> u32 {
+        self.height
+            .map_or(u32::MAX, |height| height.saturating_sub(cursor.offset))
+    }
+
+    fn draw_upright<D>(
+        &self,
+        chr: char,
+        font: &'a MonoFont<'a>,
+        cursor: &mut Cursor,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let height = font.character_size.height;
+
+        if cursor.offset > 0 && self.remaining(cursor) < height {
+            self.next_column(cursor);
+        }
+
+        let column_width = self.style.column_width();
+        let left = cursor.column.x + 1 - column_width as i32;
+        let padding = (column_width - font.character_size.width) / 2;
+        let position = Point::new(
+            left + padding as i32,
+            cursor.column.y + cursor.offset as i32,
+        );
+        let mut buffer = [0; 4];
+
+        Text::with_baseline(
+            chr.encode_utf8(&mut buffer),
+            position,
+            self.style.font_style(font),
+            Baseline::Top,
+        )
+        .draw(target)?;
+
+        cursor.offset += height;
+
+        Ok(())
+    }
+
+    /// Draws a run of half-width characters rotated, splitting it across
+    /// columns if it doesn't fit.
+    fn draw_rotated<D>(
+        &self,
+        run: &str,
+        cursor: &mut Cursor,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let font = self.style.half_width_font;
+        let advance = font.character_size.width + font.character_spacing;
+        let padding = (self.style.column_width() - font.character_size.height) / 2;
+        let mut run = run;
+
+        while !run.is_empty() {
+            // The last character doesn't need the spacing after it
+            let fits = self
+                .remaining(cursor)
+                .saturating_add(font.character_spacing)
+                / advance;
+
+            if fits == 0 && cursor.offset > 0 {
+                self.next_column(cursor);
+                continue;
+            }
+
+            let end = run
+                .char_indices()
+                .nth(fits.max(1) as usize)
+                .map_or(run.len(), |(index, _)| index);
+            let (part, rest) = run.split_at(end);
+            let origin = Point::new(
+                cursor.column.x - padding as i32,
+                cursor.column.y + cursor.offset as i32,
+            );
+
+            Text::with_baseline(
+                part,
+                Point::zero(),
+                self.style.font_style(font),
+                Baseline::Top,
+            )
+            .draw(&mut Rotated::new(target, origin))?;
+
+            cursor.offset += part.chars().count() as u32 * advance;
+            run = rest;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C> Drawable for VerticalText<'_, C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+
+    /// Position of the next character, at the right of its column.
+    type Output = Point;
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let mut cursor = Cursor {
+            column: self.position,
+            offset: 0,
+        };
+
+        for (index, line) in self.text.split('\n').enumerate() {
+            if index > 0 {
+                self.next_column(&mut cursor);
+            }
+
+            let mut run_start = None;
+
+            for (position, chr) in line.char_indices() {
+                let Some(font) = self.style.upright_font(chr) else {
+                    run_start.get_or_insert(position);
+                    continue;
+                };
+
+                if let Some(start) = run_start.take() {
+                    self.draw_rotated(&line[start..position], &mut cursor, target)?;
+                }
+
+                self.draw_upright(chr, font, &mut cursor, target)?;
+            }
+
+            if let Some(start) = run_start {
+                self.draw_rotated(&line[start..], &mut cursor, target)?;
+            }
+        }
+
+        Ok(cursor.column + Point::new(0, cursor.offset as i32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VerticalAlternates, VerticalText, VerticalTextStyle};
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        mock_display::MockDisplay,
+        mono_font::{mapping::StrGlyphMapping, DecorationDimensions, MonoFont},
+        pixelcolor::BinaryColor,
+        Drawable,
+    };
+    use embedded_graphics_cjk_glyph_mapping::{binary_image, SparseGlyphMapping};
+
+    /// A full-width font with a single, filled 4x4 glyph.
+    const FULL_WIDTH: MonoFont = MonoFont {
+        image: binary_image(&[0xF0; 4], 4),
+        glyph_mapping: &StrGlyphMapping::new("中", 0),
+        character_size: Size::new(4, 4),
+        character_spacing: 0,
+        baseline: 3,
+        underline: DecorationDimensions::new(3, 1),
+        strikethrough: DecorationDimensions::new(2, 1),
+    };
+
+    /// A half-width font with a 3x2 glyph for `a`, that looks like `L`.
+    const HALF_WIDTH: MonoFont = MonoFont {
+        image: binary_image(&[0b1000_0000, 0b1110_0000], 3),
+        glyph_mapping: &StrGlyphMapping::new("a", 0),
+        character_size: Size::new(3, 2),
+        character_spacing: 0,
+        baseline: 1,
+        underline: DecorationDimensions::new(1, 1),
+        strikethrough: DecorationDimensions::new(1, 1),
+    };
+
+    /// Vertical alternates with a 4x4 glyph for `。`, with a single pixel in
+    /// the top left corner.
+    const VERTICAL: MonoFont = MonoFont {
+        image: binary_image(&[0x80, 0, 0, 0], 4),
+        glyph_mapping: &StrGlyphMapping::new("。", 0),
+        ..FULL_WIDTH
+    };
+
+    const VERTICAL_MAPPING: SparseGlyphMapping = SparseGlyphMapping::new(&['。'], 0);
+
+    const ALTERNATES: VerticalAlternates = VerticalAlternates::new(&VERTICAL, &VERTICAL_MAPPING);
+
+    #[test]
+    fn test_upright_font() {
+        let mut style = VerticalTextStyle::new(&HALF_WIDTH, &FULL_WIDTH, BinaryColor::On);
+
+        assert!(core::ptr::eq(
+            style.upright_font('中').unwrap(),
+            &FULL_WIDTH
+        ));
+        assert!(core::ptr::eq(
+            style.upright_font('。').unwrap(),
+            &FULL_WIDTH
+        ));
+        assert!(style.upright_font('a').is_none());
+        assert!(style.upright_font('ｱ').is_none());
+
+        style.vertical_alternates = Some(ALTERNATES);
+
+        assert!(core::ptr::eq(style.upright_font('。').unwrap(), &VERTICAL));
+        assert!(core::ptr::eq(
+            style.upright_font('中').unwrap(),
+            &FULL_WIDTH
+        ));
+    }
+
+    #[test]
+    fn test_draw_column() {
+        let mut style = VerticalTextStyle::new(&HALF_WIDTH, &FULL_WIDTH, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        style.vertical_alternates = Some(ALTERNATES);
+
+        let next = VerticalText::new("中aa。", Point::new(3, 0), style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(3, 4 + 3 + 3 + 4));
+
+        // The rotated `a`s are centered in the column, with their tops facing
+        // right.
+        display.assert_pattern(&[
+            "####", //
+            "####", "####", "####", " ## ", " #  ", " #  ", " ## ", " #  ", " #  ", "#   ",
+        ]);
+    }
+
+    #[test]
+    fn test_line_break() {
+        let style = VerticalTextStyle::new(&HALF_WIDTH, &FULL_WIDTH, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        let next = VerticalText::new("中中\n中", Point::new(7, 0), style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(3, 4));
+
+        display.assert_pattern(&[
+            "########", //
+            "########", "########", "########", "    ####", "    ####", "    ####", "    ####",
+        ]);
+    }
+
+    #[test]
+    fn test_wrap_columns() {
+        let mut style = VerticalTextStyle::new(&HALF_WIDTH, &FULL_WIDTH, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        style.column_spacing = 1;
+
+        let next = VerticalText::with_height("中中中", Point::new(8, 0), style, 10)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(3, 4));
+
+        display.assert_pattern(&[
+            "#### ####", //
+            "#### ####",
+            "#### ####",
+            "#### ####",
+            "     ####",
+            "     ####",
+            "     ####",
+            "     ####",
+        ]);
+    }
+
+    #[test]
+    fn test_wrap_rotated_run() {
+        let style = VerticalTextStyle::new(&HALF_WIDTH, &FULL_WIDTH, BinaryColor::On);
+        let mut display = MockDisplay::new();
+
+        // Only two `a`s fit in a column
+        let next = VerticalText::with_height("aaa", Point::new(7, 0), style, 7)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(next, Point::new(3, 3));
+
+        display.assert_pattern(&[
+            " ##  ##", //
+            " #   # ", " #   # ", "     ##", "     # ", "     # ",
+        ]);
+    }
+}
@@ -0,0 +1,153 @@
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Draw target that rotates everything drawn to it by 90° clockwise, so text
+/// drawn left to right runs from top to bottom.
+///
+/// The origin of the rotated coordinates is at `origin` on the target. The
+/// top of text drawn at the origin faces right, with its first row in the
+/// target's column at `origin.x`.
+pub(crate) struct Rotated<'a, D> {
+    target: &'a mut D,
+    origin: Point,
+}
+
+impl<'a, D> Rotated<'a, D> {
+    pub(crate) fn new(target: &'a mut D, origin: Point) -> Self {
+        Rotated { target, origin }
+    }
+}
+
+impl<D> Dimensions for Rotated<'_, D>
+where
+    D: DrawTarget,
+{
+    fn bounding_box(&self) -> Rectangle {
+        let bounding_box = self.target.bounding_box();
+
+        match bounding_box.bottom_right() {
+            Some(bottom_right) => Rectangle::with_corners(
+                from_target(self.origin, bounding_box.top_left),
+                from_target(self.origin, bottom_right),
+            ),
+            None => Rectangle::new(
+                from_target(self.origin, bounding_box.top_left),
+                Size::zero(),
+            ),
+        }
+    }
+}
+
+impl<D> DrawTarget for Rotated<'_, D>
+where
+    D: DrawTarget,
+{
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let origin = self.origin;
+
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(to_target(origin, point), color)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        let area = Rectangle::with_corners(
+            to_target(self.origin, area.top_left),
+            to_target(self.origin, bottom_right),
+        );
+
+        self.target.fill_solid(&area, color)
+    }
+}
+
+/// Converts a point in rotated coordinates to a point on the target.
+fn to_target(origin: Point, point: Point) -> Point {
+    Point::new(origin.x - point.y, origin.y + point.x)
+}
+
+/// Converts a point on the target to a point in rotated coordinates.
+fn from_target(origin: Point, point: Point) -> Point {
+    Point::new(point.y - origin.y, origin.x - point.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rotated;
+    use embedded_graphics::{
+        draw_target::DrawTarget,
+        geometry::{Dimensions, Point, Size},
+        mock_display::MockDisplay,
+        pixelcolor::BinaryColor,
+        primitives::Rectangle,
+        Pixel,
+    };
+
+    #[test]
+    fn test_rotate_pixels() {
+        let mut display = MockDisplay::new();
+        let mut rotated = Rotated::new(&mut display, Point::new(3, 1));
+
+        // A horizontal line, with a pixel below its start
+        rotated
+            .draw_iter(
+                [
+                    Point::new(0, 0),
+                    Point::new(1, 0),
+                    Point::new(2, 0),
+                    Point::new(0, 1),
+                ]
+                .map(|point| Pixel(point, BinaryColor::On)),
+            )
+            .unwrap();
+
+        display.assert_pattern(&[
+            "    ", //
+            "  ##", "   #", "   #",
+        ]);
+    }
+
+    #[test]
+    fn test_fill_solid() {
+        let mut display = MockDisplay::new();
+        let mut rotated = Rotated::new(&mut display, Point::new(3, 0));
+
+        rotated
+            .fill_solid(
+                &Rectangle::new(Point::new(1, 0), Size::new(3, 2)),
+                BinaryColor::On,
+            )
+            .unwrap();
+
+        display.assert_pattern(&[
+            "    ", //
+            "  ##", "  ##", "  ##",
+        ]);
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut display = MockDisplay::<BinaryColor>::new();
+        let rotated = Rotated::new(&mut display, Point::new(63, 0));
+
+        assert_eq!(
+            rotated.bounding_box(),
+            Rectangle::new(Point::zero(), Size::new(64, 64))
+        );
+    }
+}